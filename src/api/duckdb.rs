@@ -28,6 +28,18 @@ pub fn duckdb_execute(query: &str) {
     connection::execute(query, []).unwrap_or_else(|err| panic!("error executing query: {err:?}"));
 }
 
+#[pg_extern]
+pub fn reset_connection() {
+    connection::reset_connection()
+        .unwrap_or_else(|err| panic!("error resetting connection: {err:?}"));
+}
+
+#[pg_extern]
+pub fn load_extension(extension_name: &str, repository: default!(Option<&str>, "NULL")) {
+    connection::load_allowed_extension(extension_name, repository)
+        .unwrap_or_else(|err| panic!("error loading extension: {err:?}"));
+}
+
 #[allow(clippy::type_complexity)]
 #[pg_extern]
 pub fn duckdb_settings() -> iter::TableIterator<
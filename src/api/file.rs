@@ -0,0 +1,146 @@
+// Copyright (c) 2023-2024 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{bail, Result};
+use pgrx::*;
+
+use crate::duckdb::connection;
+
+type SummarizeRow = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<i64>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<i64>,
+    Option<f64>,
+);
+
+#[pg_extern]
+pub fn preview(path: &str, n: default!(i32, 10)) -> iter::SetOfIterator<'static, JsonB> {
+    let rows = preview_impl(path, n).unwrap_or_else(|e| {
+        panic!("{}", e);
+    });
+    iter::SetOfIterator::new(rows)
+}
+
+#[inline]
+fn preview_impl(path: &str, n: i32) -> Result<Vec<JsonB>> {
+    if n < 0 {
+        bail!("n must not be negative, got {n}");
+    }
+
+    let reader = file_reader(path)?;
+    let path = path.replace('\'', "''");
+
+    let conn = unsafe { &*connection::get_global_connection().get() };
+    let query =
+        format!("SELECT to_json(t)::VARCHAR FROM (SELECT * FROM {reader}('{path}') LIMIT {n}) t");
+    let mut stmt = conn.prepare(&query)?;
+
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+
+    rows.iter()
+        .map(|row| Ok(JsonB(serde_json::from_str(row)?)))
+        .collect()
+}
+
+#[allow(clippy::type_complexity)]
+#[pg_extern]
+pub fn describe(
+    path: &str,
+) -> iter::TableIterator<(
+    name!(column_name, Option<String>),
+    name!(column_type, Option<String>),
+    name!(min, Option<String>),
+    name!(max, Option<String>),
+    name!(approx_unique, Option<i64>),
+    name!(avg, Option<String>),
+    name!(std, Option<String>),
+    name!(q25, Option<String>),
+    name!(q50, Option<String>),
+    name!(q75, Option<String>),
+    name!(count, Option<i64>),
+    name!(null_percentage, Option<f64>),
+)> {
+    let rows = describe_impl(path).unwrap_or_else(|e| {
+        panic!("{}", e);
+    });
+    iter::TableIterator::new(rows)
+}
+
+#[inline]
+fn describe_impl(path: &str) -> Result<Vec<SummarizeRow>> {
+    let reader = file_reader(path)?;
+    let path = path.replace('\'', "''");
+
+    let conn = unsafe { &*connection::get_global_connection().get() };
+    let query = format!(
+        "SELECT column_name, column_type, min, max, approx_unique::BIGINT, avg, std, q25, q50, q75, count::BIGINT, null_percentage::DOUBLE FROM (SUMMARIZE SELECT * FROM {reader}('{path}'))"
+    );
+    let mut stmt = conn.prepare(&query)?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<i64>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+                row.get::<_, Option<String>>(7)?,
+                row.get::<_, Option<String>>(8)?,
+                row.get::<_, Option<String>>(9)?,
+                row.get::<_, Option<i64>>(10)?,
+                row.get::<_, Option<f64>>(11)?,
+            ))
+        })?
+        .collect::<Result<Vec<SummarizeRow>, _>>()?;
+
+    Ok(rows)
+}
+
+// Picks the DuckDB table function that reads a file from its extension, ignoring any
+// compression suffix, e.g. data.csv.gz is read as CSV
+fn file_reader(path: &str) -> Result<&'static str> {
+    let lowercase_path = path.to_lowercase();
+    let uncompressed_path = [".gz", ".zst"]
+        .iter()
+        .find_map(|suffix| lowercase_path.strip_suffix(suffix))
+        .unwrap_or(&lowercase_path);
+
+    match uncompressed_path
+        .rsplit_once('.')
+        .map(|(_, extension)| extension)
+    {
+        Some("parquet") => Ok("read_parquet"),
+        Some("csv") | Some("tsv") => Ok("read_csv"),
+        Some("json") | Some("jsonl") | Some("ndjson") => Ok("read_json"),
+        _ => {
+            bail!("cannot detect the format of {path}, supported extensions are .parquet, .csv, .tsv, .json, .jsonl and .ndjson")
+        }
+    }
+}
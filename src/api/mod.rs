@@ -17,4 +17,6 @@
 
 mod csv;
 mod duckdb;
+mod file;
 mod parquet;
+mod table;
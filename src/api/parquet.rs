@@ -15,11 +15,12 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use pgrx::*;
 
 use crate::duckdb::connection;
 use crate::duckdb::utils;
+use crate::schema::cell::*;
 
 type ParquetSchemaRow = (
     Option<String>,
@@ -85,6 +86,13 @@ pub fn parquet_schema(
     iter::TableIterator::new(rows)
 }
 
+#[pg_extern]
+pub fn load_parquet(files: &str, target: pg_sys::Oid) -> i64 {
+    load_parquet_impl(files, target).unwrap_or_else(|e| {
+        panic!("{}", e);
+    })
+}
+
 #[inline]
 fn parquet_schema_impl(files: &str) -> Result<Vec<ParquetSchemaRow>> {
     let schema_str = utils::format_csv(files);
@@ -133,3 +141,272 @@ fn parquet_describe_impl(files: &str) -> Result<Vec<ParquetDescribeRow>> {
         .map(|row| row.unwrap())
         .collect::<Vec<ParquetDescribeRow>>())
 }
+
+#[inline]
+fn load_parquet_impl(files: &str, target: pg_sys::Oid) -> Result<i64> {
+    // Every column is written, so inserting into each of them has to be allowed
+    unsafe {
+        let user = pg_sys::GetUserId();
+        let mode = pg_sys::ACL_INSERT as pg_sys::AclMode;
+        if pg_sys::pg_class_aclcheck(target, user, mode) != pg_sys::AclResult::ACLCHECK_OK
+            && pg_sys::pg_attribute_aclcheck_all(
+                target,
+                user,
+                mode,
+                pg_sys::AclMaskHow::ACLMASK_ALL,
+            ) != pg_sys::AclResult::ACLCHECK_OK
+        {
+            bail!("permission denied for relation with oid {}", target);
+        }
+    }
+
+    let relation = unsafe { PgRelation::with_lock(target, pg_sys::RowExclusiveLock as i32) };
+    let rel = relation.as_ptr();
+
+    // Tuples are written straight to the heap, bypassing the executor, so
+    // anything the executor would normally maintain on insert is rejected.
+    unsafe {
+        if (*(*rel).rd_rel).relkind != pg_sys::RELKIND_RELATION as std::os::raw::c_char
+            || (*(*rel).rd_rel).relam != pg_sys::HEAP_TABLE_AM_OID
+        {
+            bail!("{} is not a heap table", relation.name());
+        }
+        if (*(*rel).rd_rel).relhasindex {
+            bail!(
+                "{} has indexes, which are not supported by load_parquet. Create the indexes after loading",
+                relation.name()
+            );
+        }
+        if !(*rel).trigdesc.is_null() {
+            bail!(
+                "{} has triggers, which are not supported by load_parquet",
+                relation.name()
+            );
+        }
+        let constr = (*(*rel).rd_att).constr;
+        if !constr.is_null() && (*constr).num_check > 0 {
+            bail!(
+                "{} has check constraints, which are not supported by load_parquet",
+                relation.name()
+            );
+        }
+        if (*(*rel).rd_rel).relrowsecurity {
+            bail!(
+                "{} has row-level security enabled, which is not supported by load_parquet",
+                relation.name()
+            );
+        }
+    }
+
+    let tuple_desc = relation.tuple_desc();
+    let attributes = tuple_desc
+        .iter()
+        .enumerate()
+        .filter(|(_, attribute)| !attribute.is_dropped())
+        .collect::<Vec<_>>();
+
+    for (_, attribute) in attributes.iter() {
+        if attribute.attgenerated != 0 {
+            bail!(
+                "column \"{}\" of {} is generated, which is not supported by load_parquet",
+                attribute.name(),
+                relation.name()
+            );
+        }
+        if attribute.attidentity != 0 {
+            bail!(
+                "column \"{}\" of {} is an identity column, which is not supported by load_parquet",
+                attribute.name(),
+                relation.name()
+            );
+        }
+    }
+
+    let mut coercions = attributes
+        .iter()
+        .map(|(_, attribute)| unsafe { ColumnCoercion::resolve(attribute, &relation) })
+        .collect::<Result<Vec<_>>>()?;
+
+    let targets = attributes
+        .iter()
+        .map(|(_, attribute)| format!("\"{}\"", attribute.name().replace('"', "\"\"")))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let schema_str = utils::format_csv(files);
+    connection::create_arrow(format!("SELECT {targets} FROM read_parquet({schema_str})").as_str())?;
+
+    let mut rows_loaded: i64 = 0;
+    let mut batch_context = PgMemoryContexts::new("load_parquet");
+
+    unsafe {
+        let command_id = pg_sys::GetCurrentCommandId(true);
+        let bulk_insert_state = pg_sys::GetBulkInsertState();
+
+        while let Some(batch) = connection::get_next_batch()? {
+            if rows_loaded == 0 {
+                for (column_index, (_, attribute)) in attributes.iter().enumerate() {
+                    warn_decimal_precision_loss(
+                        batch.column(column_index).data_type(),
+                        coercions[column_index].base_type,
+                        attribute.name(),
+                    );
+                }
+            }
+
+            let cell_readers = attributes
+                .iter()
+                .enumerate()
+                .map(|(column_index, _)| {
+                    CellReader::resolve(
+                        batch.column(column_index).data_type(),
+                        coercions[column_index].base_type,
+                        coercions[column_index].base_typmod,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            batch_context.switch_to(|_| -> Result<()> {
+                let mut slots = Vec::with_capacity(batch.num_rows());
+
+                for row_index in 0..batch.num_rows() {
+                    let slot =
+                        pg_sys::MakeTupleTableSlot(tuple_desc.as_ptr(), &pg_sys::TTSOpsVirtual);
+
+                    for attnum in 0..tuple_desc.len() {
+                        *(*slot).tts_values.add(attnum) = pg_sys::Datum::from(0);
+                        *(*slot).tts_isnull.add(attnum) = true;
+                    }
+
+                    for (column_index, (attnum, attribute)) in attributes.iter().enumerate() {
+                        let column = batch.column(column_index);
+                        let cell = cell_readers[column_index].read(
+                            column,
+                            row_index,
+                            coercions[column_index].base_type,
+                            attribute.name(),
+                        )?;
+                        let datum = coercions[column_index]
+                            .coerce(cell.and_then(|cell| cell.into_datum()));
+
+                        match datum {
+                            Some(datum) => {
+                                *(*slot).tts_values.add(*attnum) = datum;
+                                *(*slot).tts_isnull.add(*attnum) = false;
+                            }
+                            None if attribute.attnotnull => {
+                                bail!(
+                                    "null value in column \"{}\" of relation \"{}\" violates not-null constraint",
+                                    attribute.name(),
+                                    relation.name()
+                                );
+                            }
+                            None => {}
+                        }
+                    }
+
+                    pg_sys::ExecStoreVirtualTuple(slot);
+                    slots.push(slot);
+                }
+
+                pg_sys::heap_multi_insert(
+                    rel,
+                    slots.as_mut_ptr(),
+                    slots.len() as i32,
+                    command_id,
+                    0,
+                    bulk_insert_state,
+                );
+
+                for slot in slots {
+                    pg_sys::ExecDropSingleTupleTableSlot(slot);
+                }
+
+                Ok(())
+            })
+            .map_err(|err| {
+                connection::clear_arrow();
+                err
+            })?;
+
+            rows_loaded += batch.num_rows() as i64;
+            batch_context.reset();
+        }
+
+        pg_sys::FreeBulkInsertState(bulk_insert_state);
+    }
+
+    connection::clear_arrow();
+
+    Ok(rows_loaded)
+}
+
+// Tuples written straight to the heap skip the coercions the executor applies on insert, so
+// each value is brought to the column's typmod and checked against its domain here
+struct ColumnCoercion {
+    base_type: pg_sys::Oid,
+    base_typmod: i32,
+    typmod_function: Option<pg_sys::Oid>,
+    domain: Option<pg_sys::Oid>,
+    domain_extra: *mut std::ffi::c_void,
+    domain_context: pg_sys::MemoryContext,
+}
+
+impl ColumnCoercion {
+    unsafe fn resolve(
+        attribute: &pg_sys::FormData_pg_attribute,
+        relation: &PgRelation,
+    ) -> Result<Self> {
+        let mut base_typmod = attribute.atttypmod;
+        let base_type = pg_sys::getBaseTypeAndTypmod(attribute.atttypid, &mut base_typmod);
+
+        let mut typmod_function = None;
+        if base_typmod >= 0 {
+            let mut function = pg_sys::InvalidOid;
+            let path = pg_sys::find_typmod_coercion_function(base_type, &mut function);
+            if path == pg_sys::CoercionPathType::COERCION_PATH_FUNC {
+                typmod_function = Some(function);
+            } else if path != pg_sys::CoercionPathType::COERCION_PATH_NONE {
+                bail!(
+                    "column \"{}\" of {} has a type modifier on an array type, which is not supported by load_parquet",
+                    attribute.name(),
+                    relation.name()
+                );
+            }
+        }
+
+        Ok(Self {
+            base_type,
+            base_typmod,
+            typmod_function,
+            domain: (base_type != attribute.atttypid).then_some(attribute.atttypid),
+            domain_extra: std::ptr::null_mut(),
+            domain_context: pg_sys::CurrentMemoryContext,
+        })
+    }
+
+    unsafe fn coerce(&mut self, datum: Option<pg_sys::Datum>) -> Option<pg_sys::Datum> {
+        let datum = match (datum, self.typmod_function) {
+            (Some(datum), Some(function)) => Some(pg_sys::OidFunctionCall3Coll(
+                function,
+                pg_sys::InvalidOid,
+                datum,
+                pg_sys::Datum::from(self.base_typmod),
+                pg_sys::Datum::from(false),
+            )),
+            (datum, _) => datum,
+        };
+
+        if let Some(domain) = self.domain {
+            pg_sys::domain_check(
+                datum.unwrap_or(pg_sys::Datum::from(0)),
+                datum.is_none(),
+                domain,
+                &mut self.domain_extra,
+                self.domain_context,
+            );
+        }
+
+        datum
+    }
+}
@@ -0,0 +1,442 @@
+// Copyright (c) 2023-2024 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, bail, Result};
+use duckdb::arrow::array::new_null_array;
+use pgrx::*;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::ffi::{CStr, CString};
+use std::ptr::null_mut;
+use supabase_wrappers::prelude::options_to_hashmap;
+
+use crate::duckdb::error::DuckdbOptionError;
+use crate::duckdb::{connection, parquet, utils};
+use crate::fdw::base::{lowercase_option_keys, register_foreign_table_view};
+use crate::fdw::handler::FdwHandler;
+use crate::hooks::query::{get_query_relations, set_search_path_by_pg};
+use crate::schema::cell::CellReader;
+
+type SchemaDriftRow = (String, String, Option<String>, String);
+
+#[pg_extern]
+pub fn table_checksum(foreign_table: pg_sys::Oid) -> String {
+    table_checksum_impl(foreign_table).unwrap_or_else(|e| {
+        panic!("{}", e);
+    })
+}
+
+#[inline]
+fn table_checksum_impl(foreign_table: pg_sys::Oid) -> Result<String> {
+    let pg_relation = open_foreign_table(foreign_table)?;
+    let schema_name = pg_relation.namespace();
+    let table_name = pg_relation.name();
+
+    // Summing the row hashes makes the checksum independent of the order rows are read in,
+    // while the row count guards against sums that happen to collide
+    let conn = unsafe { &*connection::get_global_connection().get() };
+    let query = format!(
+        "SELECT md5(COUNT(*)::VARCHAR || ':' || COALESCE(SUM(hash(t))::VARCHAR, '')) FROM {schema_name}.{table_name} t"
+    );
+    let mut stmt = conn.prepare(&query)?;
+
+    Ok(stmt.query_row([], |row| row.get::<_, String>(0))?)
+}
+
+#[pg_extern]
+pub fn approx_count_distinct(foreign_table: pg_sys::Oid, column: &str) -> i64 {
+    approx_count_distinct_impl(foreign_table, column).unwrap_or_else(|e| {
+        panic!("{}", e);
+    })
+}
+
+#[inline]
+fn approx_count_distinct_impl(foreign_table: pg_sys::Oid, column: &str) -> Result<i64> {
+    let pg_relation = open_foreign_table(foreign_table)?;
+    let schema_name = pg_relation.namespace();
+    let table_name = pg_relation.name();
+    let column = column.replace('"', "\"\"");
+
+    let conn = unsafe { &*connection::get_global_connection().get() };
+    let query =
+        format!("SELECT approx_count_distinct(\"{column}\") FROM {schema_name}.{table_name}");
+    let mut stmt = conn.prepare(&query)?;
+
+    Ok(stmt.query_row([], |row| row.get::<_, i64>(0))?)
+}
+
+#[pg_extern]
+pub fn unnest_json_array(
+    foreign_table: pg_sys::Oid,
+    column: &str,
+) -> iter::SetOfIterator<'static, JsonB> {
+    let rows = unnest_json_array_impl(foreign_table, column).unwrap_or_else(|e| {
+        panic!("{}", e);
+    });
+    iter::SetOfIterator::new(rows)
+}
+
+#[inline]
+fn unnest_json_array_impl(foreign_table: pg_sys::Oid, column: &str) -> Result<Vec<JsonB>> {
+    let pg_relation = open_foreign_table(foreign_table)?;
+    let schema_name = pg_relation.namespace();
+    let table_name = pg_relation.name();
+    let column = column.replace('"', "\"\"");
+
+    // Each element of the array becomes its own row, in place of the array itself
+    let conn = unsafe { &*connection::get_global_connection().get() };
+    let query = format!(
+        "SELECT to_json(t)::VARCHAR FROM (SELECT * REPLACE (unnest(json_extract(\"{column}\"::JSON, '$[*]')) AS \"{column}\") FROM {schema_name}.{table_name}) t"
+    );
+    let mut stmt = conn.prepare(&query)?;
+
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+
+    rows.iter()
+        .map(|row| Ok(JsonB(serde_json::from_str(row)?)))
+        .collect()
+}
+
+#[pg_extern]
+pub fn copy_with_rownum(
+    foreign_table: pg_sys::Oid,
+    target: &str,
+    id_column: default!(&str, "'row_id'"),
+) -> i64 {
+    copy_with_rownum_impl(foreign_table, target, id_column).unwrap_or_else(|e| {
+        panic!("{}", e);
+    })
+}
+
+#[inline]
+fn copy_with_rownum_impl(foreign_table: pg_sys::Oid, target: &str, id_column: &str) -> Result<i64> {
+    let pg_relation = open_foreign_table(foreign_table)?;
+    let source = spi::quote_qualified_identifier(pg_relation.namespace(), pg_relation.name());
+    let target = match target.split_once('.') {
+        Some((schema_name, table_name)) => spi::quote_qualified_identifier(schema_name, table_name),
+        None => spi::quote_identifier(target),
+    };
+
+    // The rows are numbered in the order they are read, starting at 1
+    Spi::run(&format!(
+        "CREATE TABLE {target} AS SELECT row_number() OVER () AS {}, t.* FROM {source} t",
+        spi::quote_identifier(id_column)
+    ))?;
+
+    Spi::get_one::<i64>(&format!("SELECT COUNT(*) FROM {target}"))?
+        .ok_or_else(|| anyhow!("failed to count the rows copied into {target}"))
+}
+
+#[pg_extern]
+pub fn foreign_table_files(foreign_table: pg_sys::Oid) -> iter::SetOfIterator<'static, String> {
+    let files = foreign_table_files_impl(foreign_table).unwrap_or_else(|e| {
+        panic!("{}", e);
+    });
+    iter::SetOfIterator::new(files)
+}
+
+#[inline]
+fn foreign_table_files_impl(foreign_table: pg_sys::Oid) -> Result<Vec<String>> {
+    let pg_relation = open_foreign_table(foreign_table)?;
+    let foreign_table = unsafe { pg_sys::GetForeignTable(pg_relation.oid()) };
+
+    // Delta and Iceberg tables are directories whose files are tracked in their own metadata
+    let handler = FdwHandler::from(foreign_table);
+    if matches!(
+        handler,
+        FdwHandler::Delta | FdwHandler::Iceberg | FdwHandler::Memory
+    ) {
+        bail!(
+            "listing files is only supported for foreign tables over individual files, {} is not",
+            pg_relation.name()
+        );
+    }
+
+    let table_options =
+        lowercase_option_keys(unsafe { options_to_hashmap((*foreign_table).options)? });
+    let patterns = match (table_options.get("files"), table_options.get("files_from")) {
+        (Some(files), _) => files
+            .split(',')
+            .map(|file| file.trim().to_string())
+            .collect::<Vec<String>>(),
+        (None, Some(manifest)) => utils::read_files_from(manifest)?,
+        (None, None) => {
+            return Err(DuckdbOptionError::missing("files", handler_docs(handler)).into())
+        }
+    };
+
+    let mut files = BTreeSet::new();
+    for pattern in patterns {
+        files.extend(connection::glob_files(&pattern)?);
+    }
+
+    Ok(files.into_iter().collect())
+}
+
+// The page that documents the options of a table's format
+fn handler_docs(handler: FdwHandler) -> &'static str {
+    match handler {
+        FdwHandler::Avro => "formats/avro",
+        FdwHandler::Blob => "formats/blob",
+        FdwHandler::Csv => "formats/csv",
+        FdwHandler::Json => "formats/json",
+        FdwHandler::Spatial => "formats/spatial",
+        FdwHandler::Text => "formats/text",
+        _ => "formats/parquet",
+    }
+}
+
+#[allow(clippy::type_complexity)]
+#[pg_extern]
+pub fn check_schema_drift(
+    foreign_table: pg_sys::Oid,
+) -> iter::TableIterator<
+    'static,
+    (
+        name!(column_name, String),
+        name!(declared_type, String),
+        name!(file_type, Option<String>),
+        name!(issue, String),
+    ),
+> {
+    let rows = check_schema_drift_impl(foreign_table).unwrap_or_else(|e| {
+        panic!("{}", e);
+    });
+    iter::TableIterator::new(rows)
+}
+
+#[inline]
+fn check_schema_drift_impl(foreign_table: pg_sys::Oid) -> Result<Vec<SchemaDriftRow>> {
+    let pg_relation = open_foreign_table(foreign_table)?;
+    let schema_name = pg_relation.namespace();
+    let table_name = pg_relation.name();
+
+    // The view reads the files as they are now, so its columns are the files' current schema
+    let conn = unsafe { &*connection::get_global_connection().get() };
+    let mut stmt = conn.prepare(&format!("DESCRIBE {schema_name}.{table_name}"))?;
+    let file_types = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<(String, String)>, _>>()?;
+
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {schema_name}.{table_name} LIMIT 0"))?;
+    let schema = stmt.query_arrow([])?.get_schema();
+
+    let mut rows = vec![];
+    for attribute in pg_relation
+        .tuple_desc()
+        .iter()
+        .filter(|attribute| !attribute.is_dropped())
+    {
+        let column_name = attribute.name();
+        let declared_type = unsafe {
+            CStr::from_ptr(pg_sys::format_type_with_typemod(
+                attribute.atttypid,
+                attribute.atttypmod,
+            ))
+            .to_str()?
+            .to_string()
+        };
+
+        // DuckDB resolves column names case-insensitively, so they are matched the same way
+        let Some(field_index) = schema
+            .fields()
+            .iter()
+            .position(|field| field.name().eq_ignore_ascii_case(column_name))
+        else {
+            rows.push((
+                column_name.to_string(),
+                declared_type,
+                None,
+                "column is not in the files".to_string(),
+            ));
+            continue;
+        };
+
+        // A null of the file's type is converted the way scans convert every value, which fails
+        // if the type can no longer be read into the declared type
+        let column = new_null_array(schema.field(field_index).data_type(), 1);
+        if let Err(err) =
+            CellReader::resolve(column.data_type(), attribute.atttypid, attribute.atttypmod)
+                .and_then(|reader| reader.read(&column, 0, attribute.atttypid, column_name))
+        {
+            rows.push((
+                column_name.to_string(),
+                declared_type,
+                file_types
+                    .get(field_index)
+                    .map(|(_, file_type)| file_type.clone()),
+                err.to_string(),
+            ));
+        }
+    }
+
+    Ok(rows)
+}
+
+#[pg_extern]
+pub fn scanned_files(query: &str) -> iter::SetOfIterator<'static, String> {
+    let files = scanned_files_impl(query).unwrap_or_else(|e| {
+        panic!("{}", e);
+    });
+    iter::SetOfIterator::new(files)
+}
+
+#[inline]
+fn scanned_files_impl(query: &str) -> Result<Vec<String>> {
+    let pg_relation = match analyze_query_relations(query)?.as_slice() {
+        [pg_relation] => open_foreign_table(pg_relation.oid())?,
+        _ => bail!("scanned files can only be listed for queries over a single foreign table"),
+    };
+    let foreign_table = unsafe { pg_sys::GetForeignTable(pg_relation.oid()) };
+
+    // Only parquet scans prune files, every other format reads all of its files
+    if FdwHandler::from(foreign_table) != FdwHandler::Parquet {
+        return foreign_table_files_impl(pg_relation.oid());
+    }
+
+    set_search_path_by_pg()?;
+    let plan = connection::execute_explain(&format!("EXPLAIN (FORMAT JSON) {query}"))?;
+    let mut file_filters = vec![];
+    collect_file_filters(&serde_json::from_str(&plan)?, &mut file_filters);
+
+    if file_filters.is_empty() {
+        return foreign_table_files_impl(pg_relation.oid());
+    }
+
+    // Evaluate the filters DuckDB prunes files with against a copy of the view that
+    // exposes the file each row was read from
+    let schema_name = pg_relation.namespace();
+    let listing_view = format!("{}_scanned_files", pg_relation.name());
+    let mut table_options =
+        lowercase_option_keys(unsafe { options_to_hashmap((*foreign_table).options)? });
+    table_options.remove(parquet::ParquetOption::Select.as_ref());
+    table_options.remove(parquet::ParquetOption::ColumnTypes.as_ref());
+    table_options.insert(
+        parquet::ParquetOption::FileName.as_ref().to_string(),
+        "true".to_string(),
+    );
+
+    connection::execute(
+        &parquet::create_view(&listing_view, schema_name, table_options)?,
+        [],
+    )?;
+
+    let files = (|| -> Result<Vec<String>> {
+        let conn = unsafe { &*connection::get_global_connection().get() };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT DISTINCT filename FROM {schema_name}.{listing_view} WHERE {} ORDER BY filename",
+            file_filters.join(" AND ")
+        ))?;
+
+        Ok(stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<String>, _>>()?)
+    })();
+
+    connection::execute(
+        &format!("DROP VIEW IF EXISTS {schema_name}.{listing_view}"),
+        [],
+    )?;
+
+    files
+}
+
+// Collects the filters that DuckDB evaluates against file paths, e.g. hive partition keys,
+// from every scan in a JSON query plan
+fn collect_file_filters(plan: &Value, file_filters: &mut Vec<String>) {
+    match plan {
+        Value::Array(nodes) => {
+            for node in nodes {
+                collect_file_filters(node, file_filters);
+            }
+        }
+        Value::Object(node) => {
+            if let Some(Value::String(filter)) = node
+                .get("extra_info")
+                .and_then(|extra_info| extra_info.get("File Filters"))
+            {
+                file_filters.push(filter.clone());
+            }
+            if let Some(children) = node.get("children") {
+                collect_file_filters(children, file_filters);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Parses and analyzes a query to find the relations it reads from
+fn analyze_query_relations(query: &str) -> Result<Vec<PgRelation>> {
+    let query_string = CString::new(query)?;
+
+    unsafe {
+        let raw_stmts = pg_sys::pg_parse_query(query_string.as_ptr());
+        if raw_stmts.is_null() || (*raw_stmts).length != 1 {
+            bail!("expected a single query");
+        }
+        let raw_stmt = (*(*raw_stmts).elements).ptr_value as *mut pg_sys::RawStmt;
+
+        #[cfg(any(feature = "pg15", feature = "pg16", feature = "pg17"))]
+        let queries = pg_sys::pg_analyze_and_rewrite_fixedparams(
+            raw_stmt,
+            query_string.as_ptr(),
+            null_mut(),
+            0,
+            null_mut(),
+        );
+
+        #[cfg(any(feature = "pg13", feature = "pg14"))]
+        let queries = pg_sys::pg_analyze_and_rewrite(
+            raw_stmt,
+            query_string.as_ptr(),
+            null_mut(),
+            0,
+            null_mut(),
+        );
+
+        let query = (*(*queries).elements).ptr_value as *mut pg_sys::Query;
+        if (*query).commandType != pg_sys::CmdType::CMD_SELECT {
+            bail!("expected a SELECT query");
+        }
+
+        Ok(get_query_relations((*query).rtable))
+    }
+}
+
+// Opens a foreign table that the current user can read and makes sure its DuckDB view exists
+fn open_foreign_table(foreign_table: pg_sys::Oid) -> Result<PgRelation> {
+    unsafe {
+        let acl_result = pg_sys::pg_class_aclcheck(
+            foreign_table,
+            pg_sys::GetUserId(),
+            pg_sys::ACL_SELECT as pg_sys::AclMode,
+        );
+        if acl_result != pg_sys::AclResult::ACLCHECK_OK {
+            bail!("permission denied for relation with oid {}", foreign_table);
+        }
+    }
+
+    let pg_relation =
+        unsafe { PgRelation::with_lock(foreign_table, pg_sys::AccessShareLock as i32) };
+    register_foreign_table_view(&pg_relation)?;
+
+    Ok(pg_relation)
+}
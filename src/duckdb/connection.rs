@@ -15,26 +15,62 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use duckdb::arrow::array::RecordBatch;
 use duckdb::{Connection, Params, Statement};
+use pgrx::{ereport, pg_sys, PgSqlErrorCode};
+use sha2::{Digest, Sha256};
 use signal_hook::consts::signal::*;
 use signal_hook::iterator::Signals;
 use std::cell::UnsafeCell;
 use std::collections::HashMap;
-use std::sync::Once;
+use std::os::unix::fs::DirBuilderExt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Mutex, Once};
 use std::thread;
+use std::time::Duration;
 
-use super::{csv, delta, iceberg, json, parquet, secret, spatial};
+use super::error::QueryCanceledError;
+use super::{avro, blob, csv, delta, iceberg, json, memory, parquet, secret, spatial, text};
+use crate::GUCS;
 
 // Global mutable static variables
 static mut GLOBAL_CONNECTION: Option<UnsafeCell<Connection>> = None;
 static mut GLOBAL_STATEMENT: Option<UnsafeCell<Option<Statement<'static>>>> = None;
 static mut GLOBAL_ARROW: Option<UnsafeCell<Option<duckdb::Arrow<'static>>>> = None;
 static INIT: Once = Once::new();
+static PERSISTENT_SECRETS: AtomicBool = AtomicBool::new(false);
+// The temp directory last set on the connection, which is empty while it has DuckDB's default
+static APPLIED_TEMP_DIRECTORY: Mutex<String> = Mutex::new(String::new());
+
+// Postgres JSON functions and types that DuckDB spells differently, so that queries filtering
+// on JSON documents can be pushed down. The -> and ->> operators already work on DuckDB's JSON type.
+const POSTGRES_JSON_COMPAT: [&str; 4] = [
+    "CREATE TYPE jsonb AS JSON",
+    "CREATE MACRO jsonb_array_length(j) AS json_array_length(j)",
+    "CREATE MACRO json_typeof(j) AS CASE json_type(j) WHEN 'VARCHAR' THEN 'string' WHEN 'BIGINT' THEN 'number' WHEN 'UBIGINT' THEN 'number' WHEN 'DOUBLE' THEN 'number' ELSE lower(json_type(j)) END",
+    "CREATE MACRO jsonb_typeof(j) AS json_typeof(j)",
+];
+
+fn open_connection() -> Result<Connection> {
+    let conn = Connection::open_in_memory()?;
+    for statement in POSTGRES_JSON_COMPAT {
+        // Queries that need these fall back to Postgres if they are missing
+        if let Err(err) = conn.execute(statement, []) {
+            pgrx::warning!("failed to create DuckDB compatibility function: {err}");
+        }
+    }
+    // Secrets fall back to being created in memory if the directory can't be used
+    if let Err(err) = init_secret_directory(&conn) {
+        pgrx::warning!("failed to use paradedb.secret_directory: {err}");
+    }
+    Ok(conn)
+}
 
 fn init_globals() {
-    let conn = Connection::open_in_memory().expect("failed to open duckdb connection");
+    let conn = open_connection().expect("failed to open duckdb connection");
     unsafe {
         GLOBAL_CONNECTION = Some(UnsafeCell::new(conn));
         GLOBAL_STATEMENT = Some(UnsafeCell::new(None));
@@ -51,6 +87,35 @@ fn init_globals() {
     });
 }
 
+// DuckDB only reads the secret directory before the first secret is used, so it is set once
+// when the connection is opened
+fn init_secret_directory(conn: &Connection) -> Result<()> {
+    PERSISTENT_SECRETS.store(false, Ordering::Relaxed);
+    let Some(secret_directory) = GUCS.secret_directory.get() else {
+        return Ok(());
+    };
+    let secret_directory = secret_directory.to_str()?;
+    if secret_directory.is_empty() {
+        return Ok(());
+    }
+
+    // Persisted secrets hold credentials, so only the Postgres OS user can read them
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(secret_directory)?;
+    conn.execute(
+        format!(
+            "SET secret_directory = '{}'",
+            secret_directory.replace('\'', "''")
+        )
+        .as_str(),
+        [],
+    )?;
+    PERSISTENT_SECRETS.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
 fn check_extension_loaded(extension_name: &str) -> Result<bool> {
     unsafe {
         let conn = &mut *get_global_connection().get();
@@ -62,6 +127,64 @@ fn check_extension_loaded(extension_name: &str) -> Result<bool> {
     }
 }
 
+// Loads an extension that a format or object store needs, installing it first unless
+// paradedb.autoinstall_extensions is disabled
+fn load_extension(extension_name: &str, repository: Option<&str>) -> Result<()> {
+    if check_extension_loaded(extension_name)? {
+        return Ok(());
+    }
+
+    let install = match repository {
+        Some(repository) => format!("INSTALL {extension_name} FROM {repository}"),
+        None => format!("INSTALL {extension_name}"),
+    };
+    let autoinstall = GUCS.autoinstall_extensions.get();
+    let loaded = if autoinstall {
+        execute(install.as_str(), [])
+    } else {
+        Ok(0)
+    }
+    .and_then(|_| execute(format!("LOAD {extension_name}").as_str(), []));
+
+    if let Err(err) = loaded {
+        let hint = if autoinstall {
+            "if DuckDB can't download extensions, install it into paradedb.extension_directory"
+        } else {
+            "install it into paradedb.extension_directory or enable paradedb.autoinstall_extensions"
+        };
+        bail!("the DuckDB {extension_name} extension could not be loaded, {hint}: {err}");
+    }
+
+    Ok(())
+}
+
+// Loads an extension that a user asked for, if paradedb.allowed_extensions lists it
+pub fn load_allowed_extension(extension_name: &str, repository: Option<&str>) -> Result<()> {
+    let allowed_extensions = match GUCS.allowed_extensions.get() {
+        Some(allowed_extensions) => allowed_extensions.to_str()?.to_string(),
+        None => String::new(),
+    };
+    if !allowed_extensions
+        .split(',')
+        .any(|allowed| allowed.trim().eq_ignore_ascii_case(extension_name))
+    {
+        bail!("the DuckDB {extension_name} extension is not in paradedb.allowed_extensions");
+    }
+
+    // The names are spliced into INSTALL and LOAD, so only plain identifiers are accepted
+    let is_identifier = |name: &str| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    if !is_identifier(extension_name) {
+        bail!("'{extension_name}' is not a valid extension name");
+    }
+    if let Some(repository) = repository.filter(|repository| !is_identifier(repository)) {
+        bail!("'{repository}' is not a valid extension repository, e.g. core or community");
+    }
+
+    load_extension(&extension_name.to_lowercase(), repository)
+}
+
 pub fn get_global_connection() -> &'static UnsafeCell<Connection> {
     INIT.call_once(|| {
         init_globals();
@@ -96,6 +219,124 @@ fn get_global_arrow() -> &'static UnsafeCell<Option<duckdb::Arrow<'static>>> {
     }
 }
 
+// Applies the extension settings that are passed through to DuckDB, which can change between queries
+pub fn apply_settings() -> Result<()> {
+    execute(
+        format!(
+            "SET preserve_insertion_order = {}",
+            GUCS.preserve_insertion_order.get()
+        )
+        .as_str(),
+        [],
+    )?;
+
+    // Settings that are left to DuckDB's default are reset, so that they don't keep a value the
+    // session set earlier
+    let scan_parallelism = GUCS.scan_parallelism.get();
+    if scan_parallelism > 0 {
+        execute(format!("SET threads = {scan_parallelism}").as_str(), [])?;
+    } else {
+        execute("RESET threads", [])?;
+    }
+
+    execute(
+        format!(
+            "SET enable_object_cache = {}",
+            GUCS.enable_object_cache.get()
+        )
+        .as_str(),
+        [],
+    )?;
+
+    execute(
+        format!("SET force_download = {}", GUCS.force_download.get()).as_str(),
+        [],
+    )?;
+
+    apply_temp_directory()?;
+
+    let extension_directory = match GUCS.extension_directory.get() {
+        Some(extension_directory) => extension_directory.to_str()?.replace('\'', "''"),
+        None => String::new(),
+    };
+    if extension_directory.is_empty() {
+        execute("RESET extension_directory", [])?;
+    } else {
+        execute(
+            format!("SET extension_directory = '{extension_directory}'").as_str(),
+            [],
+        )?;
+    }
+
+    execute(
+        format!(
+            "SET autoinstall_known_extensions = {}",
+            GUCS.autoinstall_extensions.get()
+        )
+        .as_str(),
+        [],
+    )?;
+
+    let timezone = match GUCS.duckdb_timezone.get() {
+        Some(timezone) => timezone.to_str()?.replace('\'', "''"),
+        None => String::new(),
+    };
+    if timezone.is_empty() {
+        execute("RESET TimeZone", [])?;
+    } else {
+        execute(format!("SET TimeZone = '{timezone}'").as_str(), [])?;
+    }
+
+    Ok(())
+}
+
+// DuckDB can't switch its temp directory once it has spilled to it, so it is only changed when
+// paradedb.temp_directory is
+fn apply_temp_directory() -> Result<()> {
+    let temp_directory = match GUCS.temp_directory.get() {
+        Some(temp_directory) => temp_directory.to_str()?.replace('\'', "''"),
+        None => String::new(),
+    };
+
+    let mut applied_temp_directory = APPLIED_TEMP_DIRECTORY
+        .lock()
+        .map_err(|_| anyhow!("applied temp directory lock was poisoned"))?;
+    if *applied_temp_directory == temp_directory {
+        return Ok(());
+    }
+
+    if temp_directory.is_empty() {
+        execute("RESET temp_directory", [])?;
+    } else {
+        execute(
+            format!("SET temp_directory = '{temp_directory}'").as_str(),
+            [],
+        )?;
+    }
+    *applied_temp_directory = temp_directory;
+    Ok(())
+}
+
+pub fn create_avro_view(
+    table_name: &str,
+    schema_name: &str,
+    table_options: HashMap<String, String>,
+) -> Result<usize> {
+    load_extension("avro", Some("community"))?;
+
+    let statement = avro::create_view(table_name, schema_name, table_options)?;
+    execute(statement.as_str(), [])
+}
+
+pub fn create_blob_view(
+    table_name: &str,
+    schema_name: &str,
+    table_options: HashMap<String, String>,
+) -> Result<usize> {
+    let statement = blob::create_view(table_name, schema_name, table_options)?;
+    execute(statement.as_str(), [])
+}
+
 pub fn create_csv_view(
     table_name: &str,
     schema_name: &str,
@@ -119,10 +360,7 @@ pub fn create_iceberg_view(
     schema_name: &str,
     table_options: HashMap<String, String>,
 ) -> Result<usize> {
-    if !check_extension_loaded("iceberg")? {
-        execute("INSTALL iceberg", [])?;
-        execute("LOAD iceberg", [])?;
-    }
+    load_extension("iceberg", None)?;
 
     let statement = iceberg::create_view(table_name, schema_name, table_options)?;
     execute(statement.as_str(), [])
@@ -142,15 +380,21 @@ pub fn create_spatial_view(
     schema_name: &str,
     table_options: HashMap<String, String>,
 ) -> Result<usize> {
-    if !check_extension_loaded("spatial")? {
-        execute("INSTALL spatial", [])?;
-        execute("LOAD spatial", [])?;
-    }
+    load_extension("spatial", None)?;
 
     let statement = spatial::create_view(table_name, schema_name, table_options)?;
     execute(statement.as_str(), [])
 }
 
+pub fn create_text_view(
+    table_name: &str,
+    schema_name: &str,
+    table_options: HashMap<String, String>,
+) -> Result<usize> {
+    let statement = text::create_view(table_name, schema_name, table_options)?;
+    execute(statement.as_str(), [])
+}
+
 pub fn create_json_view(
     table_name: &str,
     schema_name: &str,
@@ -160,7 +404,144 @@ pub fn create_json_view(
     execute(statement.as_str(), [])
 }
 
+pub fn create_memory_view(
+    table_name: &str,
+    schema_name: &str,
+    table_options: HashMap<String, String>,
+) -> Result<usize> {
+    let statement = memory::create_view(table_name, schema_name, table_options)?;
+    execute(statement.as_str(), [])
+}
+
+pub fn glob_files(pattern: &str) -> Result<Vec<String>> {
+    let conn = unsafe { &*get_global_connection().get() };
+    let mut statement = conn.prepare(&format!(
+        "SELECT file FROM glob('{}')",
+        pattern.replace('\'', "''")
+    ))?;
+    let files = statement
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(files)
+}
+
+pub fn parquet_column_names(table_options: &HashMap<String, String>) -> Result<Vec<String>> {
+    let conn = unsafe { &*get_global_connection().get() };
+    let mut statement = conn.prepare(&parquet::describe_files(table_options)?)?;
+    let column_names = statement
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(column_names)
+}
+
+pub fn csv_column_names(table_options: &HashMap<String, String>) -> Result<Vec<String>> {
+    let conn = unsafe { &*get_global_connection().get() };
+    let mut statement = conn.prepare(&csv::describe_files(table_options)?)?;
+    let column_names = statement
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(column_names)
+}
+
+// The columns of parquet files that only contain nulls
+pub fn parquet_null_columns(table_options: &HashMap<String, String>) -> Result<Vec<String>> {
+    let conn = unsafe { &*get_global_connection().get() };
+    let mut statement = conn.prepare(&parquet::describe_null_columns(table_options)?)?;
+    let null_columns = statement
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(null_columns)
+}
+
+// The columns of parquet files that have field ids, as (file, column name, field id)
+pub fn parquet_field_ids(
+    table_options: &HashMap<String, String>,
+) -> Result<Vec<(String, String, i32)>> {
+    let conn = unsafe { &*get_global_connection().get() };
+    let mut statement = conn.prepare(&parquet::describe_field_ids(table_options)?)?;
+    let field_ids = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<(String, String, i32)>, _>>()?;
+    Ok(field_ids)
+}
+
+// The number of rows in each row group of a parquet file, in row group order
+pub fn parquet_row_group_sizes(file: &str) -> Result<Vec<i64>> {
+    let conn = unsafe { &*get_global_connection().get() };
+    let mut statement = conn.prepare(&format!(
+        "SELECT DISTINCT row_group_id, row_group_num_rows FROM parquet_metadata('{}') ORDER BY row_group_id",
+        file.replace('\'', "''")
+    ))?;
+    let row_group_sizes = statement
+        .query_map([], |row| row.get::<_, i64>(1))?
+        .collect::<Result<Vec<i64>, _>>()?;
+    Ok(row_group_sizes)
+}
+
+// The names and DuckDB types of the columns of a parquet file
+pub fn parquet_file_columns(file: &str) -> Result<Vec<(String, String)>> {
+    let conn = unsafe { &*get_global_connection().get() };
+    let mut statement = conn.prepare(&format!(
+        "SELECT column_name, column_type FROM (DESCRIBE SELECT * FROM read_parquet('{}'))",
+        file.replace('\'', "''")
+    ))?;
+    let columns = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<(String, String)>, _>>()?;
+    Ok(columns)
+}
+
+// The DuckDB type that a column of a parquet file is read as
+pub fn parquet_column_type(file: &str, column_name: &str) -> Result<String> {
+    let conn = unsafe { &*get_global_connection().get() };
+    let mut statement = conn.prepare(&format!(
+        "SELECT column_type FROM (DESCRIBE SELECT \"{}\" FROM read_parquet('{}'))",
+        column_name.replace('"', "\"\""),
+        file.replace('\'', "''")
+    ))?;
+    Ok(statement.query_row([], |row| row.get::<_, String>(0))?)
+}
+
+// Errors if the footer of a parquet file can't be read
+pub fn check_parquet_file(file: &str) -> Result<()> {
+    let conn = unsafe { &*get_global_connection().get() };
+    let mut statement = conn.prepare(&format!(
+        "SELECT COUNT(*) FROM parquet_metadata('{}')",
+        file.replace('\'', "''")
+    ))?;
+    statement.query_row([], |row| row.get::<_, i64>(0))?;
+    Ok(())
+}
+
+pub fn glob_file_count(pattern: &str) -> Result<i64> {
+    let conn = unsafe { &*get_global_connection().get() };
+    let mut statement = conn.prepare(&format!(
+        "SELECT COUNT(*) FROM glob('{}')",
+        pattern.replace('\'', "''")
+    ))?;
+    Ok(statement.query_row([], |row| row.get::<_, i64>(0))?)
+}
+
 pub fn create_arrow(sql: &str) -> Result<bool> {
+    log_query(sql);
+
+    let timeout_ms = GUCS.duckdb_query_timeout_ms.get();
+    let timeout = QueryTimeout::start(timeout_ms);
+    let result = query_arrow(sql);
+    if timeout.finish() && result.is_err() {
+        bail!(QueryCanceledError::Timeout(timeout_ms));
+    }
+    result
+}
+
+// Whether a query failed because it was interrupted, either by paradedb.duckdb_query_timeout_ms
+// or by a signal, rather than because DuckDB could not run it
+pub fn is_interrupted(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<QueryCanceledError>().is_some()
+        || err.to_string().contains("INTERRUPT Error")
+}
+
+fn query_arrow(sql: &str) -> Result<bool> {
     unsafe {
         let conn = &mut *get_global_connection().get();
         let statement = conn.prepare(sql)?;
@@ -180,6 +561,58 @@ pub fn create_arrow(sql: &str) -> Result<bool> {
     Ok(true)
 }
 
+// Interrupts the connection from a separate thread if a query runs for longer than the timeout
+struct QueryTimeout {
+    timer: Option<(mpsc::Sender<()>, thread::JoinHandle<bool>)>,
+}
+
+impl QueryTimeout {
+    fn start(timeout_ms: i32) -> Self {
+        if timeout_ms <= 0 {
+            return Self { timer: None };
+        }
+
+        // Only the interrupt handle is shared with the timer thread, since the backend keeps
+        // using the connection while the query runs
+        let interrupt = unsafe { &*get_global_connection().get() }.interrupt_handle();
+        let (sender, receiver) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            match receiver.recv_timeout(Duration::from_millis(timeout_ms as u64)) {
+                Err(RecvTimeoutError::Timeout) => {
+                    interrupt.interrupt();
+                    true
+                }
+                _ => false,
+            }
+        });
+
+        Self {
+            timer: Some((sender, handle)),
+        }
+    }
+
+    // Stops the timer and returns whether the query was interrupted
+    fn finish(self) -> bool {
+        match self.timer {
+            Some((sender, handle)) => {
+                let _ = sender.send(());
+                handle.join().unwrap_or(false)
+            }
+            None => false,
+        }
+    }
+}
+
+fn log_query(sql: &str) {
+    if GUCS.log_duckdb_queries.get() {
+        ereport!(
+            GUCS.log_duckdb_queries_level.get().into(),
+            PgSqlErrorCode::ERRCODE_SUCCESSFUL_COMPLETION,
+            format!("duckdb query: {sql}")
+        );
+    }
+}
+
 pub fn clear_arrow() {
     unsafe {
         *get_global_statement().get() = None;
@@ -187,14 +620,197 @@ pub fn clear_arrow() {
     }
 }
 
+// Replaces the session's DuckDB connection with a fresh one, discarding its settings, secrets
+// and views. Views and secrets are recreated the next time each foreign table is read.
+pub fn reset_connection() -> Result<()> {
+    let conn = open_connection()?;
+    unsafe {
+        *get_global_arrow().get() = None;
+        *get_global_statement().get() = None;
+        *get_global_connection().get() = conn;
+    }
+    if let Ok(mut applied_temp_directory) = APPLIED_TEMP_DIRECTORY.lock() {
+        applied_temp_directory.clear();
+    }
+    Ok(())
+}
+
 pub fn create_secret(
     secret_name: &str,
     user_mapping_options: HashMap<String, String>,
 ) -> Result<usize> {
+    // DuckDB only autoloads the azure extension when it first reads an Azure path, which is
+    // after the secret has to be created
+    let is_azure = user_mapping_options
+        .get(secret::UserMappingOptions::Type.as_ref())
+        .is_some_and(|secret_type| secret_type.eq_ignore_ascii_case("azure"));
+    if is_azure {
+        load_extension("azure", None)?;
+    }
+
+    if PERSISTENT_SECRETS.load(Ordering::Relaxed) {
+        return create_persistent_secret(secret_name, user_mapping_options);
+    }
+
     let statement = secret::create_secret(secret_name, user_mapping_options)?;
     execute(statement.as_str(), [])
 }
 
+// Persisted secrets are shared by every database and role in the cluster, so their names start
+// with the database and user that they are created for, and end with a fingerprint of their
+// options. A secret that is already persisted is reused instead of being written again.
+fn create_persistent_secret(
+    secret_name: &str,
+    user_mapping_options: HashMap<String, String>,
+) -> Result<usize> {
+    // The fingerprint has to stay the same across builds to find the secrets persisted by them
+    let mut options = user_mapping_options.iter().collect::<Vec<_>>();
+    options.sort();
+    let mut hasher = Sha256::new();
+    for (key, value) in options {
+        hasher.update(key.as_bytes());
+        hasher.update([0]);
+        hasher.update(value.as_bytes());
+        hasher.update([0]);
+    }
+    let fingerprint = hasher
+        .finalize()
+        .iter()
+        .take(8)
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+
+    let secret_name = secret_name.to_lowercase();
+    let user = unsafe { pg_sys::GetUserId() };
+    let persistent_name = format!(
+        "{}{user}_{secret_name}_{fingerprint}",
+        persistent_secret_namespace()
+    );
+
+    let loaded_secrets = loaded_persistent_secrets()?;
+    if loaded_secrets.contains(&persistent_name) {
+        return Ok(0);
+    }
+
+    // Secrets with other options were persisted before the user mapping or table changed
+    for stale_name in loaded_secrets.iter().filter(|name| {
+        parse_persistent_secret_name(name)
+            .is_some_and(|(name_user, name_secret)| name_user == user && name_secret == secret_name)
+    }) {
+        execute(
+            &format!(
+                "DROP PERSISTENT SECRET IF EXISTS \"{}\"",
+                stale_name.replace('"', "\"\"")
+            ),
+            [],
+        )?;
+    }
+
+    let statement = secret::create_secret(&persistent_name, user_mapping_options)?.replacen(
+        "CREATE OR REPLACE SECRET",
+        "CREATE OR REPLACE PERSISTENT SECRET",
+        1,
+    );
+    execute(statement.as_str(), [])
+}
+
+// Drops the secrets persisted for a foreign table in the current database, or for all of its
+// foreign tables if no table is given, for one user or for every user
+pub fn drop_persistent_secrets(
+    table: Option<(&str, &str)>,
+    user: Option<pg_sys::Oid>,
+) -> Result<()> {
+    // Opening the connection is what decides whether secrets are persisted
+    get_global_connection();
+    if !PERSISTENT_SECRETS.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+
+    let table_secret = table.map(|(schema_name, table_name)| {
+        format!("{schema_name}_{table_name}_secret").to_lowercase()
+    });
+    let secret_directory = secret_directory()?;
+
+    // Secrets persisted by other connections after this one read the directory are only files
+    let mut names = loaded_persistent_secrets()?;
+    for entry in std::fs::read_dir(&secret_directory)? {
+        if let Some(name) = entry?
+            .file_name()
+            .to_str()
+            .and_then(|file_name| file_name.strip_suffix(".duckdb_secret"))
+        {
+            if !names.iter().any(|loaded_name| loaded_name == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    for name in names {
+        let Some((name_user, name_secret)) = parse_persistent_secret_name(&name) else {
+            continue;
+        };
+        if user.is_some_and(|user| user != name_user) {
+            continue;
+        }
+        // Tables whose files span several scopes have a secret for each, e.g. table_secret_1
+        if let Some(table_secret) = &table_secret {
+            let index = name_secret.strip_prefix(table_secret.as_str());
+            if !index.is_some_and(|index| {
+                index.is_empty()
+                    || index
+                        .strip_prefix('_')
+                        .is_some_and(|index| index.chars().all(|c| c.is_ascii_digit()))
+            }) {
+                continue;
+            }
+        }
+
+        execute(
+            &format!(
+                "DROP PERSISTENT SECRET IF EXISTS \"{}\"",
+                name.replace('"', "\"\"")
+            ),
+            [],
+        )?;
+        let secret_file = secret_directory.join(format!("{name}.duckdb_secret"));
+        if secret_file.exists() {
+            std::fs::remove_file(secret_file)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn persistent_secret_namespace() -> String {
+    format!("paradedb_{}_", unsafe { pg_sys::MyDatabaseId })
+}
+
+// The user and secret name of a secret persisted in the current database
+fn parse_persistent_secret_name(name: &str) -> Option<(pg_sys::Oid, &str)> {
+    let (user, secret_name) = name
+        .strip_prefix(persistent_secret_namespace().as_str())?
+        .split_once('_')?;
+    let (secret_name, _fingerprint) = secret_name.rsplit_once('_')?;
+    Some((pg_sys::Oid::from(user.parse::<u32>().ok()?), secret_name))
+}
+
+fn secret_directory() -> Result<PathBuf> {
+    let conn = unsafe { &*get_global_connection().get() };
+    let directory = conn.query_row("SELECT current_setting('secret_directory')", [], |row| {
+        row.get::<_, String>(0)
+    })?;
+    Ok(PathBuf::from(directory))
+}
+
+fn loaded_persistent_secrets() -> Result<Vec<String>> {
+    let conn = unsafe { &*get_global_connection().get() };
+    let mut statement = conn.prepare("SELECT name FROM duckdb_secrets() WHERE persistent")?;
+    let names = statement
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(names)
+}
+
 pub fn get_next_batch() -> Result<Option<RecordBatch>> {
     unsafe {
         if let Some(arrow) = get_global_arrow().get().as_mut().unwrap() {
@@ -15,12 +15,13 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use std::collections::HashMap;
 use strum::{AsRefStr, EnumIter};
 
 use crate::fdw::base::OptionValidator;
 
+use super::error::DuckdbOptionError;
 use super::utils;
 
 #[derive(EnumIter, AsRefStr, PartialEq, Debug)]
@@ -30,11 +31,14 @@ pub enum CsvOption {
     AllowQuotedNulls,
     AutoDetect,
     AutoTypeCandidates,
+    ColumnMapping,
+    ColumnTypes,
     Columns,
     Compression,
     Dateformat,
     DecimalSeparator,
     Delim,
+    DisableGlob,
     Escape,
     Filename,
     Files,
@@ -57,6 +61,7 @@ pub enum CsvOption {
     Select,
     Sep,
     Skip,
+    Thousands,
     Timestampformat,
     Types,
     UnionByName,
@@ -69,11 +74,16 @@ impl OptionValidator for CsvOption {
             Self::AllowQuotedNulls => false,
             Self::AutoDetect => false,
             Self::AutoTypeCandidates => false,
+            // Applied when the view is registered, since it needs the declared columns
+            Self::ColumnMapping => false,
+            Self::ColumnTypes => false,
             Self::Columns => false,
             Self::Compression => false,
             Self::Dateformat => false,
             Self::DecimalSeparator => false,
             Self::Delim => false,
+            // Applied when the view is registered, since it rewrites the files option
+            Self::DisableGlob => false,
             Self::Escape => false,
             Self::Filename => false,
             Self::Files => true,
@@ -96,6 +106,7 @@ impl OptionValidator for CsvOption {
             Self::Select => false,
             Self::Sep => false,
             Self::Skip => false,
+            Self::Thousands => false,
             Self::Timestampformat => false,
             Self::Types => false,
             Self::UnionByName => false,
@@ -103,15 +114,11 @@ impl OptionValidator for CsvOption {
     }
 }
 
-pub fn create_view(
-    table_name: &str,
-    schema_name: &str,
-    table_options: HashMap<String, String>,
-) -> Result<String> {
+fn read_csv(table_options: &HashMap<String, String>) -> Result<String> {
     let files = Some(utils::format_csv(
         table_options
             .get(CsvOption::Files.as_ref())
-            .ok_or_else(|| anyhow!("files option is required"))?,
+            .ok_or_else(|| DuckdbOptionError::missing(CsvOption::Files, "formats/csv"))?,
     ));
 
     let all_varchar = table_options
@@ -226,13 +233,35 @@ pub fn create_view(
         .get(CsvOption::Skip.as_ref())
         .map(|option| format!("skip = {option}"));
 
+    let thousands = table_options
+        .get(CsvOption::Thousands.as_ref())
+        .map(|option| format!("thousands = '{option}'"));
+
     let timestampformat = table_options
         .get(CsvOption::Timestampformat.as_ref())
         .map(|option| format!("timestampformat = '{option}'"));
 
-    let types = table_options
-        .get(CsvOption::Types.as_ref())
-        .map(|option| format!("types = {}", utils::format_csv(option)));
+    // column_types overrides the detected types of individual columns while they are read,
+    // so that e.g. zip codes read as VARCHAR keep their leading zeros
+    let types = match (
+        table_options.get(CsvOption::Types.as_ref()),
+        table_options.get(CsvOption::ColumnTypes.as_ref()),
+    ) {
+        (Some(_), Some(_)) => {
+            return Err(DuckdbOptionError::invalid(
+                CsvOption::ColumnTypes,
+                "types and column_types options cannot both be set",
+                "formats/csv",
+            )
+            .into())
+        }
+        (Some(types), None) => Some(format!("types = {}", utils::format_csv(types))),
+        (None, Some(column_types)) => Some(format!(
+            "types = {}",
+            utils::format_column_types_struct(column_types, "formats/csv")?
+        )),
+        (None, None) => None,
+    };
 
     let union_by_name = table_options
         .get(CsvOption::UnionByName.as_ref())
@@ -268,6 +297,7 @@ pub fn create_view(
         sample_size,
         sep,
         skip,
+        thousands,
         timestampformat,
         types,
         union_by_name,
@@ -277,12 +307,31 @@ pub fn create_view(
     .collect::<Vec<String>>()
     .join(", ");
 
+    Ok(format!("read_csv({create_csv_str})"))
+}
+
+pub fn describe_files(table_options: &HashMap<String, String>) -> Result<String> {
+    Ok(format!(
+        "DESCRIBE SELECT * FROM {}",
+        read_csv(table_options)?
+    ))
+}
+
+pub fn create_view(
+    table_name: &str,
+    schema_name: &str,
+    table_options: HashMap<String, String>,
+) -> Result<String> {
+    let files = read_csv(&table_options)?;
+
     let default_select = "*".to_string();
     let select = table_options
         .get(CsvOption::Select.as_ref())
         .unwrap_or(&default_select);
 
-    Ok(format!("CREATE VIEW IF NOT EXISTS {schema_name}.{table_name} AS SELECT {select} FROM read_csv({create_csv_str})"))
+    Ok(format!(
+        "CREATE VIEW IF NOT EXISTS {schema_name}.{table_name} AS SELECT {select} FROM {files}"
+    ))
 }
 
 #[cfg(test)]
@@ -311,6 +360,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_describe_csv_files() {
+        let table_options = HashMap::from([
+            (
+                CsvOption::Files.as_ref().to_string(),
+                "/data/file.csv".to_string(),
+            ),
+            (CsvOption::Header.as_ref().to_string(), "false".to_string()),
+        ]);
+        let expected = "DESCRIBE SELECT * FROM read_csv('/data/file.csv', header = false)";
+        let actual = describe_files(&table_options).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_create_csv_view_missing_files() {
+        let err = create_view("test", "main", HashMap::new()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DuckdbOptionError>(),
+            Some(&DuckdbOptionError::missing(CsvOption::Files, "formats/csv"))
+        );
+    }
+
     #[test]
     fn test_create_csv_view_multiple_files() {
         let table_name = "test";
@@ -426,6 +499,7 @@ mod tests {
             ),
             (CsvOption::Sep.as_ref().to_string(), ",".to_string()),
             (CsvOption::Skip.as_ref().to_string(), "0".to_string()),
+            (CsvOption::Thousands.as_ref().to_string(), "_".to_string()),
             (
                 CsvOption::Timestampformat.as_ref().to_string(),
                 "yyyy-MM-dd HH:mm:ss".to_string(),
@@ -440,7 +514,7 @@ mod tests {
             ),
         ]);
 
-        let expected = "CREATE VIEW IF NOT EXISTS main.test AS SELECT * FROM read_csv('/data/file.csv', all_varchar = true, allow_quoted_nulls = true, auto_detect = true, auto_type_candidates = ['BIGINT', 'DATE'], columns = {'col1': 'INTEGER', 'col2': 'VARCHAR'}, compression = 'gzip', dateformat = '%d/%m/%Y', decimal_separator = '.', delim = ',', escape = '\"', filename = true, force_not_null = ['col1', 'col2'], header = true, hive_partitioning = true, hive_types = true, hive_types_autocast = true, ignore_errors = true, max_line_size = 1000, names = ['col1', 'col2'], new_line = '\n', normalize_names = true, null_padding = true, nullstr = ['none', 'null'], parallel = true, quote = '\"', sample_size = 100, sep = ',', skip = 0, timestampformat = 'yyyy-MM-dd HH:mm:ss', types = ['BIGINT', 'VARCHAR'], union_by_name = true)";
+        let expected = "CREATE VIEW IF NOT EXISTS main.test AS SELECT * FROM read_csv('/data/file.csv', all_varchar = true, allow_quoted_nulls = true, auto_detect = true, auto_type_candidates = ['BIGINT', 'DATE'], columns = {'col1': 'INTEGER', 'col2': 'VARCHAR'}, compression = 'gzip', dateformat = '%d/%m/%Y', decimal_separator = '.', delim = ',', escape = '\"', filename = true, force_not_null = ['col1', 'col2'], header = true, hive_partitioning = true, hive_types = true, hive_types_autocast = true, ignore_errors = true, max_line_size = 1000, names = ['col1', 'col2'], new_line = '\n', normalize_names = true, null_padding = true, nullstr = ['none', 'null'], parallel = true, quote = '\"', sample_size = 100, sep = ',', skip = 0, thousands = '_', timestampformat = 'yyyy-MM-dd HH:mm:ss', types = ['BIGINT', 'VARCHAR'], union_by_name = true)";
         let actual = create_view(table_name, schema_name, table_options).unwrap();
 
         assert_eq!(expected, actual);
@@ -451,4 +525,39 @@ mod tests {
             Err(e) => assert!(e.to_string().contains("file.csv")),
         }
     }
+
+    #[test]
+    fn test_create_csv_view_with_column_types() {
+        let table_name = "test";
+        let schema_name = "main";
+        let table_options = HashMap::from([
+            (
+                CsvOption::Files.as_ref().to_string(),
+                "/data/file.csv".to_string(),
+            ),
+            (
+                CsvOption::ColumnTypes.as_ref().to_string(),
+                r#"{"zip": "VARCHAR"}"#.to_string(),
+            ),
+        ]);
+
+        let expected = "CREATE VIEW IF NOT EXISTS main.test AS SELECT * FROM read_csv('/data/file.csv', types = {'zip': 'VARCHAR'})";
+        let actual = create_view(table_name, schema_name, table_options).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let table_options = HashMap::from([
+            (
+                CsvOption::Files.as_ref().to_string(),
+                "/data/file.csv".to_string(),
+            ),
+            (
+                CsvOption::ColumnTypes.as_ref().to_string(),
+                r#"{"zip": "VARCHAR"}"#.to_string(),
+            ),
+            (CsvOption::Types.as_ref().to_string(), "VARCHAR".to_string()),
+        ]);
+
+        assert!(create_view(table_name, schema_name, table_options).is_err());
+    }
 }
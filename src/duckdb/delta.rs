@@ -15,8 +15,9 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use crate::duckdb::error::DuckdbOptionError;
 use crate::fdw::base::OptionValidator;
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use std::collections::HashMap;
 use strum::{AsRefStr, EnumIter};
 
@@ -43,12 +44,25 @@ pub fn create_view(
     schema_name: &str,
     table_options: HashMap<String, String>,
 ) -> Result<String> {
-    let files = format!(
-        "'{}'",
-        table_options
-            .get(DeltaOption::Files.as_ref())
-            .ok_or_else(|| anyhow!("files option is required"))?
-    );
+    let files = table_options
+        .get(DeltaOption::Files.as_ref())
+        .ok_or_else(|| DuckdbOptionError::missing(DeltaOption::Files, "formats/delta"))?
+        .split(',')
+        .map(|file| file.trim())
+        .collect::<Vec<&str>>();
+
+    // delta_scan reads a single table, so multiple tables are combined with a union
+    let delta_scan = match files.as_slice() {
+        [file] => format!("delta_scan('{file}')"),
+        files => format!(
+            "({})",
+            files
+                .iter()
+                .map(|file| format!("SELECT * FROM delta_scan('{file}')"))
+                .collect::<Vec<String>>()
+                .join(" UNION ALL BY NAME ")
+        ),
+    };
 
     let default_select = "*".to_string();
     let select = table_options
@@ -56,7 +70,7 @@ pub fn create_view(
         .unwrap_or(&default_select);
 
     Ok(format!(
-        "CREATE VIEW IF NOT EXISTS {schema_name}.{table_name} AS SELECT {select} FROM delta_scan({files})"
+        "CREATE VIEW IF NOT EXISTS {schema_name}.{table_name} AS SELECT {select} FROM {delta_scan}"
     ))
 }
 
@@ -86,4 +100,25 @@ mod tests {
             Err(e) => assert!(e.to_string().contains("/data/delta")),
         }
     }
+
+    #[test]
+    fn test_create_delta_view_multiple_tables() {
+        let table_name = "test";
+        let schema_name = "main";
+        let table_options = HashMap::from([(
+            DeltaOption::Files.as_ref().to_string(),
+            "/data/delta1, /data/delta2".to_string(),
+        )]);
+
+        let expected = "CREATE VIEW IF NOT EXISTS main.test AS SELECT * FROM (SELECT * FROM delta_scan('/data/delta1') UNION ALL BY NAME SELECT * FROM delta_scan('/data/delta2'))";
+        let actual = create_view(table_name, schema_name, table_options).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let conn = Connection::open_in_memory().unwrap();
+        match conn.prepare(&actual) {
+            Ok(_) => panic!("invalid delta file should throw an error"),
+            Err(e) => assert!(e.to_string().contains("/data/delta1")),
+        }
+    }
 }
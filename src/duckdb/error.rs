@@ -0,0 +1,98 @@
+// Copyright (c) 2023-2024 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use thiserror::Error;
+
+const DOCS_URL: &str = "https://docs.paradedb.com/integrations";
+const DUCKDB_META_TABLES_URL: &str =
+    "https://duckdb.org/docs/guides/meta/duckdb_environment#meta-table-functions";
+
+// Errors in the options of foreign tables and user mappings. `docs` is the path of the page
+// that documents the option, e.g. formats/csv.
+#[derive(Error, Debug, PartialEq)]
+pub enum DuckdbOptionError {
+    #[error("{option} option is required, see {}/{docs}", DOCS_URL)]
+    MissingOption { option: String, docs: &'static str },
+
+    #[error(
+        "invalid value for the {option} option: {reason}, see {}/{docs}",
+        DOCS_URL
+    )]
+    InvalidValue {
+        option: String,
+        reason: String,
+        docs: &'static str,
+    },
+
+    #[error(
+        "{kind} name '{name}' is not allowed because it is reserved by DuckDB, see {}",
+        DUCKDB_META_TABLES_URL
+    )]
+    ReservedName { kind: &'static str, name: String },
+}
+
+impl DuckdbOptionError {
+    pub fn missing(option: impl AsRef<str>, docs: &'static str) -> Self {
+        Self::MissingOption {
+            option: option.as_ref().to_string(),
+            docs,
+        }
+    }
+
+    pub fn invalid(option: impl AsRef<str>, reason: impl Into<String>, docs: &'static str) -> Self {
+        Self::InvalidValue {
+            option: option.as_ref().to_string(),
+            reason: reason.into(),
+            docs,
+        }
+    }
+}
+
+// Queries that DuckDB stopped before they finished
+#[derive(Error, Debug, PartialEq)]
+pub enum QueryCanceledError {
+    #[error(
+        "DuckDB query was canceled because it exceeded paradedb.duckdb_query_timeout_ms ({0} ms)"
+    )]
+    Timeout(i32),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_option_error_display() {
+        assert_eq!(
+            DuckdbOptionError::missing("files", "formats/csv").to_string(),
+            "files option is required, see https://docs.paradedb.com/integrations/formats/csv"
+        );
+        assert_eq!(
+            DuckdbOptionError::invalid("type", "INVALID is not a secret type", "object_stores/s3")
+                .to_string(),
+            "invalid value for the type option: INVALID is not a secret type, see https://docs.paradedb.com/integrations/object_stores/s3"
+        );
+        assert_eq!(
+            DuckdbOptionError::ReservedName {
+                kind: "Table",
+                name: "duckdb_tables".to_string()
+            }
+            .to_string(),
+            "Table name 'duckdb_tables' is not allowed because it is reserved by DuckDB, see https://duckdb.org/docs/guides/meta/duckdb_environment#meta-table-functions"
+        );
+    }
+}
@@ -15,12 +15,14 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use std::collections::HashMap;
 use strum::{AsRefStr, EnumIter};
 
 use crate::fdw::base::OptionValidator;
 
+use super::error::DuckdbOptionError;
+
 #[derive(EnumIter, AsRefStr, PartialEq, Debug)]
 #[strum(serialize_all = "snake_case")]
 pub enum IcebergOption {
@@ -54,7 +56,7 @@ pub fn create_view(
         "'{}'",
         table_options
             .get(IcebergOption::Files.as_ref())
-            .ok_or_else(|| anyhow!("files option is required"))?
+            .ok_or_else(|| DuckdbOptionError::missing(IcebergOption::Files, "formats/iceberg"))?
     ));
 
     let allow_moved_paths = table_options
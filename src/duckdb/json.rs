@@ -15,12 +15,13 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use std::collections::HashMap;
 use strum::{AsRefStr, Display, EnumIter};
 
 use crate::fdw::base::OptionValidator;
 
+use super::error::DuckdbOptionError;
 use super::utils;
 
 #[derive(EnumIter, AsRefStr, PartialEq, Debug, Display)]
@@ -31,6 +32,7 @@ pub enum JsonOption {
     Compression,
     ConvertStringsToIntegers,
     Dateformat,
+    DisableGlob,
     Filename,
     Files,
     Format,
@@ -59,9 +61,24 @@ pub fn create_view(
     let files = Some(utils::format_csv(
         table_options
             .get(JsonOption::Files.as_ref())
-            .ok_or_else(|| anyhow!("files option is required"))?,
+            .ok_or_else(|| DuckdbOptionError::missing(JsonOption::Files, "formats/json"))?,
     ));
 
+    // DuckDB errors if an object is larger than this, so that a huge object can't exhaust memory
+    if let Some(maximum_object_size) = table_options.get(JsonOption::MaximumObjectSize.as_ref()) {
+        if !maximum_object_size
+            .parse::<u64>()
+            .is_ok_and(|maximum_object_size| maximum_object_size > 0)
+        {
+            return Err(DuckdbOptionError::invalid(
+                JsonOption::MaximumObjectSize,
+                format!("{maximum_object_size} is not a positive number of bytes"),
+                "formats/json",
+            )
+            .into());
+        }
+    }
+
     let create_json_str = vec![
         files,
         extract_option(JsonOption::AutoDetect, &table_options, false),
@@ -185,4 +202,26 @@ mod tests {
             Err(e) => assert!(e.to_string().contains("file1.json")),
         }
     }
+
+    #[test]
+    fn test_create_json_view_invalid_maximum_object_size() {
+        for maximum_object_size in ["0", "-1", "16MB"] {
+            let table_options = HashMap::from([
+                (
+                    JsonOption::Files.as_ref().to_string(),
+                    "/data/file1.json".to_string(),
+                ),
+                (
+                    JsonOption::MaximumObjectSize.as_ref().to_string(),
+                    maximum_object_size.to_string(),
+                ),
+            ]);
+
+            let err = create_view("json_test", "main", table_options).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<DuckdbOptionError>(),
+                Some(DuckdbOptionError::InvalidValue { option, .. }) if option == "maximum_object_size"
+            ));
+        }
+    }
 }
@@ -0,0 +1,146 @@
+// Copyright (c) 2023-2024 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use anyhow::{anyhow, bail, Result};
+use std::collections::HashMap;
+use strum::{AsRefStr, EnumIter};
+
+use crate::fdw::base::OptionValidator;
+
+use super::error::DuckdbOptionError;
+
+#[derive(EnumIter, AsRefStr, PartialEq, Debug)]
+#[strum(serialize_all = "snake_case")]
+pub enum MemoryOption {
+    PreserveCasing,
+    Select,
+    Values,
+}
+
+impl OptionValidator for MemoryOption {
+    fn is_required(&self) -> bool {
+        match self {
+            Self::PreserveCasing => false,
+            Self::Select => false,
+            Self::Values => true,
+        }
+    }
+}
+
+// Turns a list of rows, e.g. [[1, 'a'], [2, 'b']], into the rows of a VALUES clause,
+// e.g. (1, 'a'), (2, 'b')
+fn format_values(values: &str) -> Result<String> {
+    let values = values
+        .trim()
+        .strip_prefix('[')
+        .and_then(|values| values.strip_suffix(']'))
+        .ok_or_else(|| anyhow!("values must be a list of rows, e.g. [[1, 'a'], [2, 'b']]"))?;
+
+    let mut rows = vec![];
+    let mut row_start = None;
+    let mut depth = 0;
+    let mut in_quotes = false;
+
+    for (index, c) in values.char_indices() {
+        match c {
+            '\'' => in_quotes = !in_quotes,
+            '[' if !in_quotes => {
+                if depth == 0 {
+                    row_start = Some(index + 1);
+                }
+                depth += 1;
+            }
+            ']' if !in_quotes => {
+                depth -= 1;
+                if depth == 0 {
+                    let start = row_start
+                        .take()
+                        .ok_or_else(|| anyhow!("unbalanced brackets in values"))?;
+                    rows.push(format!("({})", &values[start..index]));
+                }
+            }
+            c if depth == 0 && !in_quotes && !c.is_whitespace() && c != ',' => {
+                bail!("each row in values must be a list, e.g. [1, 'a']")
+            }
+            _ => {}
+        }
+    }
+
+    if depth != 0 || in_quotes {
+        bail!("unbalanced brackets or quotes in values");
+    }
+
+    if rows.is_empty() {
+        bail!("values must contain at least one row");
+    }
+
+    Ok(rows.join(", "))
+}
+
+pub fn create_view(
+    table_name: &str,
+    schema_name: &str,
+    table_options: HashMap<String, String>,
+) -> Result<String> {
+    let values = format_values(
+        table_options
+            .get(MemoryOption::Values.as_ref())
+            .ok_or_else(|| DuckdbOptionError::missing(MemoryOption::Values, "formats/memory"))?,
+    )?;
+
+    let default_select = "*".to_string();
+    let select = table_options
+        .get(MemoryOption::Select.as_ref())
+        .unwrap_or(&default_select);
+
+    Ok(format!("CREATE VIEW IF NOT EXISTS {schema_name}.{table_name} AS SELECT {select} FROM (VALUES {values})"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use duckdb::Connection;
+
+    #[test]
+    fn test_create_memory_view() {
+        let table_name = "test";
+        let schema_name = "main";
+        let table_options = HashMap::from([(
+            MemoryOption::Values.as_ref().to_string(),
+            "[[1, 'a'], [2, 'b]'], [3, [4, 5]]]".to_string(),
+        )]);
+
+        let expected = "CREATE VIEW IF NOT EXISTS main.test AS SELECT * FROM (VALUES (1, 'a'), (2, 'b]'), (3, [4, 5]))";
+        let actual = create_view(table_name, schema_name, table_options).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(&actual, []).unwrap();
+    }
+
+    #[test]
+    fn test_create_memory_view_invalid_values() {
+        for values in ["1, 2", "[1, 2]", "[[1, 2]", "[]"] {
+            let table_options = HashMap::from([(
+                MemoryOption::Values.as_ref().to_string(),
+                values.to_string(),
+            )]);
+
+            assert!(create_view("test", "main", table_options).is_err());
+        }
+    }
+}
@@ -15,12 +15,17 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+pub mod avro;
+pub mod blob;
 pub mod connection;
 pub mod csv;
 pub mod delta;
+pub mod error;
 pub mod iceberg;
 pub mod json;
+pub mod memory;
 pub mod parquet;
 pub mod secret;
 pub mod spatial;
+pub mod text;
 pub mod utils;
@@ -15,26 +15,39 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use std::collections::HashMap;
 use strum::{AsRefStr, EnumIter};
 
 use crate::fdw::base::OptionValidator;
 
+use super::error::DuckdbOptionError;
 use super::utils;
 
 #[derive(EnumIter, AsRefStr, PartialEq, Debug)]
 #[strum(serialize_all = "snake_case")]
 pub enum ParquetOption {
     BinaryAsString,
+    ColumnMapping,
+    ColumnTypes,
+    ComputedColumns,
+    DisableGlob,
     FileName,
     FileRowNumber,
     Files,
+    FilesFrom,
     HivePartitioning,
     HiveTypes,
     HiveTypesAutocast,
+    IgnoreErrors,
+    LowercaseNames,
+    MapByFieldId,
+    Names,
+    NullColumnType,
     PreserveCasing,
+    RowGroups,
     UnionByName,
+    UnionByNameTypeCoercion,
     Select,
     // TODO: EncryptionConfig
 }
@@ -43,29 +56,189 @@ impl OptionValidator for ParquetOption {
     fn is_required(&self) -> bool {
         match self {
             Self::BinaryAsString => false,
+            // Applied when the view is registered, since it needs the declared columns
+            Self::ColumnMapping => false,
+            Self::ColumnTypes => false,
+            Self::ComputedColumns => false,
+            // Applied when the view is registered, since it rewrites the files option
+            Self::DisableGlob => false,
             Self::FileName => false,
             Self::FileRowNumber => false,
-            Self::Files => true,
+            // Either files or files_from must be set, which is checked when the view is created
+            Self::Files => false,
+            Self::FilesFrom => false,
             Self::HivePartitioning => false,
             Self::HiveTypes => false,
             Self::HiveTypesAutocast => false,
+            // Applied when the view is registered, since it needs to read the files
+            Self::IgnoreErrors => false,
+            // Applied when the view is registered, since it needs the columns of the files
+            Self::LowercaseNames => false,
+            // Applied when the view is registered, since it needs the field ids of the files
+            Self::MapByFieldId => false,
+            Self::Names => false,
+            // Applied when the view is registered, since it needs the statistics of the files
+            Self::NullColumnType => false,
             Self::PreserveCasing => false,
+            // Applied when the view is registered, since it needs the row groups of the file
+            Self::RowGroups => false,
             Self::Select => false,
             Self::UnionByName => false,
+            // Applied when the view is registered, since it needs the types of the files
+            Self::UnionByNameTypeCoercion => false,
         }
     }
 }
 
-pub fn create_view(
-    table_name: &str,
-    schema_name: &str,
-    table_options: HashMap<String, String>,
-) -> Result<String> {
-    let files = Some(utils::format_csv(
-        table_options
-            .get(ParquetOption::Files.as_ref())
-            .ok_or_else(|| anyhow!("files option is required"))?,
-    ));
+// Extensions of files that are compressed as a whole, which read_parquet can't decompress
+const WHOLE_FILE_COMPRESSION_EXTENSIONS: [&str; 7] =
+    [".gz", ".zst", ".bz2", ".xz", ".lz4", ".zip", ".snappy"];
+
+// Parquet files are compressed internally, so a compressed parquet file would otherwise fail with
+// an error about a missing magic number
+fn check_whole_file_compression(files: &str) -> Result<()> {
+    for file in files.split(',').map(str::trim) {
+        let lowercase_file = file.to_lowercase();
+        if let Some(extension) = WHOLE_FILE_COMPRESSION_EXTENSIONS
+            .iter()
+            .find(|extension| lowercase_file.ends_with(*extension))
+        {
+            return Err(DuckdbOptionError::invalid(
+                ParquetOption::Files,
+                format!("{file} is compressed with {extension}, but parquet files are compressed internally and can't be read from a compressed file, decompress it first"),
+                "formats/parquet",
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+// The width of the signed integer type that can hold every value of an integer type
+fn integer_width(column_type: &str) -> Option<u8> {
+    match column_type {
+        "TINYINT" => Some(8),
+        "SMALLINT" | "UTINYINT" => Some(16),
+        "INTEGER" | "USMALLINT" => Some(32),
+        "BIGINT" | "UINTEGER" => Some(64),
+        "HUGEINT" | "UBIGINT" => Some(128),
+        _ => None,
+    }
+}
+
+// The narrowest DuckDB type that both types can be cast to without losing values. Integers widen
+// to larger integers, integers and floats to DOUBLE, and any other mix to VARCHAR.
+pub fn widest_type(left: &str, right: &str) -> String {
+    const UNSIGNED_TYPES: [&str; 4] = ["UTINYINT", "USMALLINT", "UINTEGER", "UBIGINT"];
+    const FLOAT_TYPES: [&str; 2] = ["FLOAT", "DOUBLE"];
+
+    if left == right {
+        return left.to_string();
+    }
+
+    match (integer_width(left), integer_width(right)) {
+        (Some(_), Some(_)) if UNSIGNED_TYPES.contains(&left) && UNSIGNED_TYPES.contains(&right) => {
+            let left_index = UNSIGNED_TYPES.iter().position(|t| *t == left);
+            let right_index = UNSIGNED_TYPES.iter().position(|t| *t == right);
+            UNSIGNED_TYPES[left_index.max(right_index).unwrap_or_default()].to_string()
+        }
+        (Some(left_width), Some(right_width)) => match left_width.max(right_width) {
+            8 => "TINYINT",
+            16 => "SMALLINT",
+            32 => "INTEGER",
+            64 => "BIGINT",
+            _ => "HUGEINT",
+        }
+        .to_string(),
+        _ if FLOAT_TYPES.contains(&left) || FLOAT_TYPES.contains(&right) => {
+            let numeric = |column_type: &str| {
+                FLOAT_TYPES.contains(&column_type) || integer_width(column_type).is_some()
+            };
+            match numeric(left) && numeric(right) {
+                true => "DOUBLE".to_string(),
+                false => "VARCHAR".to_string(),
+            }
+        }
+        _ => "VARCHAR".to_string(),
+    }
+}
+
+// Set when the view is registered with row_groups, to a filter on file_row_number that selects
+// the rows of the requested row groups
+pub const ROW_GROUP_FILTER: &str = "row_group_filter";
+
+// Parses a list of row group ids and inclusive ranges of them, e.g. '0-2, 5'
+pub fn parse_row_groups(row_groups: &str) -> Result<Vec<u64>> {
+    let invalid = || {
+        DuckdbOptionError::invalid(
+            ParquetOption::RowGroups,
+            format!("{row_groups} is not a list of row group ids and ranges like 0-2, 5"),
+            "formats/parquet",
+        )
+    };
+
+    let mut row_group_ids = vec![];
+    for part in row_groups.split(',').map(str::trim) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let start = start.trim().parse::<u64>().map_err(|_| invalid())?;
+                let end = end.trim().parse::<u64>().map_err(|_| invalid())?;
+                if start > end {
+                    return Err(invalid().into());
+                }
+                row_group_ids.extend(start..=end);
+            }
+            None => row_group_ids.push(part.parse::<u64>().map_err(|_| invalid())?),
+        }
+    }
+    Ok(row_group_ids)
+}
+
+// Set when the view is registered with map_by_field_id, to the schema that maps the field ids of
+// the files to the declared columns
+pub const FIELD_ID_SCHEMA: &str = "field_id_schema";
+
+fn format_files(table_options: &HashMap<String, String>) -> Result<String> {
+    match (
+        table_options.get(ParquetOption::Files.as_ref()),
+        table_options.get(ParquetOption::FilesFrom.as_ref()),
+    ) {
+        (Some(files), None) => {
+            check_whole_file_compression(files)?;
+            Ok(utils::format_csv(files))
+        }
+        (None, Some(manifest)) => utils::format_files_from(manifest),
+        (Some(_), Some(_)) => Err(DuckdbOptionError::invalid(
+            ParquetOption::FilesFrom,
+            "files and files_from options cannot both be set",
+            "formats/parquet",
+        )
+        .into()),
+        (None, None) => {
+            Err(DuckdbOptionError::missing("files or files_from", "formats/parquet").into())
+        }
+    }
+}
+
+// Lists the columns of the files of a table that have field ids, with the file they are in
+pub fn describe_field_ids(table_options: &HashMap<String, String>) -> Result<String> {
+    Ok(format!(
+        "SELECT file_name, name, field_id FROM parquet_schema({}) WHERE field_id IS NOT NULL",
+        format_files(table_options)?
+    ))
+}
+
+// The columns whose values are null in every row group of the files, going by their statistics
+pub fn describe_null_columns(table_options: &HashMap<String, String>) -> Result<String> {
+    Ok(format!(
+        "SELECT path_in_schema FROM parquet_metadata({}) GROUP BY path_in_schema HAVING count(stats_null_count) = count(*) AND sum(stats_null_count) = sum(num_values)",
+        format_files(table_options)?
+    ))
+}
+
+// The read_parquet call that reads the files of a table, before its columns are renamed or selected
+fn read_parquet(table_options: &HashMap<String, String>) -> Result<String> {
+    let files = Some(format_files(table_options)?);
 
     let binary_as_string = table_options
         .get(ParquetOption::BinaryAsString.as_ref())
@@ -75,9 +248,17 @@ pub fn create_view(
         .get(ParquetOption::FileName.as_ref())
         .map(|option| format!("filename = {option}"));
 
-    let file_row_number = table_options
+    // Row groups are selected by the row numbers they span, which needs the row numbers to be read
+    let row_group_filter = table_options.get(ROW_GROUP_FILTER);
+    let user_file_row_number = table_options
         .get(ParquetOption::FileRowNumber.as_ref())
-        .map(|option| format!("file_row_number = {option}"));
+        .is_some_and(|option| option.eq_ignore_ascii_case("true"));
+    let file_row_number = match row_group_filter {
+        Some(_) => Some("file_row_number = true".to_string()),
+        None => table_options
+            .get(ParquetOption::FileRowNumber.as_ref())
+            .map(|option| format!("file_row_number = {option}")),
+    };
 
     let hive_partitioning = table_options
         .get(ParquetOption::HivePartitioning.as_ref())
@@ -95,6 +276,10 @@ pub fn create_view(
         .get(ParquetOption::UnionByName.as_ref())
         .map(|option| format!("union_by_name = {option}"));
 
+    let field_id_schema = table_options
+        .get(FIELD_ID_SCHEMA)
+        .map(|schema| format!("schema = {schema}"));
+
     let create_parquet_str = [
         files,
         binary_as_string,
@@ -104,18 +289,76 @@ pub fn create_view(
         hive_types,
         hive_types_autocast,
         union_by_name,
+        field_id_schema,
     ]
     .into_iter()
     .flatten()
     .collect::<Vec<String>>()
     .join(", ");
 
+    match row_group_filter {
+        Some(filter) if user_file_row_number => Ok(format!(
+            "(SELECT * FROM read_parquet({create_parquet_str}) WHERE {filter})"
+        )),
+        Some(filter) => Ok(format!(
+            "(SELECT * EXCLUDE (file_row_number) FROM read_parquet({create_parquet_str}) WHERE {filter})"
+        )),
+        None => Ok(format!("read_parquet({create_parquet_str})")),
+    }
+}
+
+// Describes the columns of the files of a table, e.g. to name them before the view is created
+pub fn describe_files(table_options: &HashMap<String, String>) -> Result<String> {
+    Ok(format!(
+        "DESCRIBE SELECT * FROM {}",
+        read_parquet(table_options)?
+    ))
+}
+
+pub fn create_view(
+    table_name: &str,
+    schema_name: &str,
+    table_options: HashMap<String, String>,
+) -> Result<String> {
+    let files = read_parquet(&table_options)?;
+
+    // Renames the columns of the files by position, leaving any columns past the names as-is
+    let names = table_options
+        .get(ParquetOption::Names.as_ref())
+        .map(|option| {
+            option
+                .split(',')
+                .map(|name| format!("\"{}\"", name.trim().replace('"', "\"\"")))
+                .collect::<Vec<String>>()
+                .join(", ")
+        })
+        .map(|names| format!(" AS {table_name}({names})"))
+        .unwrap_or_default();
+
     let default_select = "*".to_string();
     let select = table_options
         .get(ParquetOption::Select.as_ref())
         .unwrap_or(&default_select);
 
-    Ok(format!("CREATE VIEW IF NOT EXISTS {schema_name}.{table_name} AS SELECT {select} FROM read_parquet({create_parquet_str})"))
+    let column_types = table_options
+        .get(ParquetOption::ColumnTypes.as_ref())
+        .map(|option| utils::format_column_types(option, "formats/parquet"))
+        .transpose()?;
+
+    // Computed columns are added after the columns of the files. DuckDB binds the view when it
+    // is created, so expressions that reference columns the files don't have fail the table.
+    let computed_columns = table_options
+        .get(ParquetOption::ComputedColumns.as_ref())
+        .map(|option| utils::format_computed_columns(option))
+        .transpose()?
+        .map(|computed_columns| format!(", {computed_columns}"))
+        .unwrap_or_default();
+
+    match column_types {
+        Some(casts) if *select == default_select => Ok(format!("CREATE VIEW IF NOT EXISTS {schema_name}.{table_name} AS SELECT * REPLACE ({casts}){computed_columns} FROM {files}{names}")),
+        Some(casts) => Ok(format!("CREATE VIEW IF NOT EXISTS {schema_name}.{table_name} AS SELECT * REPLACE ({casts}){computed_columns} FROM (SELECT {select} FROM {files}{names})")),
+        None => Ok(format!("CREATE VIEW IF NOT EXISTS {schema_name}.{table_name} AS SELECT {select}{computed_columns} FROM {files}{names}")),
+    }
 }
 
 #[cfg(test)]
@@ -142,6 +385,82 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_parquet_view_compressed_file() {
+        let table_options = HashMap::from([(
+            ParquetOption::Files.as_ref().to_string(),
+            "/data/a.parquet, /data/b.parquet.GZ".to_string(),
+        )]);
+        let err = create_view("test", "main", table_options).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("/data/b.parquet.GZ is compressed with .gz"));
+    }
+
+    #[test]
+    fn test_create_parquet_view_with_field_id_schema() {
+        let table_options = HashMap::from([
+            (
+                ParquetOption::Files.as_ref().to_string(),
+                "/data/file.parquet".to_string(),
+            ),
+            (
+                FIELD_ID_SCHEMA.to_string(),
+                "MAP {1: {name: 'id', type: 'INTEGER', default_value: NULL}}".to_string(),
+            ),
+        ]);
+        let expected = "CREATE VIEW IF NOT EXISTS main.test AS SELECT * FROM read_parquet('/data/file.parquet', schema = MAP {1: {name: 'id', type: 'INTEGER', default_value: NULL}})";
+        let actual = create_view("test", "main", table_options).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_widest_type() {
+        assert_eq!(widest_type("INTEGER", "BIGINT"), "BIGINT");
+        assert_eq!(widest_type("SMALLINT", "UINTEGER"), "BIGINT");
+        assert_eq!(widest_type("UTINYINT", "USMALLINT"), "USMALLINT");
+        assert_eq!(widest_type("INTEGER", "FLOAT"), "DOUBLE");
+        assert_eq!(widest_type("FLOAT", "DOUBLE"), "DOUBLE");
+        assert_eq!(widest_type("INTEGER", "VARCHAR"), "VARCHAR");
+        assert_eq!(widest_type("DATE", "DATE"), "DATE");
+    }
+
+    #[test]
+    fn test_create_parquet_view_with_row_group_filter() {
+        let table_options = HashMap::from([
+            (
+                ParquetOption::Files.as_ref().to_string(),
+                "/data/file.parquet".to_string(),
+            ),
+            (
+                ROW_GROUP_FILTER.to_string(),
+                "file_row_number BETWEEN 0 AND 99".to_string(),
+            ),
+        ]);
+        let expected = "CREATE VIEW IF NOT EXISTS main.test AS SELECT * FROM (SELECT * EXCLUDE (file_row_number) FROM read_parquet('/data/file.parquet', file_row_number = true) WHERE file_row_number BETWEEN 0 AND 99)";
+        let actual = create_view("test", "main", table_options).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_row_groups() {
+        assert_eq!(parse_row_groups("0-2, 5").unwrap(), vec![0, 1, 2, 5]);
+        assert_eq!(parse_row_groups("3").unwrap(), vec![3]);
+        assert!(parse_row_groups("2-1").is_err());
+        assert!(parse_row_groups("first").is_err());
+    }
+
+    #[test]
+    fn test_create_parquet_view_missing_files() {
+        let err = create_view("test", "main", HashMap::new()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DuckdbOptionError>(),
+            Some(DuckdbOptionError::MissingOption { .. })
+        ));
+    }
+
     #[test]
     fn test_create_parquet_view_multiple_files() {
         let table_name = "test";
@@ -212,4 +531,152 @@ mod tests {
             Err(e) => assert!(e.to_string().contains("file.parquet")),
         }
     }
+
+    #[test]
+    fn test_create_parquet_view_with_column_types() {
+        let table_name = "test";
+        let schema_name = "main";
+        let table_options = HashMap::from([
+            (
+                ParquetOption::Files.as_ref().to_string(),
+                "/data/file.parquet".to_string(),
+            ),
+            (
+                ParquetOption::ColumnTypes.as_ref().to_string(),
+                r#"{"id": "BIGINT", "name": "VARCHAR"}"#.to_string(),
+            ),
+        ]);
+
+        let expected = "CREATE VIEW IF NOT EXISTS main.test AS SELECT * REPLACE (CAST(\"id\" AS BIGINT) AS \"id\", CAST(\"name\" AS VARCHAR) AS \"name\") FROM read_parquet('/data/file.parquet')";
+        let actual = create_view(table_name, schema_name, table_options).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let conn = Connection::open_in_memory().unwrap();
+        match conn.prepare(&actual) {
+            Ok(_) => panic!("invalid parquet file should throw an error"),
+            Err(e) => assert!(e.to_string().contains("file.parquet")),
+        }
+    }
+
+    #[test]
+    fn test_create_parquet_view_with_computed_columns() {
+        let table_name = "test";
+        let schema_name = "main";
+        let table_options = HashMap::from([
+            (
+                ParquetOption::Files.as_ref().to_string(),
+                "/data/file.parquet".to_string(),
+            ),
+            (
+                ParquetOption::ComputedColumns.as_ref().to_string(),
+                r#"{"total": "price * quantity"}"#.to_string(),
+            ),
+        ]);
+
+        let expected = "CREATE VIEW IF NOT EXISTS main.test AS SELECT *, (price * quantity) AS \"total\" FROM read_parquet('/data/file.parquet')";
+        let actual = create_view(table_name, schema_name, table_options).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let table_options = HashMap::from([
+            (
+                ParquetOption::Files.as_ref().to_string(),
+                "/data/file.parquet".to_string(),
+            ),
+            (
+                ParquetOption::ComputedColumns.as_ref().to_string(),
+                r#"{"total": 1}"#.to_string(),
+            ),
+        ]);
+
+        assert!(create_view(table_name, schema_name, table_options).is_err());
+    }
+
+    #[test]
+    fn test_create_parquet_view_with_names() {
+        let table_name = "test";
+        let schema_name = "main";
+        let table_options = HashMap::from([
+            (
+                ParquetOption::Files.as_ref().to_string(),
+                "/data/file.parquet".to_string(),
+            ),
+            (
+                ParquetOption::Names.as_ref().to_string(),
+                "id, name".to_string(),
+            ),
+        ]);
+
+        let expected = "CREATE VIEW IF NOT EXISTS main.test AS SELECT * FROM read_parquet('/data/file.parquet') AS test(\"id\", \"name\")";
+        let actual = create_view(table_name, schema_name, table_options).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let conn = Connection::open_in_memory().unwrap();
+        match conn.prepare(&actual) {
+            Ok(_) => panic!("invalid parquet file should throw an error"),
+            Err(e) => assert!(e.to_string().contains("file.parquet")),
+        }
+    }
+
+    #[test]
+    fn test_create_parquet_view_with_invalid_column_types() {
+        let table_options = HashMap::from([
+            (
+                ParquetOption::Files.as_ref().to_string(),
+                "/data/file.parquet".to_string(),
+            ),
+            (
+                ParquetOption::ColumnTypes.as_ref().to_string(),
+                "id BIGINT".to_string(),
+            ),
+        ]);
+
+        assert!(create_view("test", "main", table_options).is_err());
+    }
+
+    #[test]
+    fn test_create_parquet_view_files_from() {
+        let manifest_path = std::env::temp_dir().join("pg_analytics_test_files_from.txt");
+        std::fs::write(
+            &manifest_path,
+            "/data/file1.parquet\n\n/data/file2.parquet\n",
+        )
+        .unwrap();
+
+        let table_options = HashMap::from([(
+            ParquetOption::FilesFrom.as_ref().to_string(),
+            manifest_path.to_str().unwrap().to_string(),
+        )]);
+
+        let expected = "CREATE VIEW IF NOT EXISTS main.test AS SELECT * FROM read_parquet(['/data/file1.parquet', '/data/file2.parquet'])";
+        let actual = create_view("test", "main", table_options).unwrap();
+        std::fs::remove_file(&manifest_path).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let conn = Connection::open_in_memory().unwrap();
+        match conn.prepare(&actual) {
+            Ok(_) => panic!("invalid parquet file should throw an error"),
+            Err(e) => assert!(e.to_string().contains("file1.parquet")),
+        }
+    }
+
+    #[test]
+    fn test_create_parquet_view_files_and_files_from() {
+        let table_options = HashMap::from([
+            (
+                ParquetOption::Files.as_ref().to_string(),
+                "/data/file.parquet".to_string(),
+            ),
+            (
+                ParquetOption::FilesFrom.as_ref().to_string(),
+                "/data/manifest.txt".to_string(),
+            ),
+        ]);
+
+        assert!(create_view("test", "main", table_options).is_err());
+        assert!(create_view("test", "main", HashMap::new()).is_err());
+    }
 }
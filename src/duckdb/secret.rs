@@ -15,12 +15,14 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{bail, Result};
 use std::collections::HashMap;
-use strum::{AsRefStr, EnumIter};
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
 
 use crate::fdw::base::OptionValidator;
 
+use super::error::DuckdbOptionError;
+
 #[derive(EnumIter, AsRefStr, PartialEq, Debug)]
 #[strum(serialize_all = "snake_case")]
 pub enum UserMappingOptions {
@@ -46,9 +48,12 @@ pub enum UserMappingOptions {
     ClientId,
     ClientSecret,
     ClientCertificatePath,
+    SasToken,
     HttpProxy,
     ProxyUserName,
     ProxyPassword,
+    // Hugging Face
+    Token,
 }
 
 impl OptionValidator for UserMappingOptions {
@@ -73,13 +78,182 @@ impl OptionValidator for UserMappingOptions {
             Self::ClientId => false,
             Self::ClientSecret => false,
             Self::ClientCertificatePath => false,
+            Self::SasToken => false,
             Self::HttpProxy => false,
             Self::ProxyUserName => false,
             Self::ProxyPassword => false,
+            Self::Token => false,
         }
     }
 }
 
+// Secret types that DuckDB supports with the extensions that are loaded
+const SECRET_TYPES: [&str; 6] = ["S3", "GCS", "R2", "AZURE", "HUGGINGFACE", "HTTP"];
+
+// Credentials that can be set on a foreign table to override those of the user mapping
+#[derive(EnumIter, AsRefStr, PartialEq, Debug)]
+#[strum(serialize_all = "snake_case")]
+pub enum TableSecretOptions {
+    KeyId,
+    Secret,
+    Region,
+    SessionToken,
+    Endpoint,
+    UrlStyle,
+    UseSsl,
+    Token,
+}
+
+impl TableSecretOptions {
+    // Every role can read the options of a foreign table, so only superusers may set the options
+    // that hold credentials on one
+    pub fn is_credential(&self) -> bool {
+        matches!(
+            self,
+            Self::KeyId | Self::Secret | Self::SessionToken | Self::Token
+        )
+    }
+}
+
+// Builds the options of the secrets used to read a foreign table. Options set on the table take
+// precedence over those of the user mapping. Unless the user mapping sets its own scope, there is
+// a secret scoped to each directory of the table's files so that secrets of different tables
+// don't conflict, while tables over several buckets or prefixes have credentials for all of them.
+pub fn scoped_secret_options(
+    table_options: &HashMap<String, String>,
+    user_mapping_options: HashMap<String, String>,
+) -> Result<Vec<HashMap<String, String>>> {
+    if let Some(secret_options) = table_secret_options(table_options, &user_mapping_options)? {
+        return Ok(secret_options);
+    }
+
+    if user_mapping_options.is_empty() {
+        return Ok(vec![]);
+    }
+
+    if user_mapping_options.contains_key(UserMappingOptions::Scope.as_ref()) {
+        return Ok(vec![user_mapping_options]);
+    }
+
+    // The secret stays unscoped if any file can't be scoped, e.g. if it is local
+    let scopes = match table_options.get("files").map(|files| files_scopes(files)) {
+        Some(Ok(scopes)) if !scopes.is_empty() => scopes,
+        _ => return Ok(vec![user_mapping_options]),
+    };
+
+    Ok(scopes
+        .into_iter()
+        .map(|scope| {
+            let mut secret_options = user_mapping_options.clone();
+            secret_options.insert(
+                UserMappingOptions::Scope.as_ref().to_string(),
+                format!("'{}'", scope.replace('\'', "''")),
+            );
+            secret_options
+        })
+        .collect())
+}
+
+// Builds the options of secrets scoped to a foreign table's files from the table's secret
+// options, on top of those of the user mapping, or returns None if the table does not set any
+fn table_secret_options(
+    table_options: &HashMap<String, String>,
+    user_mapping_options: &HashMap<String, String>,
+) -> Result<Option<Vec<HashMap<String, String>>>> {
+    let table_secret_options = TableSecretOptions::iter()
+        .filter_map(|option| {
+            table_options
+                .get(option.as_ref())
+                .map(|value| (option.as_ref().to_string(), value.clone()))
+        })
+        .collect::<HashMap<String, String>>();
+
+    if table_secret_options.is_empty() {
+        return Ok(None);
+    }
+
+    let files = table_options
+        .get("files")
+        .ok_or_else(|| DuckdbOptionError::missing("files", "object_stores/s3"))?;
+
+    let mut secrets = vec![];
+    for scope in files_scopes(files)? {
+        let secret_type = scope_secret_type(&scope)?;
+
+        // A table that only overrides e.g. the region keeps the user mapping's credentials, as
+        // long as they are for the same kind of store
+        let mut secret_options = match user_mapping_options.get(UserMappingOptions::Type.as_ref()) {
+            Some(user_mapping_type) if user_mapping_type.eq_ignore_ascii_case(secret_type) => {
+                user_mapping_options.clone()
+            }
+            _ => HashMap::new(),
+        };
+        secret_options.extend(table_secret_options.clone());
+        secret_options.insert(
+            UserMappingOptions::Type.as_ref().to_string(),
+            secret_type.to_string(),
+        );
+        secret_options.insert(
+            UserMappingOptions::Scope.as_ref().to_string(),
+            format!("'{}'", scope.replace('\'', "''")),
+        );
+        secrets.push(secret_options);
+    }
+
+    Ok(Some(secrets))
+}
+
+// The type of the secret for the scheme of a scope
+fn scope_secret_type(scope: &str) -> Result<&'static str> {
+    match scope.split_once("://").map(|(scheme, _)| scheme) {
+        Some("s3") | Some("s3a") | Some("s3n") => Ok("S3"),
+        Some("gs") | Some("gcs") => Ok("GCS"),
+        Some("r2") => Ok("R2"),
+        Some("hf") => Ok("HUGGINGFACE"),
+        _ => Err(DuckdbOptionError::invalid(
+            "files",
+            "table credentials are only supported for S3, GCS, R2 and Hugging Face paths",
+            "object_stores/s3",
+        )
+        .into()),
+    }
+}
+
+// The distinct directories of the files, up to any glob, e.g. s3://bucket/folder/ for
+// s3://bucket/folder/*.parquet
+fn files_scopes(files: &str) -> Result<Vec<String>> {
+    let mut scopes: Vec<String> = vec![];
+
+    for file in files
+        .split(',')
+        .map(str::trim)
+        .filter(|file| !file.is_empty())
+    {
+        let literal_prefix = file
+            .split(|c| matches!(c, '*' | '?' | '[' | '{'))
+            .next()
+            .unwrap_or_default();
+
+        let scope = match literal_prefix.rfind('/') {
+            Some(index) if literal_prefix.contains("://") => literal_prefix[..=index].to_string(),
+            _ => {
+                return Err(DuckdbOptionError::invalid(
+                    "files",
+                    format!("table credentials are only supported for remote files, got {file}"),
+                    "object_stores/s3",
+                )
+                .into())
+            }
+        };
+
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+
+    Ok(scopes)
+}
+
 pub fn create_secret(
     secret_name: &str,
     user_mapping_options: HashMap<String, String>,
@@ -88,13 +262,21 @@ pub fn create_secret(
         bail!("create_secret requires user mapping options")
     }
 
-    let secret_type = Some(format!(
-        "TYPE {}",
-        user_mapping_options
-            .get(UserMappingOptions::Type.as_ref())
-            .ok_or_else(|| anyhow!("type option required for USER MAPPING"))?
-            .as_str()
-    ));
+    let secret_type = user_mapping_options
+        .get(UserMappingOptions::Type.as_ref())
+        .ok_or_else(|| DuckdbOptionError::missing(UserMappingOptions::Type, "object_stores/s3"))?;
+    if !SECRET_TYPES.contains(&secret_type.to_uppercase().as_str()) {
+        return Err(DuckdbOptionError::invalid(
+            UserMappingOptions::Type,
+            format!(
+                "{secret_type} is not a secret type, valid types are {}",
+                SECRET_TYPES.join(", ")
+            ),
+            "object_stores/s3",
+        )
+        .into());
+    }
+    let secret_type = Some(format!("TYPE {secret_type}"));
 
     let provider = user_mapping_options
         .get(UserMappingOptions::Provider.as_ref())
@@ -144,12 +326,43 @@ pub fn create_secret(
         .get(UserMappingOptions::AccountId.as_ref())
         .map(|account_id| format!("ACCOUNT_ID '{}'", account_id));
 
-    let connection_string = user_mapping_options
-        .get(UserMappingOptions::ConnectionString.as_ref())
-        .map(|connection_string| format!("CONNECTION_STRING '{}'", connection_string));
+    // DuckDB reads SAS tokens from a connection string, which also names the account's endpoint
+    let sas_token = user_mapping_options.get(UserMappingOptions::SasToken.as_ref());
+    let connection_string = match (
+        user_mapping_options.get(UserMappingOptions::ConnectionString.as_ref()),
+        sas_token,
+    ) {
+        (Some(_), Some(_)) => {
+            return Err(DuckdbOptionError::invalid(
+                UserMappingOptions::SasToken,
+                "sas_token can't be combined with connection_string",
+                "object_stores/azure",
+            )
+            .into())
+        }
+        (Some(connection_string), None) => {
+            Some(format!("CONNECTION_STRING '{}'", connection_string))
+        }
+        (None, Some(sas_token)) => {
+            let account_name = user_mapping_options
+                .get(UserMappingOptions::AccountName.as_ref())
+                .ok_or_else(|| {
+                    DuckdbOptionError::missing(
+                        UserMappingOptions::AccountName,
+                        "object_stores/azure",
+                    )
+                })?;
+            Some(format!(
+                "CONNECTION_STRING 'BlobEndpoint=https://{account_name}.blob.core.windows.net;SharedAccessSignature={}'",
+                sas_token.trim_start_matches('?')
+            ))
+        }
+        (None, None) => None,
+    };
 
     let account_name = user_mapping_options
         .get(UserMappingOptions::AccountName.as_ref())
+        .filter(|_| sas_token.is_none())
         .map(|account_name| format!("ACCOUNT_NAME '{}'", account_name));
 
     let tenant_id = user_mapping_options
@@ -182,6 +395,10 @@ pub fn create_secret(
         .get(UserMappingOptions::ProxyPassword.as_ref())
         .map(|proxy_password| format!("PROXY_PASSWORD '{}'", proxy_password));
 
+    let token = user_mapping_options
+        .get(UserMappingOptions::Token.as_ref())
+        .map(|token| format!("TOKEN '{}'", token));
+
     let secret_string = vec![
         secret_type,
         provider,
@@ -205,6 +422,7 @@ pub fn create_secret(
         http_proxy,
         proxy_user_name,
         proxy_password,
+        token,
     ]
     .into_iter()
     .flatten()
@@ -212,7 +430,8 @@ pub fn create_secret(
     .join(", ");
 
     Ok(format!(
-        "CREATE OR REPLACE SECRET {secret_name} ({secret_string})"
+        "CREATE OR REPLACE SECRET \"{}\" ({secret_string})",
+        secret_name.replace('"', "\"\"")
     ))
 }
 
@@ -269,7 +488,7 @@ mod tests {
             ),
         ]);
 
-        let expected = "CREATE OR REPLACE SECRET s3_secret (TYPE S3, PROVIDER CONFIG, KEY_ID 'key_id', SECRET 'secret', REGION 'us-west-2', SESSION_TOKEN 'session_token', ENDPOINT 's3.amazonaws.com', URL_STYLE 'vhost', USE_SSL true, URL_COMPATIBILITY_MODE true)";
+        let expected = "CREATE OR REPLACE SECRET \"s3_secret\" (TYPE S3, PROVIDER CONFIG, KEY_ID 'key_id', SECRET 'secret', REGION 'us-west-2', SESSION_TOKEN 'session_token', ENDPOINT 's3.amazonaws.com', URL_STYLE 'vhost', USE_SSL true, URL_COMPATIBILITY_MODE true)";
         let actual = create_secret(secret_name, user_mapping_options).unwrap();
 
         assert_eq!(expected, actual);
@@ -331,7 +550,7 @@ mod tests {
             ),
         ]);
 
-        let expected = "CREATE OR REPLACE SECRET azure_secret (TYPE AZURE, PROVIDER CONFIG, CONNECTION_STRING 'connection_string', HTTP_PROXY 'http_proxy', PROXY_USER_NAME 'proxy_user_name', PROXY_PASSWORD 'proxy_password')";
+        let expected = "CREATE OR REPLACE SECRET \"azure_secret\" (TYPE AZURE, PROVIDER CONFIG, CONNECTION_STRING 'connection_string', HTTP_PROXY 'http_proxy', PROXY_USER_NAME 'proxy_user_name', PROXY_PASSWORD 'proxy_password')";
         let actual = create_secret(secret_name, user_mapping_options).unwrap();
 
         assert_eq!(expected, actual);
@@ -349,11 +568,382 @@ mod tests {
             "INVALID".to_string(),
         )]);
 
-        let actual = create_secret(secret_name, user_mapping_options).unwrap();
+        let err = create_secret(secret_name, user_mapping_options).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DuckdbOptionError>(),
+            Some(DuckdbOptionError::InvalidValue { option, .. }) if option == "type"
+        ));
+    }
+
+    #[test]
+    fn test_create_type_missing() {
+        let user_mapping_options = HashMap::from([(
+            UserMappingOptions::KeyId.as_ref().to_string(),
+            "key_id".to_string(),
+        )]);
+
+        let err = create_secret("missing_type_secret", user_mapping_options).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DuckdbOptionError>(),
+            Some(&DuckdbOptionError::missing(
+                UserMappingOptions::Type,
+                "object_stores/s3"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_table_secret_options() {
+        let table_options = HashMap::from([
+            (
+                "files".to_string(),
+                "s3://bucket/folder/*.parquet".to_string(),
+            ),
+            (
+                TableSecretOptions::KeyId.as_ref().to_string(),
+                "key_id".to_string(),
+            ),
+            (
+                TableSecretOptions::Secret.as_ref().to_string(),
+                "secret".to_string(),
+            ),
+        ]);
+
+        let secret_options = scoped_secret_options(&table_options, HashMap::new())
+            .unwrap()
+            .remove(0);
+        let expected = "CREATE OR REPLACE SECRET \"table_secret\" (TYPE S3, SCOPE 's3://bucket/folder/', KEY_ID 'key_id', SECRET 'secret')";
+        let actual = create_secret("table_secret", secret_options).unwrap();
+
+        assert_eq!(expected, actual);
+
         let conn = Connection::open_in_memory().unwrap();
-        match conn.prepare(&actual) {
-            Ok(_) => panic!("invalid secret type should throw an error"),
-            Err(e) => assert!(e.to_string().contains("invalid")),
-        }
+        let mut statement = conn.prepare(&actual).unwrap();
+        statement.execute([]).unwrap();
+    }
+
+    #[test]
+    fn test_table_secret_options_merge_user_mapping() {
+        let table_options = HashMap::from([
+            (
+                "files".to_string(),
+                "s3://bucket/folder/*.parquet".to_string(),
+            ),
+            (
+                TableSecretOptions::Region.as_ref().to_string(),
+                "eu-west-1".to_string(),
+            ),
+        ]);
+        let user_mapping_options = HashMap::from([
+            (
+                UserMappingOptions::Type.as_ref().to_string(),
+                "S3".to_string(),
+            ),
+            (
+                UserMappingOptions::KeyId.as_ref().to_string(),
+                "key_id".to_string(),
+            ),
+            (
+                UserMappingOptions::Secret.as_ref().to_string(),
+                "secret".to_string(),
+            ),
+            (
+                UserMappingOptions::Region.as_ref().to_string(),
+                "us-east-1".to_string(),
+            ),
+        ]);
+
+        let secret_options = scoped_secret_options(&table_options, user_mapping_options.clone())
+            .unwrap()
+            .remove(0);
+        let expected = "CREATE OR REPLACE SECRET \"table_secret\" (TYPE S3, SCOPE 's3://bucket/folder/', KEY_ID 'key_id', SECRET 'secret', REGION 'eu-west-1')";
+        assert_eq!(
+            expected,
+            create_secret("table_secret", secret_options).unwrap()
+        );
+
+        // The credentials of a user mapping for another kind of store are not used
+        let table_options = HashMap::from([
+            ("files".to_string(), "gs://bucket/*.parquet".to_string()),
+            (
+                TableSecretOptions::Region.as_ref().to_string(),
+                "eu-west-1".to_string(),
+            ),
+        ]);
+        let secret_options = scoped_secret_options(&table_options, user_mapping_options)
+            .unwrap()
+            .remove(0);
+        assert!(!secret_options.contains_key(UserMappingOptions::KeyId.as_ref()));
+    }
+
+    #[test]
+    fn test_secret_quoting() {
+        let table_options = HashMap::from([
+            (
+                "files".to_string(),
+                "s3://bucket/it's/*.parquet".to_string(),
+            ),
+            (
+                TableSecretOptions::KeyId.as_ref().to_string(),
+                "key_id".to_string(),
+            ),
+        ]);
+
+        let secret_options = scoped_secret_options(&table_options, HashMap::new())
+            .unwrap()
+            .remove(0);
+        let expected = "CREATE OR REPLACE SECRET \"my \"\"schema\"\"_table_secret\" (TYPE S3, SCOPE 's3://bucket/it''s/', KEY_ID 'key_id')";
+        let actual = create_secret("my \"schema\"_table_secret", secret_options).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let conn = Connection::open_in_memory().unwrap();
+        let mut statement = conn.prepare(&actual).unwrap();
+        statement.execute([]).unwrap();
+    }
+
+    #[test]
+    fn test_table_secret_options_local_files() {
+        let table_options = HashMap::from([
+            ("files".to_string(), "/data/file.parquet".to_string()),
+            (
+                TableSecretOptions::KeyId.as_ref().to_string(),
+                "key_id".to_string(),
+            ),
+        ]);
+
+        assert!(table_secret_options(&table_options, &HashMap::new()).is_err());
+
+        let table_options =
+            HashMap::from([("files".to_string(), "/data/file.parquet".to_string())]);
+        assert!(table_secret_options(&table_options, &HashMap::new())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_huggingface_secret() {
+        let table_options = HashMap::from([(
+            "files".to_string(),
+            "hf://datasets/owner/name/*.parquet".to_string(),
+        )]);
+        let user_mapping_options = HashMap::from([
+            (
+                UserMappingOptions::Type.as_ref().to_string(),
+                "HUGGINGFACE".to_string(),
+            ),
+            (
+                UserMappingOptions::Token.as_ref().to_string(),
+                "hf_token".to_string(),
+            ),
+        ]);
+
+        let secret_options = scoped_secret_options(&table_options, user_mapping_options)
+            .unwrap()
+            .remove(0);
+        let expected = "CREATE OR REPLACE SECRET \"hf_secret\" (TYPE HUGGINGFACE, SCOPE 'hf://datasets/owner/name/', TOKEN 'hf_token')";
+        let actual = create_secret("hf_secret", secret_options).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let conn = Connection::open_in_memory().unwrap();
+        let mut statement = conn.prepare(&actual).unwrap();
+        statement.execute([]).unwrap();
+
+        // A token set on the table creates a Hugging Face secret without any S3 options
+        let mut table_options = table_options;
+        table_options.insert(
+            TableSecretOptions::Token.as_ref().to_string(),
+            "hf_token".to_string(),
+        );
+        let secret_options = scoped_secret_options(&table_options, HashMap::new())
+            .unwrap()
+            .remove(0);
+        let actual = create_secret("hf_secret", secret_options).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_scoped_user_mapping_secret() {
+        let table_options = HashMap::from([(
+            "files".to_string(),
+            "s3://bucket/folder/file.parquet".to_string(),
+        )]);
+        let user_mapping_options = HashMap::from([
+            (
+                UserMappingOptions::Type.as_ref().to_string(),
+                "S3".to_string(),
+            ),
+            (
+                UserMappingOptions::KeyId.as_ref().to_string(),
+                "key_id".to_string(),
+            ),
+        ]);
+
+        let secret_options = scoped_secret_options(&table_options, user_mapping_options.clone())
+            .unwrap()
+            .remove(0);
+        let expected = "CREATE OR REPLACE SECRET \"table_secret\" (TYPE S3, SCOPE 's3://bucket/folder/', KEY_ID 'key_id')";
+        let actual = create_secret("table_secret", secret_options).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let mut user_mapping_options = user_mapping_options;
+        user_mapping_options.insert(
+            UserMappingOptions::Scope.as_ref().to_string(),
+            "'s3://bucket'".to_string(),
+        );
+        let secret_options = scoped_secret_options(&table_options, user_mapping_options)
+            .unwrap()
+            .remove(0);
+        let expected =
+            "CREATE OR REPLACE SECRET \"table_secret\" (TYPE S3, SCOPE 's3://bucket', KEY_ID 'key_id')";
+        let actual = create_secret("table_secret", secret_options).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_secrets_for_multiple_prefixes() {
+        let table_options = HashMap::from([
+            (
+                "files".to_string(),
+                "s3://bucket/a/*.parquet, s3://bucket/a/file.parquet, gs://other/b/file.parquet"
+                    .to_string(),
+            ),
+            (
+                TableSecretOptions::KeyId.as_ref().to_string(),
+                "key_id".to_string(),
+            ),
+        ]);
+
+        let actual = scoped_secret_options(&table_options, HashMap::new())
+            .unwrap()
+            .into_iter()
+            .map(|secret_options| create_secret("table_secret", secret_options).unwrap())
+            .collect::<Vec<String>>();
+        let expected = vec![
+            "CREATE OR REPLACE SECRET \"table_secret\" (TYPE S3, SCOPE 's3://bucket/a/', KEY_ID 'key_id')",
+            "CREATE OR REPLACE SECRET \"table_secret\" (TYPE GCS, SCOPE 'gs://other/b/', KEY_ID 'key_id')",
+        ];
+
+        assert_eq!(expected, actual);
+
+        let table_options = HashMap::from([(
+            "files".to_string(),
+            "s3://bucket/a/*.parquet,s3://bucket/b/*.parquet".to_string(),
+        )]);
+        let user_mapping_options = HashMap::from([
+            (
+                UserMappingOptions::Type.as_ref().to_string(),
+                "S3".to_string(),
+            ),
+            (
+                UserMappingOptions::KeyId.as_ref().to_string(),
+                "key_id".to_string(),
+            ),
+        ]);
+
+        let actual = scoped_secret_options(&table_options, user_mapping_options.clone())
+            .unwrap()
+            .into_iter()
+            .map(|secret_options| create_secret("table_secret", secret_options).unwrap())
+            .collect::<Vec<String>>();
+        let expected = vec![
+            "CREATE OR REPLACE SECRET \"table_secret\" (TYPE S3, SCOPE 's3://bucket/a/', KEY_ID 'key_id')",
+            "CREATE OR REPLACE SECRET \"table_secret\" (TYPE S3, SCOPE 's3://bucket/b/', KEY_ID 'key_id')",
+        ];
+
+        assert_eq!(expected, actual);
+
+        // A local file among the files leaves a single unscoped secret
+        let table_options = HashMap::from([(
+            "files".to_string(),
+            "s3://bucket/a/*.parquet,/data/file.parquet".to_string(),
+        )]);
+        let actual = scoped_secret_options(&table_options, user_mapping_options).unwrap();
+
+        assert_eq!(actual.len(), 1);
+        assert!(!actual[0].contains_key(UserMappingOptions::Scope.as_ref()));
+    }
+
+    #[test]
+    fn test_azure_abfss_secret() {
+        let table_options = HashMap::from([(
+            "files".to_string(),
+            "abfss://container@account.dfs.core.windows.net/path/*.parquet".to_string(),
+        )]);
+        let user_mapping_options = HashMap::from([
+            (
+                UserMappingOptions::Type.as_ref().to_string(),
+                "AZURE".to_string(),
+            ),
+            (
+                UserMappingOptions::ConnectionString.as_ref().to_string(),
+                "connection_string".to_string(),
+            ),
+        ]);
+
+        let secret_options = scoped_secret_options(&table_options, user_mapping_options)
+            .unwrap()
+            .remove(0);
+        let expected = "CREATE OR REPLACE SECRET \"table_secret\" (TYPE AZURE, SCOPE 'abfss://container@account.dfs.core.windows.net/path/', CONNECTION_STRING 'connection_string')";
+        let actual = create_secret("table_secret", secret_options).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let conn = Connection::open_in_memory().unwrap();
+        let mut statement = conn.prepare(&actual).unwrap();
+        statement.execute([]).unwrap();
+    }
+
+    #[test]
+    fn test_create_azure_sas_token_secret() {
+        let user_mapping_options = HashMap::from([
+            (
+                UserMappingOptions::Type.as_ref().to_string(),
+                "AZURE".to_string(),
+            ),
+            (
+                UserMappingOptions::AccountName.as_ref().to_string(),
+                "account".to_string(),
+            ),
+            (
+                UserMappingOptions::SasToken.as_ref().to_string(),
+                "?sv=2022-11-02&ss=b&srt=co&sp=rl&sig=signature".to_string(),
+            ),
+        ]);
+
+        let expected = "CREATE OR REPLACE SECRET \"sas_secret\" (TYPE AZURE, CONNECTION_STRING 'BlobEndpoint=https://account.blob.core.windows.net;SharedAccessSignature=sv=2022-11-02&ss=b&srt=co&sp=rl&sig=signature')";
+        let actual = create_secret("sas_secret", user_mapping_options.clone()).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let conn = Connection::open_in_memory().unwrap();
+        let mut statement = conn.prepare(&actual).unwrap();
+        statement.execute([]).unwrap();
+
+        let mut without_account = user_mapping_options.clone();
+        without_account.remove(UserMappingOptions::AccountName.as_ref());
+        let err = create_secret("sas_secret", without_account).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DuckdbOptionError>(),
+            Some(&DuckdbOptionError::missing(
+                UserMappingOptions::AccountName,
+                "object_stores/azure"
+            ))
+        );
+
+        let mut with_connection_string = user_mapping_options;
+        with_connection_string.insert(
+            UserMappingOptions::ConnectionString.as_ref().to_string(),
+            "connection_string".to_string(),
+        );
+        let err = create_secret("sas_secret", with_connection_string).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DuckdbOptionError>(),
+            Some(DuckdbOptionError::InvalidValue { option, .. }) if option == "sas_token"
+        ));
     }
 }
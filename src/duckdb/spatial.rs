@@ -15,13 +15,15 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use std::collections::HashMap;
 use strum::IntoEnumIterator;
 use strum::{AsRefStr, EnumIter};
 
 use crate::fdw::base::OptionValidator;
 
+use super::error::DuckdbOptionError;
+
 /// SpatialOption is an enum that represents the options that can be passed to the st_read function.
 /// Reference https://github.com/duckdb/duckdb_spatial/blob/main/docs/functions.md#st_read
 #[derive(EnumIter, AsRefStr, PartialEq, Debug)]
@@ -60,7 +62,7 @@ pub fn create_view(
     table_options: HashMap<String, String>,
 ) -> Result<String> {
     if !table_options.contains_key(SpatialOption::Files.as_ref()) {
-        return Err(anyhow!("Files option is required"));
+        return Err(DuckdbOptionError::missing(SpatialOption::Files, "formats/spatial").into());
     }
 
     let spatial_options = SpatialOption::iter()
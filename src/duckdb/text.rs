@@ -0,0 +1,90 @@
+// Copyright (c) 2023-2024 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use anyhow::Result;
+use std::collections::HashMap;
+use strum::{AsRefStr, EnumIter};
+
+use crate::fdw::base::OptionValidator;
+
+use super::error::DuckdbOptionError;
+use super::utils;
+
+#[derive(EnumIter, AsRefStr, PartialEq, Debug)]
+#[strum(serialize_all = "snake_case")]
+pub enum TextOption {
+    Files,
+    PreserveCasing,
+    Select,
+}
+
+impl OptionValidator for TextOption {
+    fn is_required(&self) -> bool {
+        match self {
+            Self::Files => true,
+            Self::PreserveCasing => false,
+            Self::Select => false,
+        }
+    }
+}
+
+pub fn create_view(
+    table_name: &str,
+    schema_name: &str,
+    table_options: HashMap<String, String>,
+) -> Result<String> {
+    let files = utils::format_csv(
+        table_options
+            .get(TextOption::Files.as_ref())
+            .ok_or_else(|| DuckdbOptionError::missing(TextOption::Files, "formats/text"))?,
+    );
+
+    let default_select = "*".to_string();
+    let select = table_options
+        .get(TextOption::Select.as_ref())
+        .unwrap_or(&default_select);
+
+    Ok(format!(
+        "CREATE VIEW IF NOT EXISTS {schema_name}.{table_name} AS SELECT {select} FROM read_text({files})"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use duckdb::Connection;
+
+    #[test]
+    fn test_create_text_view() {
+        let table_name = "test";
+        let schema_name = "main";
+        let table_options = HashMap::from([(
+            TextOption::Files.as_ref().to_string(),
+            "/data/file1.txt, /data/file2.txt".to_string(),
+        )]);
+
+        let expected = "CREATE VIEW IF NOT EXISTS main.test AS SELECT * FROM read_text(['/data/file1.txt', '/data/file2.txt'])";
+        let actual = create_view(table_name, schema_name, table_options).unwrap();
+
+        assert_eq!(expected, actual);
+
+        let conn = Connection::open_in_memory().unwrap();
+        match conn.prepare(&actual) {
+            Ok(_) => panic!("missing text file should throw an error"),
+            Err(e) => assert!(e.to_string().contains("file1.txt")),
+        }
+    }
+}
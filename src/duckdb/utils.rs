@@ -14,6 +14,11 @@
 //
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
+use anyhow::{anyhow, bail, Result};
+use serde_json::{Map, Value};
+use std::fs;
+
+use super::error::DuckdbOptionError;
 
 pub fn format_csv(csv_str: &str) -> String {
     let csv_split = csv_str.split(',').collect::<Vec<&str>>();
@@ -29,3 +34,191 @@ pub fn format_csv(csv_str: &str) -> String {
         ),
     }
 }
+
+// Reads the paths listed in a manifest file containing one path per line
+pub fn read_files_from(manifest_path: &str) -> Result<Vec<String>> {
+    let manifest = fs::read_to_string(manifest_path)
+        .map_err(|err| anyhow!("could not read files_from manifest {manifest_path}: {err}"))?;
+
+    let files = manifest
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect::<Vec<String>>();
+
+    if files.is_empty() {
+        bail!("files_from manifest {manifest_path} does not list any files");
+    }
+
+    Ok(files)
+}
+
+// Reads a manifest file containing one path per line into a DuckDB list of paths
+pub fn format_files_from(manifest_path: &str) -> Result<String> {
+    let files = read_files_from(manifest_path)?
+        .iter()
+        .map(|file| format!("'{}'", file.replace('\'', "''")))
+        .collect::<Vec<String>>();
+
+    Ok(format!("[{}]", files.join(", ")))
+}
+
+// Parses a JSON object of column names to DuckDB types, e.g. '{"a": "BIGINT"}'. `docs` is the
+// page that documents the column_types option of the table's format.
+fn parse_column_types(column_types: &str, docs: &'static str) -> Result<Vec<(String, String)>> {
+    let column_types: Map<String, Value> = serde_json::from_str(column_types).map_err(|err| {
+        DuckdbOptionError::invalid(
+            "column_types",
+            format!("must be a JSON object of column names to types: {err}"),
+            docs,
+        )
+    })?;
+
+    column_types
+        .into_iter()
+        .map(|(column_name, column_type)| {
+            let column_type = column_type
+                .as_str()
+                .ok_or_else(|| {
+                    DuckdbOptionError::invalid(
+                        "column_types",
+                        format!("type of column {column_name} must be a string"),
+                        docs,
+                    )
+                })?
+                .to_string();
+            check_column_type(&column_name, &column_type, docs)?;
+            Ok((column_name, column_type))
+        })
+        .collect()
+}
+
+// Types are spliced into SQL, so they may only contain what DuckDB type names are made of, e.g.
+// DECIMAL(10, 2), INTEGER[] or STRUCT("a b" VARCHAR), and can't close the parentheses around them
+fn check_column_type(column_name: &str, column_type: &str, docs: &'static str) -> Result<()> {
+    let invalid = || {
+        DuckdbOptionError::invalid(
+            "column_types",
+            format!("type of column {column_name} is not a valid DuckDB type: {column_type}"),
+            docs,
+        )
+    };
+
+    let mut depth = 0;
+    let mut quoted = false;
+    for c in column_type.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            _ if quoted => {}
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            c if c.is_ascii_alphanumeric() || matches!(c, '_' | ' ' | ',' | '[' | ']') => {}
+            _ => return Err(invalid().into()),
+        }
+    }
+
+    if quoted || depth != 0 || column_type.trim().is_empty() {
+        return Err(invalid().into());
+    }
+    Ok(())
+}
+
+// Turns a JSON object of column names to DuckDB types into casts that can be passed to
+// `SELECT * REPLACE (...)`
+pub fn format_column_types(column_types: &str, docs: &'static str) -> Result<String> {
+    Ok(parse_column_types(column_types, docs)?
+        .iter()
+        .map(|(column_name, column_type)| {
+            let column_name = column_name.replace('"', "\"\"");
+            Ok(format!(
+                "CAST(\"{column_name}\" AS {column_type}) AS \"{column_name}\""
+            ))
+        })
+        .collect::<Result<Vec<String>>>()?
+        .join(", "))
+}
+
+// Turns a JSON object of column names to DuckDB expressions, e.g. '{"total": "price * quantity"}',
+// into select list entries that add the computed columns
+pub fn format_computed_columns(computed_columns: &str) -> Result<String> {
+    let computed_columns: Map<String, Value> =
+        serde_json::from_str(computed_columns).map_err(|err| {
+            anyhow!("computed_columns must be a JSON object of column names to expressions: {err}")
+        })?;
+
+    Ok(computed_columns
+        .iter()
+        .map(|(column_name, expression)| {
+            let expression = expression
+                .as_str()
+                .ok_or_else(|| anyhow!("expression of column {column_name} must be a string"))?;
+            Ok(format!(
+                "({expression}) AS \"{}\"",
+                column_name.replace('"', "\"\"")
+            ))
+        })
+        .collect::<Result<Vec<String>>>()?
+        .join(", "))
+}
+
+// Turns a JSON object of column names to DuckDB types into a struct, e.g. {'a': 'BIGINT'},
+// for readers that take the types of individual columns
+pub fn format_column_types_struct(column_types: &str, docs: &'static str) -> Result<String> {
+    Ok(format!(
+        "{{{}}}",
+        parse_column_types(column_types, docs)?
+            .iter()
+            .map(|(column_name, column_type)| format!(
+                "'{}': '{}'",
+                column_name.replace('\'', "''"),
+                column_type.replace('\'', "''")
+            ))
+            .collect::<Vec<String>>()
+            .join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_column_types() {
+        assert_eq!(
+            format_column_types(
+                r#"{"a": "DECIMAL(10, 2)", "say \"hi\"": "STRUCT(\"b c\" INTEGER[])"}"#,
+                "formats/parquet"
+            )
+            .unwrap(),
+            r#"CAST("a" AS DECIMAL(10, 2)) AS "a", CAST("say ""hi""" AS STRUCT("b c" INTEGER[])) AS "say ""hi""""#
+        );
+
+        for column_type in [
+            "INTEGER) AS a, (SELECT 1",
+            "INTEGER; DROP TABLE t",
+            "VARCHAR -- comment",
+            "STRUCT(\"a INTEGER)",
+            "",
+        ] {
+            let column_types = serde_json::json!({ "a": column_type }).to_string();
+            let err = format_column_types(&column_types, "formats/parquet").unwrap_err();
+            assert!(
+                matches!(
+                    err.downcast_ref::<DuckdbOptionError>(),
+                    Some(DuckdbOptionError::InvalidValue { .. })
+                ),
+                "{column_type}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_column_types_struct() {
+        assert_eq!(
+            format_column_types_struct(r#"{"it's": "DECIMAL(10, 2)"}"#, "formats/csv").unwrap(),
+            "{'it''s': 'DECIMAL(10, 2)'}"
+        );
+        assert!(format_column_types_struct(r#"{"a": "VARCHAR') || ('"}"#, "formats/csv").is_err());
+    }
+}
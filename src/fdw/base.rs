@@ -19,33 +19,77 @@ use anyhow::{anyhow, bail, Result};
 use duckdb::arrow::array::RecordBatch;
 use pgrx::*;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use strum::IntoEnumIterator;
 use supabase_wrappers::prelude::*;
 use thiserror::Error;
 
 use super::handler::FdwHandler;
-use crate::duckdb::connection;
+use super::progress;
+use crate::duckdb::error::DuckdbOptionError;
+use crate::duckdb::secret::{scoped_secret_options, TableSecretOptions};
+use crate::duckdb::{connection, parquet};
 use crate::schema::cell::*;
 #[cfg(debug_assertions)]
 use crate::DEBUG_GUCS;
+use crate::GUCS;
 
-const DEFAULT_SECRET: &str = "default_secret";
+// Whether the running EXPLAIN uses a machine-readable format such as JSON, in which a scan's SQL
+// is shown as the duckdb_query property
+static STRUCTURED_EXPLAIN: AtomicBool = AtomicBool::new(false);
+
+// Whether an EXPLAIN ANALYZE that shows the SQL of each foreign scan is running. Its query is
+// not pushed down to DuckDB, so that the foreign scans run and are measured.
+static ANALYZE_FOREIGN_SCANS: AtomicBool = AtomicBool::new(false);
+
+pub fn analyzing_foreign_scans() -> bool {
+    ANALYZE_FOREIGN_SCANS.load(Ordering::Relaxed)
+}
+
+// Set by the explain hook for as long as Postgres runs the EXPLAIN. The previous state is
+// restored when it is dropped, which also happens if the EXPLAIN fails.
+pub struct ForeignScanExplain {
+    structured: bool,
+    analyze: bool,
+}
+
+impl ForeignScanExplain {
+    pub fn begin(structured: bool, analyze: bool) -> Self {
+        Self {
+            structured: STRUCTURED_EXPLAIN.swap(structured, Ordering::Relaxed),
+            analyze: ANALYZE_FOREIGN_SCANS.swap(analyze, Ordering::Relaxed),
+        }
+    }
+}
+
+impl Drop for ForeignScanExplain {
+    fn drop(&mut self) {
+        STRUCTURED_EXPLAIN.store(self.structured, Ordering::Relaxed);
+        ANALYZE_FOREIGN_SCANS.store(self.analyze, Ordering::Relaxed);
+    }
+}
 
 pub trait BaseFdw {
     // Getter methods
+    fn get_cell_readers(&self) -> &[CellReader];
     fn get_current_batch(&self) -> Option<RecordBatch>;
     fn get_current_batch_index(&self) -> usize;
+    fn get_rows_returned(&self) -> usize;
     fn get_scan_started(&self) -> bool;
     fn get_sql(&self) -> Option<String>;
     fn get_target_columns(&self) -> Vec<Column>;
+    fn get_target_typmods(&self) -> Vec<i32>;
     fn get_user_mapping_options(&self) -> HashMap<String, String>;
 
     // Setter methods
+    fn set_cell_readers(&mut self, readers: Vec<CellReader>);
     fn set_current_batch(&mut self, batch: Option<RecordBatch>);
     fn set_current_batch_index(&mut self, idx: usize);
+    fn set_rows_returned(&mut self, rows: usize);
     fn set_scan_started(&mut self);
     fn set_sql(&mut self, statement: Option<String>);
     fn set_target_columns(&mut self, columns: &[Column]);
+    fn set_target_typmods(&mut self, typmods: Vec<i32>);
 
     async fn begin_scan_impl(
         &mut self,
@@ -55,17 +99,13 @@ pub trait BaseFdw {
         limit: &Option<Limit>,
         options: HashMap<String, String>,
     ) -> Result<()> {
-        let oid_u32: u32 = options
-            .get(OPTS_TABLE_KEY)
-            .ok_or_else(|| anyhow!("table oid not found"))?
-            .parse()?;
-        let table_oid = pg_sys::Oid::from(oid_u32);
+        let table_oid = table_oid(&options)?;
         let pg_relation = unsafe { PgRelation::open(table_oid) };
         let schema_name = pg_relation.namespace();
         let table_name = pg_relation.name();
 
-        // Cache target columns
-        self.set_target_columns(columns);
+        progress::start_scan_progress(table_oid);
+        connection::apply_settings()?;
 
         // Register view with DuckDB
         let user_mapping_options = self.get_user_mapping_options();
@@ -78,21 +118,57 @@ pub trait BaseFdw {
             table_options,
             user_mapping_options,
             handler,
+            &declared_columns(&pg_relation),
         )?;
 
-        // Construct SQL scan statement
+        // Parameters of the quals only have values once the scan begins, so the SQL is built again
+        self.plan_scan_impl(quals, columns, sorts, limit, &options)
+    }
+
+    // Builds the SQL of the scan when the query is planned, so that EXPLAIN can show it even if
+    // the scan never begins
+    fn plan_scan_impl(
+        &mut self,
+        quals: &[Qual],
+        columns: &[Column],
+        sorts: &[Sort],
+        limit: &Option<Limit>,
+        options: &HashMap<String, String>,
+    ) -> Result<()> {
+        let pg_relation = unsafe { PgRelation::open(table_oid(options)?) };
+        let schema_name = pg_relation.namespace();
+        let table_name = pg_relation.name();
+
+        // Cache target columns, and the typmods that bit(n) values are checked against
+        self.set_target_columns(columns);
+        let tuple_desc = pg_relation.tuple_desc();
+        self.set_target_typmods(
+            columns
+                .iter()
+                .map(|column| {
+                    tuple_desc
+                        .get(column.num - 1)
+                        .map_or(-1, |attribute| attribute.atttypmod)
+                })
+                .collect(),
+        );
+
+        // Construct SQL scan statement. Columns are quoted so that they can't be mistaken for
+        // anything else DuckDB can bind a name to, e.g. a struct field.
         let targets = if columns.is_empty() {
             "*".to_string()
         } else {
             columns
                 .iter()
-                .map(|c| c.name.clone())
+                .map(|c| format!("\"{}\"", c.name.replace('"', "\"\"")))
                 .collect::<Vec<String>>()
                 .join(", ")
         };
 
         let mut sql = format!("SELECT {targets} FROM {schema_name}.{table_name}");
 
+        // Quals are compared to the bare column, without casts or functions around it, so that
+        // DuckDB pushes them into the scan and skips row groups by their min/max statistics
         if !quals.is_empty() {
             let mut formatter = DuckDbFormatter::new();
             let where_clauses = quals
@@ -143,14 +219,27 @@ pub trait BaseFdw {
                     .ok_or_else(|| anyhow!("current batch not found"))?
                     .num_rows()
         {
-            self.set_current_batch_index(0);
-            let next_batch = connection::get_next_batch()?;
+            if let Some(batch) = self.get_current_batch() {
+                progress::report_scan_progress(batch.num_rows());
+            }
 
-            if next_batch.is_none() {
+            self.set_current_batch_index(0);
+            let Some(next_batch) = connection::get_next_batch()? else {
                 return Ok(None);
-            }
+            };
 
-            self.set_current_batch(next_batch);
+            // How each column is read only depends on its types, so it is decided once per batch
+            let cell_readers = self
+                .get_target_columns()
+                .iter()
+                .zip(self.get_target_typmods())
+                .zip(next_batch.columns())
+                .map(|((target_column, typmod), column)| {
+                    CellReader::resolve(column.data_type(), target_column.type_oid, typmod)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            self.set_cell_readers(cell_readers);
+            self.set_current_batch(Some(next_batch));
         }
 
         let current_batch_binding = self.get_current_batch();
@@ -159,11 +248,27 @@ pub trait BaseFdw {
             .ok_or_else(|| anyhow!("current batch not found"))?;
         let current_batch_index = self.get_current_batch_index();
 
+        let max_result_rows = GUCS.max_result_rows.get();
+        if max_result_rows > 0 && self.get_rows_returned() >= max_result_rows as usize {
+            bail!(
+                "foreign scan returned more than paradedb.max_result_rows ({max_result_rows}) rows"
+            );
+        }
+        self.set_rows_returned(self.get_rows_returned() + 1);
+
         for (column_index, target_column) in
             self.get_target_columns().clone().into_iter().enumerate()
         {
             let batch_column = current_batch.column(column_index);
-            let cell = batch_column.get_cell(
+            if self.get_rows_returned() == 1 {
+                warn_decimal_precision_loss(
+                    batch_column.data_type(),
+                    target_column.type_oid,
+                    target_column.name.as_str(),
+                );
+            }
+            let cell = self.get_cell_readers()[column_index].read(
+                batch_column,
                 current_batch_index,
                 target_column.type_oid,
                 target_column.name.as_str(),
@@ -177,17 +282,28 @@ pub trait BaseFdw {
     }
 
     fn end_scan_impl(&mut self) {
+        progress::end_scan_progress();
         connection::clear_arrow();
     }
 
     fn explain_impl(&self) -> Result<Option<Vec<(String, String)>>> {
-        let sql = self
-            .get_sql()
-            .ok_or_else(|| anyhow!("sql statement was not cached"))?;
-        Ok(Some(vec![("DuckDB Scan".to_string(), sql)]))
+        let label = if STRUCTURED_EXPLAIN.load(Ordering::Relaxed) {
+            "duckdb_query"
+        } else {
+            "DuckDB Scan"
+        };
+        Ok(self.get_sql().map(|sql| vec![(label.to_string(), sql)]))
     }
 }
 
+fn table_oid(options: &HashMap<String, String>) -> Result<pg_sys::Oid> {
+    let oid_u32: u32 = options
+        .get(OPTS_TABLE_KEY)
+        .ok_or_else(|| anyhow!("table oid not found"))?
+        .parse()?;
+    Ok(pg_sys::Oid::from(oid_u32))
+}
+
 impl From<BaseFdwError> for pg_sys::panic::ErrorReport {
     fn from(value: BaseFdwError) -> Self {
         pg_sys::panic::ErrorReport::new(PgSqlErrorCode::ERRCODE_FDW_ERROR, format!("{}", value), "")
@@ -201,10 +317,14 @@ pub fn validate_options(opt_list: Vec<Option<String>>, valid_options: Vec<String
         .map(|opt| opt.split('=').next().unwrap_or(""))
     {
         if !valid_options.contains(&opt.to_string()) {
+            let suggestion = closest_option(opt, &valid_options)
+                .map(|valid_option| format!(". did you mean {valid_option}?"))
+                .unwrap_or_default();
             return Err(anyhow!(
-                "invalid option: {}. valid options are: {}",
+                "invalid option: {}. valid options are: {}{}",
                 opt,
-                valid_options.join(", ")
+                valid_options.join(", "),
+                suggestion
             ));
         }
     }
@@ -212,25 +332,103 @@ pub fn validate_options(opt_list: Vec<Option<String>>, valid_options: Vec<String
     Ok(())
 }
 
+// The valid option closest to a misspelled one, if it is only a couple of edits away
+fn closest_option<'a>(option: &str, valid_options: &'a [String]) -> Option<&'a String> {
+    valid_options
+        .iter()
+        .map(|valid_option| (edit_distance(option, valid_option), valid_option))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, valid_option)| valid_option)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
 pub fn register_duckdb_view(
     table_name: &str,
     schema_name: &str,
     table_options: HashMap<String, String>,
     user_mapping_options: HashMap<String, String>,
     handler: FdwHandler,
+    columns: &[String],
 ) -> Result<()> {
-    if !user_mapping_options.is_empty() {
-        connection::create_secret(DEFAULT_SECRET, user_mapping_options)?;
+    let mut table_options = lowercase_option_keys(table_options);
+    let user_mapping_options = lowercase_option_keys(user_mapping_options);
+
+    // Positional mapping reads the columns of the files under the names declared on the table
+    match table_options
+        .get("column_mapping")
+        .map(|option| option.to_lowercase())
+        .as_deref()
+    {
+        None | Some("name") => {}
+        Some("position") => {
+            if !columns.is_empty() && !table_options.contains_key("names") {
+                table_options.insert("names".to_string(), columns.join(","));
+            }
+        }
+        Some(other) => bail!("column_mapping must be 'name' or 'position', got '{other}'"),
+    }
+
+    // Every table gets its own secrets scoped to its files, so that tables with different
+    // credentials can be read in the same session
+    for (index, secret_options) in scoped_secret_options(&table_options, user_mapping_options)?
+        .into_iter()
+        .enumerate()
+    {
+        let secret_name = match index {
+            0 => format!("{schema_name}_{table_name}_secret"),
+            _ => format!("{schema_name}_{table_name}_secret_{index}"),
+        };
+        connection::create_secret(secret_name.as_str(), secret_options)?;
     }
 
     if !connection::view_exists(table_name, schema_name)? {
+        // Globs are only listed when the view is created, since listing a remote store on
+        // every scan would cost more than the scan itself
+        disable_file_globs(&mut table_options);
+        check_max_glob_files(&table_options)?;
+
         // Initialize DuckDB view
         connection::execute(
             format!("CREATE SCHEMA IF NOT EXISTS {schema_name}").as_str(),
             [],
         )?;
 
+        if handler == FdwHandler::Csv {
+            map_headerless_csv_columns(&mut table_options, columns)?;
+        }
+
+        if handler == FdwHandler::Parquet {
+            skip_unreadable_parquet_files(&mut table_options)?;
+            lowercase_parquet_names(&mut table_options)?;
+            map_parquet_field_ids(&mut table_options, columns)?;
+            coerce_parquet_union_types(&mut table_options)?;
+            cast_parquet_null_columns(&mut table_options)?;
+            select_parquet_row_groups(&mut table_options)?;
+        }
+
         match handler {
+            FdwHandler::Avro => {
+                connection::create_avro_view(table_name, schema_name, table_options)?;
+            }
+            FdwHandler::Blob => {
+                connection::create_blob_view(table_name, schema_name, table_options)?;
+            }
             FdwHandler::Csv => {
                 connection::create_csv_view(table_name, schema_name, table_options)?;
             }
@@ -246,9 +444,15 @@ pub fn register_duckdb_view(
             FdwHandler::Spatial => {
                 connection::create_spatial_view(table_name, schema_name, table_options)?;
             }
+            FdwHandler::Text => {
+                connection::create_text_view(table_name, schema_name, table_options)?;
+            }
             FdwHandler::Json => {
                 connection::create_json_view(table_name, schema_name, table_options)?;
             }
+            FdwHandler::Memory => {
+                connection::create_memory_view(table_name, schema_name, table_options)?;
+            }
             _ => {
                 bail!("got unexpected fdw_handler")
             }
@@ -258,6 +462,405 @@ pub fn register_duckdb_view(
     Ok(())
 }
 
+// Errors if a glob in the files option matches more files than paradedb.max_glob_files allows
+fn check_max_glob_files(table_options: &HashMap<String, String>) -> Result<()> {
+    let max_glob_files = GUCS.max_glob_files.get();
+    if max_glob_files <= 0 {
+        return Ok(());
+    }
+
+    let Some(files) = table_options.get("files") else {
+        return Ok(());
+    };
+
+    for pattern in files
+        .split(',')
+        .map(str::trim)
+        .filter(|file| file.contains(['*', '?', '[']))
+    {
+        let file_count = connection::glob_file_count(pattern)?;
+        if file_count > max_glob_files as i64 {
+            bail!(
+                "{pattern} matches {file_count} files, which exceeds paradedb.max_glob_files ({max_glob_files})"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// Escapes the glob characters of a path by wrapping each in a character class, so that DuckDB
+// matches it literally
+fn escape_glob(file: &str) -> String {
+    file.chars()
+        .map(|c| match c {
+            '*' | '?' | '[' => format!("[{c}]"),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+// With disable_glob, the paths in the files option are read literally, so that files whose names
+// contain glob characters can be read
+fn disable_file_globs(table_options: &mut HashMap<String, String>) {
+    let disable_glob = table_options
+        .remove("disable_glob")
+        .is_some_and(|option| option.eq_ignore_ascii_case("true"));
+    if !disable_glob {
+        return;
+    }
+
+    if let Some(files) = table_options.get("files") {
+        let escaped_files = files
+            .split(',')
+            .map(|file| escape_glob(file.trim()))
+            .collect::<Vec<String>>()
+            .join(",");
+        table_options.insert("files".to_string(), escaped_files);
+    }
+}
+
+// Lists the files of a files option, expanding the patterns that are globs. The files that
+// globs match are escaped, since their names can contain glob characters themselves.
+fn expand_file_patterns(files: &str) -> Result<Vec<String>> {
+    let mut expanded_files = vec![];
+    for pattern in files.split(',').map(str::trim) {
+        if pattern.contains(['*', '?', '[']) {
+            expanded_files.extend(
+                connection::glob_files(pattern)?
+                    .iter()
+                    .map(|file| escape_glob(file)),
+            );
+        } else {
+            expanded_files.push(pattern.to_string());
+        }
+    }
+    Ok(expanded_files)
+}
+
+// With ignore_errors, replaces the files option with the files it matches whose footers can be
+// read, warning about each file that is skipped. The files are resolved when the view is
+// created, so files added to a globbed directory afterwards are read in new sessions.
+fn skip_unreadable_parquet_files(table_options: &mut HashMap<String, String>) -> Result<()> {
+    let ignore_errors = table_options
+        .get("ignore_errors")
+        .is_some_and(|option| option.eq_ignore_ascii_case("true"));
+    if !ignore_errors {
+        return Ok(());
+    }
+
+    let Some(files) = table_options.get("files") else {
+        bail!("ignore_errors requires the files option");
+    };
+
+    let mut readable_files = vec![];
+    for file in expand_file_patterns(files)? {
+        match connection::check_parquet_file(&file) {
+            Ok(()) => readable_files.push(file),
+            Err(err) => warning!("skipping unreadable parquet file {file}: {err}"),
+        }
+    }
+
+    if readable_files.is_empty() {
+        bail!("none of the files in {files} could be read");
+    }
+
+    table_options.insert("files".to_string(), readable_files.join(","));
+    Ok(())
+}
+
+// CSV files without a header have no column names to match the declared columns by, so their
+// columns are read by position under the declared names
+fn map_headerless_csv_columns(
+    table_options: &mut HashMap<String, String>,
+    columns: &[String],
+) -> Result<()> {
+    let headerless = table_options
+        .get("header")
+        .is_some_and(|option| option.eq_ignore_ascii_case("false"));
+    if !headerless
+        || columns.is_empty()
+        || ["names", "columns", "column_mapping", "select"]
+            .iter()
+            .any(|option| table_options.contains_key(*option))
+    {
+        return Ok(());
+    }
+
+    let column_count = connection::csv_column_names(table_options)?.len();
+    if column_count != columns.len() {
+        bail!(
+            "the CSV files have {column_count} columns but the table declares {}, files without a header are read by position so declare one column for each of their columns",
+            columns.len()
+        );
+    }
+
+    table_options.insert("names".to_string(), columns.join(","));
+    Ok(())
+}
+
+// With lowercase_names, replaces the option with names that rename the columns of the files to
+// their lowercased names
+fn lowercase_parquet_names(table_options: &mut HashMap<String, String>) -> Result<()> {
+    let lowercase_names = table_options
+        .remove("lowercase_names")
+        .is_some_and(|option| option.eq_ignore_ascii_case("true"));
+    if !lowercase_names {
+        return Ok(());
+    }
+
+    if table_options.contains_key("names") {
+        bail!("lowercase_names and names options cannot both be set");
+    }
+
+    let mut lowercased_names: Vec<String> = vec![];
+    for column_name in connection::parquet_column_names(table_options)? {
+        let lowercased_name = column_name.to_lowercase();
+        if lowercased_names.contains(&lowercased_name) {
+            bail!("lowercase_names would give more than one column the name {lowercased_name}");
+        }
+        lowercased_names.push(lowercased_name);
+    }
+
+    table_options.insert("names".to_string(), lowercased_names.join(","));
+    Ok(())
+}
+
+// With map_by_field_id, reads the declared columns by the field ids that the files store for
+// them, so that columns renamed in some of the files are still read. The field id and type of
+// each column are taken from the first file that has a column with its declared name.
+fn map_parquet_field_ids(
+    table_options: &mut HashMap<String, String>,
+    columns: &[String],
+) -> Result<()> {
+    let map_by_field_id = table_options
+        .remove("map_by_field_id")
+        .is_some_and(|option| option.eq_ignore_ascii_case("true"));
+    if !map_by_field_id {
+        return Ok(());
+    }
+
+    if columns.is_empty() {
+        bail!("map_by_field_id requires the columns of the table to be declared");
+    }
+
+    let field_ids = connection::parquet_field_ids(table_options)?;
+    let mut schema = vec![];
+    for column in columns {
+        let Some((file, name, field_id)) = field_ids
+            .iter()
+            .find(|(_, name, _)| name.eq_ignore_ascii_case(column))
+        else {
+            bail!("column {column} has no field id in the files");
+        };
+
+        let column_type = connection::parquet_column_type(file, name)?;
+        schema.push(format!(
+            "{field_id}: {{name: '{}', type: '{}', default_value: NULL}}",
+            column.replace('\'', "''"),
+            column_type.replace('\'', "''")
+        ));
+    }
+
+    table_options.insert(
+        parquet::FIELD_ID_SCHEMA.to_string(),
+        format!("MAP {{{}}}", schema.join(", ")),
+    );
+    Ok(())
+}
+
+// With union_by_name_type_coercion, reads the files by name and casts each column whose type
+// differs between the files to the widest of its types. Casts in column_types take precedence.
+fn coerce_parquet_union_types(table_options: &mut HashMap<String, String>) -> Result<()> {
+    let coerce_types = table_options
+        .remove("union_by_name_type_coercion")
+        .is_some_and(|option| option.eq_ignore_ascii_case("true"));
+    if !coerce_types {
+        return Ok(());
+    }
+
+    let Some(files) = table_options.get("files") else {
+        bail!("union_by_name_type_coercion requires the files option");
+    };
+
+    // The type of each column across the files, and whether the files disagree on it
+    let mut union_types: Vec<(String, String, bool)> = vec![];
+    for file in expand_file_patterns(files)? {
+        for (name, column_type) in connection::parquet_file_columns(&file)? {
+            match union_types
+                .iter_mut()
+                .find(|(union_name, ..)| union_name.eq_ignore_ascii_case(&name))
+            {
+                Some((_, union_type, differs)) if *union_type != column_type => {
+                    *union_type = parquet::widest_type(union_type, &column_type);
+                    *differs = true;
+                }
+                Some(_) => {}
+                None => union_types.push((name, column_type, false)),
+            }
+        }
+    }
+
+    let mut column_types = match table_options.get("column_types") {
+        Some(column_types) => serde_json::from_str(column_types).map_err(|err| {
+            DuckdbOptionError::invalid(
+                "column_types",
+                format!("must be a JSON object of column names to types: {err}"),
+                "formats/parquet",
+            )
+        })?,
+        None => serde_json::Map::new(),
+    };
+    for (name, union_type, differs) in union_types {
+        if differs && !column_types.contains_key(&name) {
+            column_types.insert(name, serde_json::Value::String(union_type));
+        }
+    }
+
+    table_options.insert("union_by_name".to_string(), "true".to_string());
+    if !column_types.is_empty() {
+        table_options.insert(
+            "column_types".to_string(),
+            serde_json::Value::Object(column_types).to_string(),
+        );
+    }
+    Ok(())
+}
+
+// With null_column_type, casts the columns that only contain nulls, whose type in the files is
+// often a placeholder, to the given type. Casts in column_types take precedence.
+fn cast_parquet_null_columns(table_options: &mut HashMap<String, String>) -> Result<()> {
+    let Some(null_column_type) = table_options.remove("null_column_type") else {
+        return Ok(());
+    };
+
+    let null_columns = connection::parquet_null_columns(table_options)?;
+    if null_columns.is_empty() {
+        return Ok(());
+    }
+
+    // Casts apply to the columns after they are renamed by names
+    let names = table_options
+        .get("names")
+        .map(|names| {
+            names
+                .split(',')
+                .map(|name| name.trim().to_string())
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+
+    let mut column_types = match table_options.get("column_types") {
+        Some(column_types) => serde_json::from_str(column_types).map_err(|err| {
+            DuckdbOptionError::invalid(
+                "column_types",
+                format!("must be a JSON object of column names to types: {err}"),
+                "formats/parquet",
+            )
+        })?,
+        None => serde_json::Map::new(),
+    };
+    for (position, column_name) in connection::parquet_column_names(table_options)?
+        .into_iter()
+        .enumerate()
+    {
+        if !null_columns.contains(&column_name) {
+            continue;
+        }
+        let name = names.get(position).cloned().unwrap_or(column_name);
+        if !column_types.contains_key(&name) {
+            column_types.insert(name, serde_json::Value::String(null_column_type.clone()));
+        }
+    }
+
+    if !column_types.is_empty() {
+        table_options.insert(
+            "column_types".to_string(),
+            serde_json::Value::Object(column_types).to_string(),
+        );
+    }
+    Ok(())
+}
+
+// With row_groups, replaces the option with a filter on the row numbers that the requested row
+// groups of the file span
+fn select_parquet_row_groups(table_options: &mut HashMap<String, String>) -> Result<()> {
+    let Some(row_groups) = table_options.remove("row_groups") else {
+        return Ok(());
+    };
+    let row_group_ids = parquet::parse_row_groups(&row_groups)?;
+
+    let file = match table_options
+        .get("files")
+        .map(|files| expand_file_patterns(files))
+        .transpose()?
+        .as_deref()
+    {
+        Some([file]) => file.to_string(),
+        _ => bail!("row_groups can only be set on tables over a single file"),
+    };
+
+    let row_group_sizes = connection::parquet_row_group_sizes(&file)?;
+    let mut ranges = vec![];
+    for row_group_id in row_group_ids {
+        let Some(size) = row_group_sizes.get(row_group_id as usize) else {
+            bail!(
+                "row group {row_group_id} does not exist, {file} has {} row groups",
+                row_group_sizes.len()
+            );
+        };
+        let start: i64 = row_group_sizes[..row_group_id as usize].iter().sum();
+        ranges.push(format!(
+            "file_row_number BETWEEN {start} AND {}",
+            start + size - 1
+        ));
+    }
+
+    table_options.insert(parquet::ROW_GROUP_FILTER.to_string(), ranges.join(" OR "));
+    Ok(())
+}
+
+// Registers the DuckDB view of a foreign table outside of a scan, e.g. for SQL functions that
+// query the table directly
+pub fn register_foreign_table_view(pg_relation: &PgRelation) -> Result<()> {
+    if !pg_relation.is_foreign_table() {
+        bail!("{} is not a foreign table", pg_relation.name());
+    }
+
+    let foreign_table = unsafe { pg_sys::GetForeignTable(pg_relation.oid()) };
+    let handler = FdwHandler::from(foreign_table);
+
+    if handler == FdwHandler::Other {
+        bail!(
+            "{} is not a foreign table created by pg_analytics",
+            pg_relation.name()
+        );
+    }
+
+    let foreign_server = unsafe { pg_sys::GetForeignServer((*foreign_table).serverid) };
+    let user_mapping_options = unsafe { user_mapping_options(foreign_server) };
+    let table_options = unsafe { options_to_hashmap((*foreign_table).options)? };
+
+    register_duckdb_view(
+        pg_relation.name(),
+        pg_relation.namespace(),
+        table_options,
+        user_mapping_options,
+        handler,
+        &declared_columns(pg_relation),
+    )
+}
+
+// Returns the names of the columns declared on a foreign table, in order
+pub fn declared_columns(pg_relation: &PgRelation) -> Vec<String> {
+    pg_relation
+        .tuple_desc()
+        .iter()
+        .filter(|attribute| !attribute.is_dropped())
+        .map(|attribute| attribute.name().to_string())
+        .collect()
+}
+
 #[derive(Error, Debug)]
 pub enum BaseFdwError {
     #[error(transparent)]
@@ -297,10 +900,68 @@ pub(crate) trait OptionValidator {
     fn is_required(&self) -> bool;
 }
 
+// Option keys are matched case-insensitively, e.g. Files is read as files
+pub fn lowercase_option_keys(options: HashMap<String, String>) -> HashMap<String, String> {
+    options
+        .into_iter()
+        .map(|(key, value)| (key.to_lowercase(), value))
+        .collect()
+}
+
+// Lowercases the keys of options in the key=value form validators receive them in
+fn lowercase_option_list_keys(opt_list: Vec<Option<String>>) -> Vec<Option<String>> {
+    opt_list
+        .into_iter()
+        .map(|opt| {
+            opt.map(|opt| match opt.split_once('=') {
+                Some((key, value)) => format!("{}={value}", key.to_lowercase()),
+                None => opt.to_lowercase(),
+            })
+        })
+        .collect()
+}
+
+// Validates the options of a foreign table, which can also set credentials
+pub fn validate_table_option<T: IntoEnumIterator + OptionValidator + AsRef<str>>(
+    opt_list: Vec<Option<String>>,
+) -> Result<()> {
+    let secret_options: Vec<String> = TableSecretOptions::iter()
+        .map(|opt| opt.as_ref().to_string())
+        .collect();
+    let opt_list = lowercase_option_list_keys(opt_list);
+
+    if !unsafe { pg_sys::superuser() } {
+        for credential in TableSecretOptions::iter().filter(|opt| opt.is_credential()) {
+            let is_set = opt_list
+                .iter()
+                .flatten()
+                .any(|opt| opt.split('=').next().unwrap_or("") == credential.as_ref());
+            if is_set {
+                bail!(
+                    "only superusers can set the {} option of a foreign table, since every role can read the options of foreign tables; set credentials in a user mapping instead",
+                    credential.as_ref()
+                );
+            }
+        }
+    }
+
+    let opt_list = opt_list
+        .into_iter()
+        .filter(|opt| {
+            !opt.as_ref().is_some_and(|opt| {
+                secret_options.contains(&opt.split('=').next().unwrap_or("").to_string())
+            })
+        })
+        .collect();
+
+    validate_mapping_option::<T>(opt_list)
+}
+
 pub fn validate_mapping_option<T: IntoEnumIterator + OptionValidator + AsRef<str>>(
     opt_list: Vec<Option<String>>,
 ) -> Result<()> {
     let valid_options: Vec<String> = T::iter().map(|opt| opt.as_ref().to_string()).collect();
+    let opt_list = lowercase_option_list_keys(opt_list);
 
     validate_options(opt_list.clone(), valid_options)?;
 
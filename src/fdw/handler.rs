@@ -19,12 +19,16 @@ use pgrx::*;
 
 #[derive(PartialEq)]
 pub enum FdwHandler {
+    Avro,
+    Blob,
     Csv,
     Json,
+    Memory,
     Parquet,
     Delta,
     Iceberg,
     Spatial,
+    Text,
     Other,
 }
 
@@ -33,12 +37,16 @@ pub enum FdwHandler {
 impl From<&str> for FdwHandler {
     fn from(handler_name: &str) -> Self {
         match handler_name {
+            "avro_fdw_handler" => FdwHandler::Avro,
+            "blob_fdw_handler" => FdwHandler::Blob,
             "csv_fdw_handler" => FdwHandler::Csv,
             "json_fdw_handler" => FdwHandler::Json,
+            "memory_fdw_handler" => FdwHandler::Memory,
             "parquet_fdw_handler" => FdwHandler::Parquet,
             "delta_fdw_handler" => FdwHandler::Delta,
             "iceberg_fdw_handler" => FdwHandler::Iceberg,
             "spatial_fdw_handler" => FdwHandler::Spatial,
+            "text_fdw_handler" => FdwHandler::Text,
             _ => FdwHandler::Other,
         }
     }
@@ -24,6 +24,7 @@ use supabase_wrappers::prelude::*;
 
 use super::base::*;
 use crate::duckdb::{iceberg::IcebergOption, secret::UserMappingOptions};
+use crate::schema::cell::CellReader;
 
 #[wrappers_fdw(
     author = "ParadeDB",
@@ -31,15 +32,22 @@ use crate::duckdb::{iceberg::IcebergOption, secret::UserMappingOptions};
     error_type = "BaseFdwError"
 )]
 pub(crate) struct IcebergFdw {
+    cell_readers: Vec<CellReader>,
     current_batch: Option<RecordBatch>,
     current_batch_index: usize,
+    rows_returned: usize,
     scan_started: bool,
     sql: Option<String>,
     target_columns: Vec<Column>,
+    target_typmods: Vec<i32>,
     user_mapping_options: HashMap<String, String>,
 }
 
 impl BaseFdw for IcebergFdw {
+    fn get_cell_readers(&self) -> &[CellReader] {
+        &self.cell_readers
+    }
+
     fn get_current_batch(&self) -> Option<RecordBatch> {
         self.current_batch.clone()
     }
@@ -48,6 +56,10 @@ impl BaseFdw for IcebergFdw {
         self.current_batch_index
     }
 
+    fn get_rows_returned(&self) -> usize {
+        self.rows_returned
+    }
+
     fn get_scan_started(&self) -> bool {
         self.scan_started
     }
@@ -60,10 +72,18 @@ impl BaseFdw for IcebergFdw {
         self.target_columns.clone()
     }
 
+    fn get_target_typmods(&self) -> Vec<i32> {
+        self.target_typmods.clone()
+    }
+
     fn get_user_mapping_options(&self) -> HashMap<String, String> {
         self.user_mapping_options.clone()
     }
 
+    fn set_cell_readers(&mut self, readers: Vec<CellReader>) {
+        self.cell_readers = readers;
+    }
+
     fn set_current_batch(&mut self, batch: Option<RecordBatch>) {
         self.current_batch = batch;
     }
@@ -72,6 +92,10 @@ impl BaseFdw for IcebergFdw {
         self.current_batch_index = index;
     }
 
+    fn set_rows_returned(&mut self, rows: usize) {
+        self.rows_returned = rows;
+    }
+
     fn set_scan_started(&mut self) {
         self.scan_started = true;
     }
@@ -83,6 +107,10 @@ impl BaseFdw for IcebergFdw {
     fn set_target_columns(&mut self, columns: &[Column]) {
         self.target_columns = columns.to_vec();
     }
+
+    fn set_target_typmods(&mut self, typmods: Vec<i32>) {
+        self.target_typmods = typmods;
+    }
 }
 
 impl ForeignDataWrapper<BaseFdwError> for IcebergFdw {
@@ -92,11 +120,14 @@ impl ForeignDataWrapper<BaseFdwError> for IcebergFdw {
         user_mapping_options: HashMap<String, String>,
     ) -> Result<Self, BaseFdwError> {
         Ok(Self {
+            cell_readers: Vec::new(),
             current_batch: None,
             current_batch_index: 0,
+            rows_returned: 0,
             scan_started: false,
             sql: None,
             target_columns: Vec::new(),
+            target_typmods: Vec::new(),
             user_mapping_options,
         })
     }
@@ -110,7 +141,7 @@ impl ForeignDataWrapper<BaseFdwError> for IcebergFdw {
                 FOREIGN_DATA_WRAPPER_RELATION_ID => {}
                 FOREIGN_SERVER_RELATION_ID => {}
                 FOREIGN_TABLE_RELATION_ID => {
-                    validate_mapping_option::<IcebergOption>(opt_list)?;
+                    validate_table_option::<IcebergOption>(opt_list)?;
                 }
                 USER_MAPPING_RELATION_ID => {
                     validate_mapping_option::<UserMappingOptions>(opt_list)?;
@@ -122,6 +153,18 @@ impl ForeignDataWrapper<BaseFdwError> for IcebergFdw {
         Ok(())
     }
 
+    fn get_rel_size(
+        &mut self,
+        quals: &[Qual],
+        columns: &[Column],
+        sorts: &[Sort],
+        limit: &Option<Limit>,
+        options: &HashMap<String, String>,
+    ) -> Result<(i64, i32), BaseFdwError> {
+        self.plan_scan_impl(quals, columns, sorts, limit, options)?;
+        Ok((0, 0))
+    }
+
     fn begin_scan(
         &mut self,
         quals: &[Qual],
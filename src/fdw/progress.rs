@@ -0,0 +1,177 @@
+// Copyright (c) 2023-2024 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use pgrx::lwlock::PgLwLock;
+use pgrx::shmem::*;
+use pgrx::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+// Postgres does not let extensions register their own progress command types, so foreign
+// scans publish their progress in shared memory owned by the extension rather than in a
+// pg_stat_progress_* slot, where they would show up as commands that never ran.
+extension_sql!(
+    r#"
+    CREATE VIEW scan_progress AS
+    SELECT
+        s.pid,
+        s.datid,
+        d.datname,
+        s.relid,
+        s.rows_processed
+    FROM scan_progress_info() s
+    LEFT JOIN pg_database d ON s.datid = d.oid
+    WHERE pg_has_role(s.userid, 'USAGE') OR pg_has_role('pg_read_all_stats', 'USAGE');
+    "#,
+    name = "scan_progress_view",
+    requires = [scan_progress_info]
+);
+
+const MAX_SCANS: usize = 1024;
+const NO_ENTRY: usize = usize::MAX;
+
+#[derive(Copy, Clone)]
+struct ScanProgressEntry {
+    pid: i32,
+    userid: pg_sys::Oid,
+    datid: pg_sys::Oid,
+    relid: pg_sys::Oid,
+    rows_processed: i64,
+}
+
+const EMPTY_ENTRY: ScanProgressEntry = ScanProgressEntry {
+    pid: 0,
+    userid: pg_sys::InvalidOid,
+    datid: pg_sys::InvalidOid,
+    relid: pg_sys::InvalidOid,
+    rows_processed: 0,
+};
+
+#[derive(Copy, Clone)]
+struct ScanProgress {
+    entries: [ScanProgressEntry; MAX_SCANS],
+}
+
+impl Default for ScanProgress {
+    fn default() -> Self {
+        Self {
+            entries: [EMPTY_ENTRY; MAX_SCANS],
+        }
+    }
+}
+
+unsafe impl PGRXSharedMemory for ScanProgress {}
+
+static SCAN_PROGRESS: PgLwLock<ScanProgress> = PgLwLock::new(c"pg_analytics_scan_progress");
+// Shared memory can only be requested while the library is preloaded
+static SHMEM_INITIALIZED: AtomicBool = AtomicBool::new(false);
+// The entry of this backend's active scan
+static SCAN_ENTRY: AtomicUsize = AtomicUsize::new(NO_ENTRY);
+static EXIT_CALLBACK_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+pub fn init() {
+    if unsafe { pg_sys::process_shared_preload_libraries_in_progress } {
+        pg_shmem_init!(SCAN_PROGRESS);
+        SHMEM_INITIALIZED.store(true, Ordering::Relaxed);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+#[pg_extern]
+pub fn scan_progress_info() -> iter::TableIterator<(
+    name!(pid, i32),
+    name!(userid, pg_sys::Oid),
+    name!(datid, pg_sys::Oid),
+    name!(relid, pg_sys::Oid),
+    name!(rows_processed, i64),
+)> {
+    let mut rows = vec![];
+    if SHMEM_INITIALIZED.load(Ordering::Relaxed) {
+        rows.extend(
+            SCAN_PROGRESS
+                .share()
+                .entries
+                .iter()
+                .filter(|entry| entry.pid != 0)
+                .map(|entry| {
+                    (
+                        entry.pid,
+                        entry.userid,
+                        entry.datid,
+                        entry.relid,
+                        entry.rows_processed,
+                    )
+                }),
+        );
+    }
+    iter::TableIterator::new(rows)
+}
+
+pub fn start_scan_progress(relid: pg_sys::Oid) {
+    // Only the outermost foreign scan of a query is reported
+    if !SHMEM_INITIALIZED.load(Ordering::Relaxed) || SCAN_ENTRY.load(Ordering::Relaxed) != NO_ENTRY
+    {
+        return;
+    }
+
+    let mut progress = SCAN_PROGRESS.exclusive();
+    let Some(index) = progress.entries.iter().position(|entry| entry.pid == 0) else {
+        return;
+    };
+
+    unsafe {
+        progress.entries[index] = ScanProgressEntry {
+            pid: pg_sys::MyProcPid,
+            userid: pg_sys::GetUserId(),
+            datid: pg_sys::MyDatabaseId,
+            relid,
+            rows_processed: 0,
+        };
+    }
+    SCAN_ENTRY.store(index, Ordering::Relaxed);
+    drop(progress);
+
+    // A backend that exits in the middle of a scan would otherwise leave its entry behind
+    if !EXIT_CALLBACK_REGISTERED.swap(true, Ordering::Relaxed) {
+        unsafe {
+            pg_sys::before_shmem_exit(Some(release_on_exit), pg_sys::Datum::from(0));
+        }
+    }
+}
+
+pub fn report_scan_progress(rows: usize) {
+    let index = SCAN_ENTRY.load(Ordering::Relaxed);
+    if index == NO_ENTRY {
+        return;
+    }
+
+    SCAN_PROGRESS.exclusive().entries[index].rows_processed += rows as i64;
+}
+
+// Also called when a transaction aborts, since a scan that errors never reaches its end
+pub fn end_scan_progress() {
+    let index = SCAN_ENTRY.swap(NO_ENTRY, Ordering::Relaxed);
+    if index == NO_ENTRY {
+        return;
+    }
+
+    SCAN_PROGRESS.exclusive().entries[index] = EMPTY_ENTRY;
+}
+
+#[pg_guard]
+unsafe extern "C" fn release_on_exit(_code: i32, _arg: pg_sys::Datum) {
+    end_scan_progress();
+}
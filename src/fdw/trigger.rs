@@ -20,9 +20,12 @@ use pgrx::*;
 use std::ffi::CStr;
 use supabase_wrappers::prelude::{options_to_hashmap, user_mapping_options};
 
-use super::base::register_duckdb_view;
+use super::base::{declared_columns, lowercase_option_keys, register_duckdb_view};
 use crate::duckdb::connection;
+use crate::duckdb::error::DuckdbOptionError;
 use crate::fdw::handler::FdwHandler;
+use crate::schema::columns::check_declared_columns;
+use crate::GUCS;
 
 extension_sql!(
     r#"
@@ -130,10 +133,11 @@ unsafe fn auto_create_schema_impl(fcinfo: pg_sys::FunctionCallInfo) -> Result<()
 
     // Don't allow DuckDB reserved names
     if DUCKDB_RESERVED_NAMES.contains(&table_name) {
-        bail!(
-            "Table name '{}' is not allowed because it is reserved by DuckDB",
-            table_name
-        );
+        return Err(DuckdbOptionError::ReservedName {
+            kind: "Table",
+            name: table_name.to_string(),
+        }
+        .into());
     }
 
     // Drop stale view
@@ -145,25 +149,20 @@ unsafe fn auto_create_schema_impl(fcinfo: pg_sys::FunctionCallInfo) -> Result<()
     // Register DuckDB view
     let foreign_server = unsafe { pg_sys::GetForeignServer((*foreign_table).serverid) };
     let user_mapping_options = unsafe { user_mapping_options(foreign_server) };
-    let table_options = unsafe { options_to_hashmap((*foreign_table).options)? };
+    let table_options =
+        lowercase_option_keys(unsafe { options_to_hashmap((*foreign_table).options)? });
     let handler = FdwHandler::from(foreign_table);
+    let pg_relation = PgRelation::with_lock(oid, pg_sys::AccessShareLock as i32);
+    let declared_columns = declared_columns(&pg_relation);
     register_duckdb_view(
         table_name,
         schema_name,
         table_options.clone(),
         user_mapping_options,
         handler,
+        &declared_columns,
     )?;
 
-    // If the table already has columns, no need for auto schema creation
-    let relation = pg_sys::relation_open(oid, pg_sys::AccessShareLock as i32);
-    if (*(*relation).rd_att).natts != 0 {
-        pg_sys::RelationClose(relation);
-        return Ok(());
-    }
-
-    pg_sys::RelationClose(relation);
-
     // Get DuckDB schema
     let conn = unsafe { &*connection::get_global_connection().get() };
     let query = format!("DESCRIBE {schema_name}.{table_name}");
@@ -176,10 +175,32 @@ unsafe fn auto_create_schema_impl(fcinfo: pg_sys::FunctionCallInfo) -> Result<()
         .map(|row| row.unwrap())
         .collect::<Vec<(String, String)>>();
 
+    // If the table already has columns, no need for auto schema creation, but its columns
+    // must exist in the files or every scan fails
+    if !declared_columns.is_empty() {
+        let file_columns = schema_rows
+            .into_iter()
+            .map(|(column_name, _)| column_name)
+            .collect::<Vec<String>>();
+        return check_declared_columns(table_name, &declared_columns, &file_columns);
+    }
+
     if schema_rows.is_empty() {
         return Ok(());
     }
 
+    // Blob tables are for loading binary content, so it is kept as bytea rather than text
+    let schema_rows = match FdwHandler::from(foreign_table) {
+        FdwHandler::Blob => schema_rows
+            .into_iter()
+            .map(|(column_name, duckdb_type)| match duckdb_type.as_str() {
+                "BLOB" => (column_name, "BYTEA".to_string()),
+                _ => (column_name, duckdb_type),
+            })
+            .collect(),
+        _ => schema_rows,
+    };
+
     // Alter Postgres table to match DuckDB schema
     let preserve_casing = table_options
         .get("preserve_casing")
@@ -191,6 +212,111 @@ unsafe fn auto_create_schema_impl(fcinfo: pg_sys::FunctionCallInfo) -> Result<()
     Ok(())
 }
 
+extension_sql!(
+    r#"
+    CREATE EVENT TRIGGER drop_persistent_secrets_trigger
+    ON sql_drop
+    WHEN TAG IN ('DROP FOREIGN TABLE', 'DROP SERVER', 'DROP USER MAPPING')
+    EXECUTE FUNCTION persistent_secrets_hook();
+
+    CREATE EVENT TRIGGER alter_persistent_secrets_trigger
+    ON ddl_command_end
+    WHEN TAG IN ('ALTER FOREIGN TABLE', 'CREATE USER MAPPING', 'ALTER USER MAPPING')
+    EXECUTE FUNCTION persistent_secrets_hook();
+    "#,
+    name = "persistent_secrets_trigger",
+    requires = [persistent_secrets_hook]
+);
+
+#[pg_extern(sql = "
+    CREATE FUNCTION persistent_secrets_hook() 
+    RETURNS event_trigger 
+    LANGUAGE c 
+    AS 'MODULE_PATHNAME', '@FUNCTION_NAME@';
+")]
+fn persistent_secrets_hook(fcinfo: pg_sys::FunctionCallInfo) {
+    unsafe {
+        persistent_secrets_impl(fcinfo).unwrap_or_else(|e| {
+            panic!("{}", e);
+        });
+    }
+}
+
+// Secrets persisted for a foreign table are dropped when the table is dropped or altered, or when
+// a user mapping changes. They are persisted again the next time the table is read.
+#[inline]
+unsafe fn persistent_secrets_impl(fcinfo: pg_sys::FunctionCallInfo) -> Result<()> {
+    if !is_a((*fcinfo).context, pg_sys::NodeTag::T_EventTriggerData) {
+        return Ok(());
+    }
+
+    // Only connections that persist secrets have any to drop
+    if GUCS
+        .secret_directory
+        .get()
+        .map_or(true, |secret_directory| {
+            secret_directory.to_bytes().is_empty()
+        })
+    {
+        return Ok(());
+    }
+
+    let event_trigger_data = (*fcinfo).context as *mut pg_sys::EventTriggerData;
+    let parsetree = (*event_trigger_data).parsetree;
+
+    let role = if is_a(parsetree, pg_sys::NodeTag::T_CreateUserMappingStmt) {
+        Some((*(parsetree as *mut pg_sys::CreateUserMappingStmt)).user)
+    } else if is_a(parsetree, pg_sys::NodeTag::T_AlterUserMappingStmt) {
+        Some((*(parsetree as *mut pg_sys::AlterUserMappingStmt)).user)
+    } else if is_a(parsetree, pg_sys::NodeTag::T_DropUserMappingStmt) {
+        Some((*(parsetree as *mut pg_sys::DropUserMappingStmt)).user)
+    } else {
+        None
+    };
+
+    // PUBLIC user mappings apply to every user
+    if let Some(role) = role {
+        let user = match (*role).roletype {
+            pg_sys::RoleSpecType::ROLESPEC_PUBLIC => None,
+            _ => Some(pg_sys::get_rolespec_oid(role, true)),
+        };
+        return connection::drop_persistent_secrets(None, user);
+    }
+
+    let query = match CStr::from_ptr((*event_trigger_data).event).to_str()? {
+        "sql_drop" => {
+            "SELECT object_type, schema_name, object_name FROM pg_event_trigger_dropped_objects() WHERE object_type IN ('foreign table', 'user mapping')"
+        }
+        _ => {
+            "SELECT d.object_type, n.nspname::text, c.relname::text FROM pg_event_trigger_ddl_commands() d JOIN pg_class c ON c.oid = d.objid JOIN pg_namespace n ON n.oid = c.relnamespace WHERE d.object_type = 'foreign table'"
+        }
+    };
+
+    let objects = Spi::connect(|client| {
+        client
+            .select(query, None, None)?
+            .map(|row| {
+                Ok((
+                    row.get::<String>(1)?.unwrap_or_default(),
+                    row.get::<String>(2)?.unwrap_or_default(),
+                    row.get::<String>(3)?.unwrap_or_default(),
+                ))
+            })
+            .collect::<Result<Vec<(String, String, String)>, spi::Error>>()
+    })?;
+
+    for (object_type, schema_name, table_name) in objects {
+        // Dropping a server drops its user mappings, whose users aren't known any more
+        if object_type == "user mapping" {
+            connection::drop_persistent_secrets(None, None)?;
+        } else {
+            connection::drop_persistent_secrets(Some((&schema_name, &table_name)), None)?;
+        }
+    }
+
+    Ok(())
+}
+
 #[inline]
 fn duckdb_type_to_pg(column_name: &str, duckdb_type: &str) -> Result<String> {
     if duckdb_type == "INVALID" {
@@ -204,13 +330,6 @@ fn duckdb_type_to_pg(column_name: &str, duckdb_type: &str) -> Result<String> {
         );
     }
 
-    if duckdb_type.starts_with("ENUM") {
-        bail!(
-            "Column '{}' has type ENUM, which is not supported",
-            column_name
-        );
-    }
-
     if duckdb_type.starts_with("UNION") {
         bail!(
             "Column '{}' has type UNION, which is not supported",
@@ -259,6 +378,22 @@ fn duckdb_type_to_pg(column_name: &str, duckdb_type: &str) -> Result<String> {
         postgres_type = "JSONB".to_string();
     }
 
+    // DuckDB reads parquet's JSON logical type as JSON, which is queried as jsonb, and its ENUM
+    // logical type as strings
+    if postgres_type == "JSON" {
+        postgres_type = "JSONB".to_string();
+    }
+
+    if postgres_type.starts_with("ENUM") {
+        postgres_type = "TEXT".to_string();
+    }
+
+    // Parquet timestamps that are adjusted to UTC are read by DuckDB with a time zone, and the
+    // rest are wall clock times unless paradedb.timestamps_adjusted_to_utc says otherwise
+    if postgres_type == "TIMESTAMP" && GUCS.timestamps_adjusted_to_utc.get() {
+        postgres_type = "TIMESTAMP WITH TIME ZONE".to_string();
+    }
+
     Ok(postgres_type)
 }
 
@@ -0,0 +1,319 @@
+// Copyright (c) 2023-2024 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use pgrx::{GucContext, GucFlags, GucRegistry, GucSetting, PgLogLevel, PostgresGucEnum};
+use std::ffi::CStr;
+
+#[derive(PostgresGucEnum, Clone, Copy, PartialEq, Debug)]
+pub enum QueryLogLevel {
+    Debug5,
+    Debug4,
+    Debug3,
+    Debug2,
+    Debug1,
+    Log,
+    Info,
+    Notice,
+    Warning,
+}
+
+impl From<QueryLogLevel> for PgLogLevel {
+    fn from(level: QueryLogLevel) -> Self {
+        match level {
+            QueryLogLevel::Debug5 => PgLogLevel::DEBUG5,
+            QueryLogLevel::Debug4 => PgLogLevel::DEBUG4,
+            QueryLogLevel::Debug3 => PgLogLevel::DEBUG3,
+            QueryLogLevel::Debug2 => PgLogLevel::DEBUG2,
+            QueryLogLevel::Debug1 => PgLogLevel::DEBUG1,
+            QueryLogLevel::Log => PgLogLevel::LOG,
+            QueryLogLevel::Info => PgLogLevel::INFO,
+            QueryLogLevel::Notice => PgLogLevel::NOTICE,
+            QueryLogLevel::Warning => PgLogLevel::WARNING,
+        }
+    }
+}
+
+pub struct GucSettings {
+    // cap on the number of files a glob in the files option may match
+    pub max_glob_files: GucSetting<i32>,
+
+    // cap on the number of rows a foreign scan may return, 0 means no limit
+    pub max_result_rows: GucSetting<i32>,
+
+    // passed through to DuckDB's preserve_insertion_order setting
+    pub preserve_insertion_order: GucSetting<bool>,
+
+    // number of DuckDB threads that scans run on, 0 leaves DuckDB's setting unchanged
+    pub scan_parallelism: GucSetting<i32>,
+
+    // directory DuckDB spills to when queries exceed its memory limit
+    pub temp_directory: GucSetting<Option<&'static CStr>>,
+
+    // whether DuckDB caches parquet metadata between queries
+    pub enable_object_cache: GucSetting<bool>,
+
+    // directory that secrets are persisted to, so that new connections can reuse them
+    pub secret_directory: GucSetting<Option<&'static CStr>>,
+
+    // whether remote files are downloaded whole instead of with range requests
+    pub force_download: GucSetting<bool>,
+
+    // whether the SQL sent to DuckDB is logged before it runs
+    pub log_duckdb_queries: GucSetting<bool>,
+
+    // level that DuckDB queries are logged at
+    pub log_duckdb_queries_level: GucSetting<QueryLogLevel>,
+
+    // milliseconds after which queries running in DuckDB are interrupted, 0 disables the timeout
+    pub duckdb_query_timeout_ms: GucSetting<i32>,
+
+    // whether reading a timestamp with a time of day into a date column errors
+    pub strict_timestamp_to_date: GucSetting<bool>,
+
+    // whether timestamps without a time zone are read as UTC instants
+    pub timestamps_adjusted_to_utc: GucSetting<bool>,
+
+    // comma-separated arrow_type:postgres_type pairs whose values are read as text
+    pub type_overrides: GucSetting<Option<&'static CStr>>,
+
+    // whether reading decimals into floats that can't hold all of their digits warns
+    pub warn_decimal_precision_loss: GucSetting<bool>,
+
+    // passed through to DuckDB's TimeZone setting, unset leaves DuckDB's setting unchanged
+    pub duckdb_timezone: GucSetting<Option<&'static CStr>>,
+
+    // directory DuckDB installs and loads extensions from, unset leaves DuckDB's setting unchanged
+    pub extension_directory: GucSetting<Option<&'static CStr>>,
+
+    // whether extensions that are missing from the extension directory are downloaded
+    pub autoinstall_extensions: GucSetting<bool>,
+
+    // comma-separated extensions that users may load with load_extension
+    pub allowed_extensions: GucSetting<Option<&'static CStr>>,
+}
+
+impl GucSettings {
+    pub const fn new() -> Self {
+        Self {
+            max_glob_files: GucSetting::<i32>::new(0),
+            max_result_rows: GucSetting::<i32>::new(0),
+            preserve_insertion_order: GucSetting::<bool>::new(true),
+            scan_parallelism: GucSetting::<i32>::new(0),
+            temp_directory: GucSetting::<Option<&'static CStr>>::new(None),
+            enable_object_cache: GucSetting::<bool>::new(false),
+            secret_directory: GucSetting::<Option<&'static CStr>>::new(None),
+            force_download: GucSetting::<bool>::new(false),
+            log_duckdb_queries: GucSetting::<bool>::new(false),
+            log_duckdb_queries_level: GucSetting::<QueryLogLevel>::new(QueryLogLevel::Log),
+            duckdb_query_timeout_ms: GucSetting::<i32>::new(0),
+            strict_timestamp_to_date: GucSetting::<bool>::new(false),
+            timestamps_adjusted_to_utc: GucSetting::<bool>::new(false),
+            type_overrides: GucSetting::<Option<&'static CStr>>::new(None),
+            warn_decimal_precision_loss: GucSetting::<bool>::new(false),
+            duckdb_timezone: GucSetting::<Option<&'static CStr>>::new(None),
+            extension_directory: GucSetting::<Option<&'static CStr>>::new(None),
+            autoinstall_extensions: GucSetting::<bool>::new(true),
+            allowed_extensions: GucSetting::<Option<&'static CStr>>::new(None),
+        }
+    }
+
+    pub fn init(&self) {
+        GucRegistry::define_int_guc(
+            "paradedb.max_glob_files",
+            "Maximum number of files a glob pattern may match.",
+            "Scans over foreign tables whose files option contains a glob pattern matching more files than this fail. 0 means no limit.",
+            &self.max_glob_files,
+            0,
+            i32::MAX,
+            GucContext::Suset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_int_guc(
+            "paradedb.max_result_rows",
+            "Maximum number of rows a foreign scan may return.",
+            "Scans over foreign tables that return more rows than this fail, which protects clients from accidentally fetching huge result sets. 0 means no limit.",
+            &self.max_result_rows,
+            0,
+            i32::MAX,
+            GucContext::Suset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_bool_guc(
+            "paradedb.preserve_insertion_order",
+            "Whether DuckDB preserves the order of rows that are not explicitly ordered.",
+            "Disabling this lets DuckDB return rows in any order if the query has no ORDER BY, which reduces memory usage of large scans.",
+            &self.preserve_insertion_order,
+            GucContext::Userset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_int_guc(
+            "paradedb.scan_parallelism",
+            "Number of threads DuckDB uses to scan foreign tables.",
+            "DuckDB reads files and row groups in parallel on this many threads while Postgres converts the rows it has already produced. 0 leaves DuckDB's threads setting unchanged, which defaults to the number of CPU cores.",
+            &self.scan_parallelism,
+            0,
+            1024,
+            GucContext::Userset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_string_guc(
+            "paradedb.temp_directory",
+            "Directory that DuckDB spills to when a query exceeds its memory limit.",
+            "Large sorts, joins and aggregations write temporary files to this directory instead of running out of memory. If unset, DuckDB's temp_directory setting is left unchanged.",
+            &self.temp_directory,
+            GucContext::Suset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_bool_guc(
+            "paradedb.enable_object_cache",
+            "Whether DuckDB caches the metadata of parquet files between queries.",
+            "Enabling this lets repeated scans of the same parquet files skip re-reading their footers.",
+            &self.enable_object_cache,
+            GucContext::Userset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_string_guc(
+            "paradedb.secret_directory",
+            "Directory that DuckDB secrets are persisted to.",
+            "If set, the secrets created for foreign tables are written to this directory and reused by new connections. Only takes effect if set before the connection first uses DuckDB.",
+            &self.secret_directory,
+            GucContext::Suset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_bool_guc(
+            "paradedb.force_download",
+            "Whether DuckDB downloads remote files whole instead of reading them with range requests.",
+            "Enabling this works around object stores and HTTP servers that handle range requests poorly, at the cost of downloading every file a query reads in full.",
+            &self.force_download,
+            GucContext::Userset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_bool_guc(
+            "paradedb.log_duckdb_queries",
+            "Whether the queries sent to DuckDB are logged.",
+            "Enabling this logs the SQL that foreign table scans and pushed down queries run in DuckDB, which is useful for checking which filters, limits and sorts were pushed down.",
+            &self.log_duckdb_queries,
+            GucContext::Suset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_enum_guc(
+            "paradedb.log_duckdb_queries_level",
+            "Message level that DuckDB queries are logged at.",
+            "Only takes effect if paradedb.log_duckdb_queries is enabled. Valid values are debug5 through debug1, log, info, notice and warning.",
+            &self.log_duckdb_queries_level,
+            GucContext::Suset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_int_guc(
+            "paradedb.duckdb_query_timeout_ms",
+            "Time after which queries running in DuckDB are canceled.",
+            "Foreign table scans and pushed down queries that run in DuckDB for longer than this are interrupted, independently of statement_timeout. 0 disables the timeout.",
+            &self.duckdb_query_timeout_ms,
+            0,
+            i32::MAX,
+            GucContext::Userset,
+            GucFlags::UNIT_MS,
+        );
+
+        GucRegistry::define_bool_guc(
+            "paradedb.strict_timestamp_to_date",
+            "Whether timestamps with a time of day can't be read into date columns.",
+            "Timestamp columns of foreign tables that are declared as date are read as the date of each timestamp. Enabling this makes timestamps that aren't at midnight error instead of silently dropping their time of day.",
+            &self.strict_timestamp_to_date,
+            GucContext::Userset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_bool_guc(
+            "paradedb.timestamps_adjusted_to_utc",
+            "Whether timestamps without a time zone are read as UTC instants.",
+            "Parquet timestamps that are not adjusted to UTC are wall clock times, which are created as timestamp columns and read into timestamptz columns in the session's time zone. Enabling this treats them as adjusted to UTC, for files whose writers did not set the flag.",
+            &self.timestamps_adjusted_to_utc,
+            GucContext::Userset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_string_guc(
+            "paradedb.type_overrides",
+            "Comma-separated Arrow and Postgres type pairs whose values are read as text.",
+            "Each pair looks like decimal:text. Values of the Arrow type that are read into a column of the paired Postgres type, which must be text, varchar or bpchar, are read as their text representation in place of their default conversion.",
+            &self.type_overrides,
+            GucContext::Userset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_bool_guc(
+            "paradedb.warn_decimal_precision_loss",
+            "Whether reading decimals into floats that can't represent them exactly warns.",
+            "Decimal columns of foreign tables that are declared as real or double precision are rounded to the float's precision. Enabling this warns once per scan for each such column whose decimal precision exceeds the digits the float can hold exactly.",
+            &self.warn_decimal_precision_loss,
+            GucContext::Userset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_string_guc(
+            "paradedb.duckdb_timezone",
+            "Time zone that DuckDB uses for timestamps with time zones.",
+            "Pushed down queries that extract parts of, truncate, cast or format timestamptz values use this time zone in DuckDB. If unset, DuckDB's TimeZone setting is left unchanged, which defaults to the time zone of the server's environment.",
+            &self.duckdb_timezone,
+            GucContext::Userset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_string_guc(
+            "paradedb.extension_directory",
+            "Directory that DuckDB installs and loads extensions from.",
+            "Extensions that formats and object stores need, such as iceberg, spatial or azure, are loaded from this directory, so that they can be provided without internet access. If unset, DuckDB's extension_directory setting is left unchanged.",
+            &self.extension_directory,
+            GucContext::Suset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_bool_guc(
+            "paradedb.autoinstall_extensions",
+            "Whether DuckDB extensions that are not installed are downloaded.",
+            "Disabling this makes reading a format or object store whose extension is not already in the extension directory fail instead of downloading the extension.",
+            &self.autoinstall_extensions,
+            GucContext::Suset,
+            GucFlags::default(),
+        );
+
+        GucRegistry::define_string_guc(
+            "paradedb.allowed_extensions",
+            "Comma-separated list of DuckDB extensions that can be loaded with load_extension.",
+            "Extensions in this list, such as core extensions like fts or community extensions like h3, can be installed and loaded into the DuckDB connection of the current session by any user. If unset, no extensions can be loaded with load_extension.",
+            &self.allowed_extensions,
+            GucContext::Suset,
+            GucFlags::default(),
+        );
+    }
+}
+
+impl Default for GucSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -15,16 +15,18 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use pgrx::*;
 use std::ffi::CStr;
 
 use crate::duckdb::connection;
+use crate::fdw::base::analyzing_foreign_scans;
 
 use super::query::*;
 
 #[cfg(debug_assertions)]
 use crate::DEBUG_GUCS;
+use crate::GUCS;
 
 #[allow(deprecated)]
 pub async fn executor_run(
@@ -55,6 +57,8 @@ pub async fn executor_run(
     if rtable.is_null()
         || query_desc.operation != pg_sys::CmdType::CMD_SELECT
         || !is_duckdb_query
+        // EXPLAIN ANALYZE that shows foreign scans runs the plan node by node to measure each node
+        || analyzing_foreign_scans()
         // Tech Debt: Find a less hacky way to let COPY/CREATE go through
         || query.to_lowercase().starts_with("copy")
         || query.to_lowercase().starts_with("create")
@@ -64,6 +68,8 @@ pub async fn executor_run(
         return Ok(());
     }
 
+    connection::apply_settings()?;
+
     // Set DuckDB search path according search path in Postgres
     // Make sure it could find unqualified relations.
     set_search_path_by_pg()?;
@@ -71,6 +77,10 @@ pub async fn executor_run(
     match connection::create_arrow(query.as_str()) {
         Err(err) => {
             connection::clear_arrow();
+            // Running an interrupted query again through the FDW would only double its runtime
+            if connection::is_interrupted(&err) {
+                return Err(err);
+            }
             fallback_warning!(err.to_string());
             prev_hook(query_desc, direction, count, execute_once);
             return Ok(());
@@ -82,16 +92,31 @@ pub async fn executor_run(
         _ => {}
     }
 
-    match connection::get_batches() {
-        Ok(batches) => write_batches_to_slots(query_desc, batches)?,
-        Err(err) => {
-            connection::clear_arrow();
-            fallback_warning!(err.to_string());
-            prev_hook(query_desc, direction, count, execute_once);
-            return Ok(());
+    // Rows are counted as batches are fetched so a result over the limit is never held in full.
+    // Rows may already have been sent when fetching fails, so errors are raised from here on.
+    let mut rows = 0;
+    let result = write_batches_to_slots(query_desc, || {
+        let batch = connection::get_next_batch()?;
+        if let Some(batch) = &batch {
+            rows += batch.num_rows();
+            check_max_result_rows(rows)?;
         }
+        Ok(batch)
+    });
+    if let Err(err) = result {
+        connection::clear_arrow();
+        return Err(err);
     }
 
     connection::clear_arrow();
     Ok(())
 }
+
+// Queries that are pushed down to DuckDB are capped like the foreign scans they replace
+fn check_max_result_rows(rows: usize) -> Result<()> {
+    let max_result_rows = GUCS.max_result_rows.get();
+    if max_result_rows > 0 && rows > max_result_rows as usize {
+        bail!("query returned more than paradedb.max_result_rows ({max_result_rows}) rows");
+    }
+    Ok(())
+}
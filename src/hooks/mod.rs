@@ -16,13 +16,15 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 #[macro_use]
-mod query;
+pub mod query;
 mod executor;
 mod utility;
 
 use async_std::task::block_on;
 use pgrx::*;
 
+use crate::fdw::progress;
+
 pub struct ExtensionHook;
 
 #[allow(deprecated)]
@@ -90,4 +92,8 @@ impl hooks::PgHooks for ExtensionHook {
 
         HookResult::new(())
     }
+
+    fn abort(&mut self) {
+        progress::end_scan_progress();
+    }
 }
@@ -85,6 +85,11 @@ pub fn set_search_path_by_pg() -> Result<()> {
     // Filter schemas. If one of schemas doesn't exist, it will cause the DuckDB 'SET search_path' to fail.
     search_path.retain(|schema| duckdb_schemas.contains(schema));
 
+    // DuckDB's default schema holds the functions and types created for Postgres compatibility
+    if !search_path.iter().any(|schema| schema == "main") {
+        search_path.push("main".to_string());
+    }
+
     // Set duckdb catalog search path
     connection::set_search_path(search_path)?;
 
@@ -130,10 +135,11 @@ pub fn is_duckdb_query(relations: &[PgRelation]) -> bool {
         })
 }
 
+// Batches are converted as they are fetched, so only one is held in memory at a time
 #[inline]
 pub fn write_batches_to_slots<T: WhoAllocated>(
     query_desc: PgBox<pg_sys::QueryDesc, T>,
-    mut batches: Vec<RecordBatch>,
+    mut next_batch: impl FnMut() -> Result<Option<RecordBatch>>,
 ) -> Result<()> {
     // Convert the DataFusion batches to Postgres tuples and send them to the destination
     unsafe {
@@ -151,7 +157,31 @@ pub fn write_batches_to_slots<T: WhoAllocated>(
             .receiveSlot
             .ok_or_else(|| anyhow!("receiveSlot not found"))?;
 
-        for batch in batches.iter_mut() {
+        let mut first_batch = true;
+        while let Some(batch) = next_batch()? {
+            if first_batch {
+                for (col_index, attribute) in tuple_desc.iter().enumerate() {
+                    warn_decimal_precision_loss(
+                        batch.column(col_index).data_type(),
+                        attribute.atttypid,
+                        attribute.name(),
+                    );
+                }
+                first_batch = false;
+            }
+
+            let cell_readers = tuple_desc
+                .iter()
+                .enumerate()
+                .map(|(col_index, attribute)| {
+                    CellReader::resolve(
+                        batch.column(col_index).data_type(),
+                        attribute.atttypid,
+                        attribute.atttypmod,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+
             for row_index in 0..batch.num_rows() {
                 let tuple_table_slot =
                     pg_sys::MakeTupleTableSlot(query_desc.tupDesc, &pg_sys::TTSOpsVirtual);
@@ -166,7 +196,12 @@ pub fn write_batches_to_slots<T: WhoAllocated>(
                     let tts_value = (*tuple_table_slot).tts_values.add(col_index);
                     let tts_isnull = (*tuple_table_slot).tts_isnull.add(col_index);
 
-                    match column.get_cell(row_index, attribute.atttypid, attribute.name())? {
+                    match cell_readers[col_index].read(
+                        column,
+                        row_index,
+                        attribute.atttypid,
+                        attribute.name(),
+                    )? {
                         Some(cell) => {
                             if let Some(datum) = cell.into_datum() {
                                 *tts_value = datum;
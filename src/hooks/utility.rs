@@ -79,6 +79,9 @@ pub async fn process_utility_hook(
         state
     };
 
+    // Kept until the EXPLAIN that Postgres runs ends, so that its foreign scans are explained
+    let mut foreign_scan_explain = None;
+
     let need_exec_prev_hook = match stmt_type {
         pg_sys::NodeTag::T_PrepareStmt => prepare_query(
             parse_state,
@@ -113,11 +116,14 @@ pub async fn process_utility_hook(
             deallocate_query(pstmt.utilityStmt as *mut pg_sys::DeallocateStmt)?
         }
 
-        pg_sys::NodeTag::T_ExplainStmt => explain_query(
-            query_string,
-            pstmt.utilityStmt as *mut pg_sys::ExplainStmt,
-            dest.as_ptr(),
-        )?,
+        pg_sys::NodeTag::T_ExplainStmt => {
+            foreign_scan_explain = explain_query(
+                query_string,
+                pstmt.utilityStmt as *mut pg_sys::ExplainStmt,
+                dest.as_ptr(),
+            )?;
+            foreign_scan_explain.is_some()
+        }
         pg_sys::NodeTag::T_ViewStmt => {
             let utility_stmt = unsafe {
                 pg_sys::copyObjectImpl(pstmt.utilityStmt as *const std::ffi::c_void)
@@ -146,6 +152,7 @@ pub async fn process_utility_hook(
         );
     }
 
+    drop(foreign_scan_explain);
     Ok(())
 }
 
@@ -24,6 +24,7 @@ use pgrx::{error, pg_sys};
 use super::parse_query_from_utility_stmt;
 use crate::{
     duckdb::connection,
+    fdw::base::ForeignScanExplain,
     hooks::query::{get_query_relations, is_duckdb_query, set_search_path_by_pg},
 };
 
@@ -36,18 +37,30 @@ struct ExplainState {
     style: Style,
 }
 
+// Returns the state that foreign scans are explained with if Postgres should run the EXPLAIN,
+// which must be kept until it ends, or None if the EXPLAIN was handled here
 pub fn explain_query(
     query_string: &core::ffi::CStr,
     stmt: *mut pg_sys::ExplainStmt,
     dest: *mut pg_sys::DestReceiver,
-) -> Result<bool> {
+) -> Result<Option<ForeignScanExplain>> {
+    // Machine-readable formats and the merged style are Postgres' own EXPLAIN, whose Foreign Scan
+    // nodes show the SQL that each of them runs in DuckDB
+    let structured = is_structured_format(stmt);
+    if take_merged_style(stmt) || structured {
+        return Ok(Some(ForeignScanExplain::begin(
+            structured,
+            is_analyze(stmt),
+        )));
+    }
+
     let query = unsafe { (*stmt).query as *mut pg_sys::Query };
 
     let query_relations = get_query_relations(unsafe { (*query).rtable });
     if unsafe { (*query).commandType } != pg_sys::CmdType::CMD_SELECT
         || !is_duckdb_query(&query_relations)
     {
-        return Ok(true);
+        return Ok(Some(ForeignScanExplain::begin(false, false)));
     }
 
     let state = parse_explain_options(unsafe { (*stmt).options });
@@ -92,7 +105,69 @@ pub fn explain_query(
         pg_sys::end_tup_output(tstate);
     }
 
-    Ok(false)
+    Ok(None)
+}
+
+// Whether the EXPLAIN has the ANALYZE option
+fn is_analyze(stmt: *mut pg_sys::ExplainStmt) -> bool {
+    let options = unsafe { (*stmt).options };
+    if options.is_null() {
+        return false;
+    }
+
+    unsafe {
+        let elements = (*options).elements;
+
+        (0..(*options).length as isize).any(|i| {
+            let opt = (*elements.offset(i)).ptr_value as *mut pg_sys::DefElem;
+            CStr::from_ptr((*opt).defname).to_str() == Ok("analyze") && pg_sys::defGetBoolean(opt)
+        })
+    }
+}
+
+// Whether the EXPLAIN has a FORMAT other than text, e.g. FORMAT JSON
+fn is_structured_format(stmt: *mut pg_sys::ExplainStmt) -> bool {
+    let options = unsafe { (*stmt).options };
+    if options.is_null() {
+        return false;
+    }
+
+    unsafe {
+        let elements = (*options).elements;
+
+        (0..(*options).length as isize).any(|i| {
+            let opt = (*elements.offset(i)).ptr_value as *mut pg_sys::DefElem;
+            CStr::from_ptr((*opt).defname).to_str() == Ok("format")
+                && CStr::from_ptr(pg_sys::defGetString(opt)).to_str() != Ok("text")
+        })
+    }
+}
+
+// Removes STYLE merged from the options of an EXPLAIN, since Postgres does not know the option
+fn take_merged_style(stmt: *mut pg_sys::ExplainStmt) -> bool {
+    let options = unsafe { (*stmt).options };
+    if options.is_null() {
+        return false;
+    }
+
+    unsafe {
+        let elements = (*options).elements;
+
+        for i in 0..(*options).length as isize {
+            let opt = (*elements.offset(i)).ptr_value as *mut pg_sys::DefElem;
+            if CStr::from_ptr((*opt).defname).to_str() != Ok("style") {
+                continue;
+            }
+
+            if CStr::from_ptr(pg_sys::defGetString(opt)).to_str() == Ok("merged") {
+                (*stmt).options =
+                    pg_sys::list_delete_ptr(pg_sys::list_copy(options), opt as *mut _);
+                return true;
+            }
+        }
+    }
+
+    false
 }
 
 fn parse_explain_options(options: *const pg_sys::List) -> ExplainState {
@@ -187,7 +187,10 @@ pub fn execute_query<T: pgbox::WhoAllocated>(
     }
 
     match connection::get_batches() {
-        Ok(batches) => write_batches_to_slots(query_desc, batches)?,
+        Ok(batches) => {
+            let mut batches = batches.into_iter();
+            write_batches_to_slots(query_desc, || Ok(batches.next()))?
+        }
         Err(err) => {
             connection::clear_arrow();
             fallback_warning!(err.to_string());
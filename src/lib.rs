@@ -21,17 +21,21 @@ mod api;
 mod debug_guc;
 mod duckdb;
 mod fdw;
+mod guc;
 mod hooks;
 mod schema;
 
 #[cfg(debug_assertions)]
 use crate::debug_guc::DebugGucSettings;
+use guc::GucSettings;
 use hooks::ExtensionHook;
 use pgrx::*;
 
 #[cfg(debug_assertions)]
 pub static DEBUG_GUCS: DebugGucSettings = DebugGucSettings::new();
 
+pub static GUCS: GucSettings = GucSettings::new();
+
 pg_module_magic!();
 
 static mut EXTENSION_HOOK: ExtensionHook = ExtensionHook;
@@ -44,6 +48,9 @@ pub extern "C" fn _PG_init() {
         register_hook(&mut EXTENSION_HOOK)
     };
 
+    GUCS.init();
+    fdw::progress::init();
+
     #[cfg(debug_assertions)]
     DEBUG_GUCS.init();
 }
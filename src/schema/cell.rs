@@ -16,28 +16,39 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use anyhow::{anyhow, bail, Result};
+use chrono::NaiveTime;
 use duckdb::arrow::array::types::{
-    ArrowTemporalType, Date32Type, Date64Type, Decimal128Type, IntervalDayTimeType,
-    IntervalMonthDayNanoType, IntervalYearMonthType, Time32MillisecondType, Time32SecondType,
-    Time64MicrosecondType, Time64NanosecondType, TimestampMicrosecondType,
-    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt16Type, UInt32Type,
-    UInt64Type, UInt8Type,
+    ArrowTemporalType, Date32Type, Date64Type, Decimal128Type, DurationMicrosecondType,
+    DurationMillisecondType, DurationNanosecondType, DurationSecondType, Int16Type, Int32Type,
+    Int64Type, Int8Type, IntervalDayTimeType, IntervalMonthDayNanoType, IntervalYearMonthType,
+    Time32MillisecondType, Time32SecondType, Time64MicrosecondType, Time64NanosecondType,
+    TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+    TimestampSecondType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
 use duckdb::arrow::array::{
     timezone::Tz, Array, ArrayAccessor, ArrayRef, ArrowPrimitiveType, AsArray, BinaryArray,
-    BooleanArray, Decimal128Array, Float16Array, Float32Array, Float64Array, GenericByteArray,
-    Int16Array, Int32Array, Int64Array, Int8Array, LargeBinaryArray, StringArray,
+    BooleanArray, Decimal128Array, DictionaryArray, Float16Array, Float32Array, Float64Array,
+    GenericByteArray, Int16Array, Int32Array, Int64Array, Int8Array, LargeBinaryArray,
+    LargeListViewArray, ListArray, ListViewArray, StringArray, UInt16Array, UInt32Array,
+    UInt64Array, UInt8Array,
 };
-use duckdb::arrow::datatypes::{DataType, DecimalType, GenericStringType, IntervalUnit, TimeUnit};
+use duckdb::arrow::buffer::{NullBuffer, OffsetBuffer};
+use duckdb::arrow::datatypes::{
+    f16, ArrowDictionaryKeyType, ArrowNativeType, DataType, DecimalType, FieldRef,
+    GenericStringType, IntervalUnit, TimeUnit,
+};
+use duckdb::arrow::util::display::{ArrayFormatter, FormatOptions};
 use pgrx::*;
 use serde_json::{value::Number, Map, Value};
 use std::any::type_name;
+use std::ffi::{CStr, CString};
 use std::fmt::Debug;
 use std::str::FromStr;
 use std::sync::Arc;
 use supabase_wrappers::interface::Cell;
 
 use super::datetime::*;
+use crate::GUCS;
 
 type LargeStringArray = GenericByteArray<GenericStringType<i64>>;
 
@@ -150,6 +161,7 @@ where
         for<'a> &'a A: IntoIterator,
         for<'a> <&'a A as IntoIterator>::Item: IntoDatum + Clone,
         for<'a> Vec<T>: FromIterator<<&'a A as IntoIterator>::Item>,
+        T: Default,
     {
         let downcast_array = self.as_list::<i32>();
 
@@ -158,15 +170,108 @@ where
         }
 
         let binding = downcast_array.value(index);
+
+        // Lists whose elements are all null can be written with a null element type
+        if binding.data_type() == &DataType::Null {
+            return Ok(Some((0..binding.len()).map(|_| T::default()).collect()));
+        }
+
+        let value = binding.as_any().downcast_ref::<A>().ok_or_else(|| {
+            anyhow!(
+                "failed to downcast list of {:?} to {:?}",
+                binding.data_type(),
+                type_name::<A>()
+            )
+        })?;
+
+        Ok(Some(value.into_iter().collect::<Vec<T>>()))
+    }
+}
+
+pub trait GetDecimalListValue
+where
+    Self: Array + AsArray,
+{
+    fn get_decimal_list_value(&self, index: usize) -> Result<Option<Vec<Option<AnyNumeric>>>> {
+        let downcast_array = self.as_list::<i32>();
+
+        if downcast_array.nulls().is_some() && downcast_array.is_null(index) {
+            return Ok(None);
+        }
+
+        let binding = downcast_array.value(index);
+        let (precision, scale) = match binding.data_type() {
+            DataType::Decimal128(precision, scale) => (*precision, *scale),
+            unsupported => bail!(
+                "List with {:?} types cannot be read as numeric[]",
+                unsupported
+            ),
+        };
         let value = binding
             .as_any()
-            .downcast_ref::<A>()
+            .downcast_ref::<Decimal128Array>()
             .ok_or_else(|| anyhow!("failed to downcast list array"))?;
 
-        Ok(Some(value.into_iter().collect::<Vec<T>>()))
+        Ok(Some(
+            value
+                .iter()
+                .map(|decimal| {
+                    decimal
+                        .map(|decimal| {
+                            AnyNumeric::from_str(&Decimal128Type::format_decimal(
+                                decimal, precision, scale,
+                            ))
+                        })
+                        .transpose()
+                })
+                .collect::<Result<Vec<Option<AnyNumeric>>, _>>()?,
+        ))
     }
 }
 
+pub trait GetDictionaryStringValue
+where
+    Self: Array + AsArray,
+{
+    // DuckDB returns enums as dictionaries of their labels
+    fn get_dictionary_string_value(&self, index: usize) -> Result<Option<String>> {
+        if self.is_null(index) {
+            return Ok(None);
+        }
+
+        let (key, values) = match self.data_type() {
+            DataType::Dictionary(key_type, _) => match key_type.as_ref() {
+                DataType::Int8 => dictionary_entry(self.as_dictionary::<Int8Type>(), index),
+                DataType::Int16 => dictionary_entry(self.as_dictionary::<Int16Type>(), index),
+                DataType::Int32 => dictionary_entry(self.as_dictionary::<Int32Type>(), index),
+                DataType::Int64 => dictionary_entry(self.as_dictionary::<Int64Type>(), index),
+                DataType::UInt8 => dictionary_entry(self.as_dictionary::<UInt8Type>(), index),
+                DataType::UInt16 => dictionary_entry(self.as_dictionary::<UInt16Type>(), index),
+                DataType::UInt32 => dictionary_entry(self.as_dictionary::<UInt32Type>(), index),
+                DataType::UInt64 => dictionary_entry(self.as_dictionary::<UInt64Type>(), index),
+                unsupported => bail!("{:?} is not a dictionary key type", unsupported),
+            },
+            unsupported => bail!("{:?} is not a dictionary type", unsupported),
+        };
+
+        match values.data_type() {
+            DataType::Utf8 => Ok(Some(values.as_string::<i32>().value(key).to_string())),
+            DataType::LargeUtf8 => Ok(Some(values.as_string::<i64>().value(key).to_string())),
+            unsupported => bail!(
+                "Dictionary with {:?} values cannot be read as strings",
+                unsupported
+            ),
+        }
+    }
+}
+
+fn dictionary_entry<K: ArrowDictionaryKeyType>(
+    array: &DictionaryArray<K>,
+    index: usize,
+) -> (usize, &ArrayRef) {
+    (array.keys().value(index).as_usize(), array.values())
+}
+
 pub trait GetStringListValue
 where
     Self: Array + AsArray,
@@ -193,6 +298,40 @@ where
     }
 }
 
+pub trait GetStringMapValue
+where
+    Self: Array + AsArray,
+{
+    fn get_string_map_value(&self, index: usize) -> Result<Option<Vec<(String, Option<String>)>>> {
+        let downcast_array = self.as_map();
+
+        if downcast_array.nulls().is_some() && downcast_array.is_null(index) {
+            return Ok(None);
+        }
+
+        let entries = downcast_array.value(index);
+        let keys = entries
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| anyhow!("failed to downcast map keys"))?;
+        let values = entries
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| anyhow!("failed to downcast map values"))?;
+
+        Ok(Some(
+            keys.iter()
+                .zip(values.iter())
+                .filter_map(|(key, value)| {
+                    key.map(|key| (key.to_string(), value.map(|value| value.to_string())))
+                })
+                .collect(),
+        ))
+    }
+}
+
 pub trait GetStructValue
 where
     Self: Array + AsArray,
@@ -204,122 +343,165 @@ where
             return Ok(None);
         }
 
-        let column_names = downcast_array.column_names();
         let fields = downcast_array.fields();
         let mut map = Map::new();
 
-        for column_name in column_names {
-            if let Some((column_index, field)) = fields.find(column_name) {
-                match field.data_type() {
-                    DataType::Boolean => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_primitive_value::<BooleanArray>(index)? {
-                            map.insert(column_name.to_string(), Value::Bool(value));
-                        }
+        // Fields are read by position rather than looked up by name, so that each field reads its
+        // own column even if the struct repeats a name. The first field with a name is kept.
+        for (column_index, field) in fields.iter().enumerate() {
+            let column_name = field.name();
+            if fields[..column_index]
+                .iter()
+                .any(|previous| previous.name() == column_name)
+            {
+                continue;
+            }
+
+            match field.data_type() {
+                DataType::Boolean => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_primitive_value::<BooleanArray>(index)? {
+                        map.insert(column_name.to_string(), Value::Bool(value));
                     }
-                    DataType::Int8 => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_primitive_value::<Int8Array>(index)? {
-                            map.insert(column_name.to_string(), Value::Number(Number::from(value)));
-                        }
+                }
+                DataType::Int8 => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_primitive_value::<Int8Array>(index)? {
+                        map.insert(column_name.to_string(), Value::Number(Number::from(value)));
                     }
-                    DataType::Int16 => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_primitive_value::<Int16Array>(index)? {
-                            map.insert(column_name.to_string(), Value::Number(Number::from(value)));
-                        }
+                }
+                DataType::Int16 => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_primitive_value::<Int16Array>(index)? {
+                        map.insert(column_name.to_string(), Value::Number(Number::from(value)));
                     }
-                    DataType::Int32 => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_primitive_value::<Int32Array>(index)? {
-                            map.insert(column_name.to_string(), Value::Number(Number::from(value)));
-                        }
+                }
+                DataType::Int32 => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_primitive_value::<Int32Array>(index)? {
+                        map.insert(column_name.to_string(), Value::Number(Number::from(value)));
                     }
-                    DataType::Int64 => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_primitive_value::<Int64Array>(index)? {
-                            map.insert(column_name.to_string(), Value::Number(Number::from(value)));
-                        }
+                }
+                DataType::Int64 => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_primitive_value::<Int64Array>(index)? {
+                        map.insert(column_name.to_string(), Value::Number(Number::from(value)));
                     }
-                    DataType::UInt8 => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_uint_value::<UInt8Type>(index)? {
-                            map.insert(column_name.to_string(), Value::Number(Number::from(value)));
-                        }
+                }
+                DataType::UInt8 => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_uint_value::<UInt8Type>(index)? {
+                        map.insert(column_name.to_string(), Value::Number(Number::from(value)));
                     }
-                    DataType::UInt16 => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_uint_value::<UInt16Type>(index)? {
-                            map.insert(column_name.to_string(), Value::Number(Number::from(value)));
-                        }
+                }
+                DataType::UInt16 => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_uint_value::<UInt16Type>(index)? {
+                        map.insert(column_name.to_string(), Value::Number(Number::from(value)));
                     }
-                    DataType::UInt32 => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_uint_value::<UInt32Type>(index)? {
-                            map.insert(column_name.to_string(), Value::Number(Number::from(value)));
-                        }
+                }
+                DataType::UInt32 => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_uint_value::<UInt32Type>(index)? {
+                        map.insert(column_name.to_string(), Value::Number(Number::from(value)));
                     }
-                    DataType::UInt64 => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_uint_value::<UInt64Type>(index)? {
-                            map.insert(column_name.to_string(), Value::Number(Number::from(value)));
-                        }
+                }
+                DataType::UInt64 => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_uint_value::<UInt64Type>(index)? {
+                        map.insert(column_name.to_string(), Value::Number(Number::from(value)));
                     }
-                    DataType::Float16 => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_primitive_value::<Float16Array>(index)? {
-                            map.insert(
-                                column_name.to_string(),
-                                Value::Number(Number::from_f64(value.to_f32() as f64).ok_or_else(
-                                    || anyhow!("failed to convert {:?} to f64", value),
-                                )?),
-                            );
-                        }
+                }
+                DataType::Float16 => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_primitive_value::<Float16Array>(index)? {
+                        map.insert(
+                            column_name.to_string(),
+                            Value::Number(
+                                Number::from_f64(value.to_f32() as f64).ok_or_else(|| {
+                                    anyhow!("failed to convert {:?} to f64", value)
+                                })?,
+                            ),
+                        );
                     }
-                    DataType::Float32 => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_primitive_value::<Float32Array>(index)? {
-                            map.insert(
-                                column_name.to_string(),
-                                Value::Number(Number::from_f64(value as f64).ok_or_else(|| {
+                }
+                DataType::Float32 => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_primitive_value::<Float32Array>(index)? {
+                        map.insert(
+                            column_name.to_string(),
+                            Value::Number(
+                                Number::from_f64(value as f64).ok_or_else(|| {
                                     anyhow!("failed to convert {:?} to f64", value)
-                                })?),
-                            );
-                        }
+                                })?,
+                            ),
+                        );
                     }
-                    DataType::Float64 => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_primitive_value::<Float64Array>(index)? {
-                            map.insert(
-                                column_name.to_string(),
-                                Value::Number(Number::from_f64(value).ok_or_else(|| {
+                }
+                DataType::Float64 => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_primitive_value::<Float64Array>(index)? {
+                        map.insert(
+                            column_name.to_string(),
+                            Value::Number(
+                                Number::from_f64(value).ok_or_else(|| {
                                     anyhow!("failed to convert {:?} to f64", value)
-                                })?),
-                            );
-                        }
+                                })?,
+                            ),
+                        );
                     }
-                    DataType::Decimal128(p, s) => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_decimal_value::<f64>(index, *p, *s)? {
-                            map.insert(
-                                column_name.to_string(),
-                                Value::Number(Number::from_f64(value).ok_or_else(|| {
+                }
+                DataType::Decimal128(p, s) => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_decimal_value::<f64>(index, *p, *s)? {
+                        map.insert(
+                            column_name.to_string(),
+                            Value::Number(
+                                Number::from_f64(value).ok_or_else(|| {
                                     anyhow!("failed to convert {:?} to f64", value)
-                                })?),
-                            );
-                        }
+                                })?,
+                            ),
+                        );
                     }
-                    DataType::Utf8 => {
-                        let column = downcast_array.column(column_index);
-                        if let Some(value) = column.get_primitive_value::<StringArray>(index)? {
-                            map.insert(column_name.to_string(), Value::String(value.to_string()));
-                        }
+                }
+                DataType::Utf8 => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_primitive_value::<StringArray>(index)? {
+                        map.insert(column_name.to_string(), Value::String(value.to_string()));
+                    }
+                }
+                DataType::Struct(_) => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_struct_value(index)? {
+                        map.insert(column_name.to_string(), value.0);
                     }
-                    unsupported => bail!(
-                        "Structs with {:?} field types are not yet supported",
-                        unsupported
-                    ),
                 }
+                DataType::List(_) => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = column.get_list_value(index)? {
+                        map.insert(column_name.to_string(), value.0);
+                    }
+                }
+                DataType::Date32
+                | DataType::Date64
+                | DataType::Time32(_)
+                | DataType::Time64(_)
+                | DataType::Timestamp(_, _) => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = temporal_json_value(column, index)? {
+                        map.insert(column_name.to_string(), value);
+                    }
+                }
+                DataType::Duration(unit) => {
+                    let column = downcast_array.column(column_index);
+                    if let Some(value) = duration_json_value(column, index, unit)? {
+                        map.insert(column_name.to_string(), value);
+                    }
+                }
+                unsupported => bail!(
+                    "Structs with {:?} field types are not yet supported",
+                    unsupported
+                ),
             }
         }
 
@@ -473,13 +655,13 @@ where
 
         match downcast_array.nulls().is_some() && downcast_array.is_null(index) {
             false => {
-                const MICROSECONDS_IN_MILLISECOND: i32 = 1_000;
+                const MICROSECONDS_IN_MILLISECOND: i64 = 1_000;
                 let interval = downcast_array.value(index);
 
                 Ok(Some(datum::Interval::new(
                     0,
                     interval.days,
-                    (interval.milliseconds * MICROSECONDS_IN_MILLISECOND) as i64,
+                    interval.milliseconds as i64 * MICROSECONDS_IN_MILLISECOND,
                 )?))
             }
             true => Ok(None),
@@ -527,6 +709,37 @@ where
     }
 }
 
+pub trait GetDurationValue
+where
+    Self: Array + AsArray,
+{
+    fn get_duration_value<T>(&self, index: usize) -> Result<Option<datum::Interval>>
+    where
+        T: ArrowPrimitiveType<Native = i64> + ArrowTemporalType,
+    {
+        let downcast_array = self.as_primitive::<T>();
+
+        match downcast_array.nulls().is_some() && downcast_array.is_null(index) {
+            false => {
+                // Durations have no months or days, so they are read as a number of microseconds
+                let microseconds = downcast_array
+                    .value_as_duration(index)
+                    .and_then(|duration| duration.num_microseconds())
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "duration {} of {:?} is out of range for interval",
+                            downcast_array.value(index),
+                            downcast_array.data_type()
+                        )
+                    })?;
+
+                Ok(Some(datum::Interval::new(0, 0, microseconds)?))
+            }
+            true => Ok(None),
+        }
+    }
+}
+
 pub trait GetTimeValue
 where
     Self: Array + AsArray,
@@ -564,15 +777,43 @@ where
 
         match downcast_array.nulls().is_some() && downcast_array.is_null(index) {
             false => {
-                let datetime = downcast_array
-                    .value_as_datetime(index)
-                    .ok_or_else(|| anyhow!("failed to convert timestamp to NaiveDateTime"))?;
+                let datetime = downcast_array.value_as_datetime(index).ok_or_else(|| {
+                    timestamp_out_of_range(downcast_array.value(index), downcast_array.data_type())
+                })?;
 
                 Ok(Some(datum::Timestamp::try_from(DateTimeNoTz(datetime))?))
             }
             true => Ok(None),
         }
     }
+
+    // Reads a timestamp as its date, in UTC if the timestamp has a time zone. With require_midnight,
+    // timestamps with a time of day error rather than having it dropped.
+    fn get_timestamp_date_value<T>(
+        &self,
+        index: usize,
+        require_midnight: bool,
+    ) -> Result<Option<datum::Date>>
+    where
+        T: ArrowPrimitiveType<Native = i64> + ArrowTemporalType,
+    {
+        let downcast_array = self.as_primitive::<T>();
+
+        match downcast_array.nulls().is_some() && downcast_array.is_null(index) {
+            false => {
+                let datetime = downcast_array.value_as_datetime(index).ok_or_else(|| {
+                    timestamp_out_of_range(downcast_array.value(index), downcast_array.data_type())
+                })?;
+
+                if require_midnight && datetime.time() != NaiveTime::MIN {
+                    bail!("timestamp {datetime} has a time of day, which paradedb.strict_timestamp_to_date does not allow to be dropped when it is read as a date");
+                }
+
+                Ok(Some(datum::Date::try_from(Date(datetime.date()))?))
+            }
+            true => Ok(None),
+        }
+    }
 }
 
 pub trait GetTimestampTzValue
@@ -592,20 +833,28 @@ where
             return Ok(None);
         }
 
-        match tz {
-            Some(tz) => {
+        // Zoned values are instants since the UTC epoch whatever their zone, so they are read
+        // in UTC rather than as wall clock times, which are ambiguous when clocks go back. Values
+        // without a zone are wall clock times unless paradedb.timestamps_adjusted_to_utc is set.
+        match tz.is_some() || GUCS.timestamps_adjusted_to_utc.get() {
+            true => {
                 let datetime = downcast_array
-                    .value_as_datetime_with_tz(index, Tz::from_str(&tz)?)
-                    .ok_or_else(|| anyhow!("failed to convert timestamp to NaiveDateTime"))?;
+                    .value_as_datetime_with_tz(index, Tz::from_str("UTC")?)
+                    .ok_or_else(|| {
+                        timestamp_out_of_range(
+                            downcast_array.value(index),
+                            downcast_array.data_type(),
+                        )
+                    })?;
 
                 Ok(Some(datum::TimestampWithTimeZone::try_from(
-                    DateTimeTz::new(datetime, &tz),
+                    DateTimeTz::new(datetime, "UTC"),
                 )?))
             }
-            None => {
-                let datetime = downcast_array
-                    .value_as_datetime(index)
-                    .ok_or_else(|| anyhow!("failed to convert timestamp to NaiveDateTime"))?;
+            false => {
+                let datetime = downcast_array.value_as_datetime(index).ok_or_else(|| {
+                    timestamp_out_of_range(downcast_array.value(index), downcast_array.data_type())
+                })?;
 
                 Ok(Some(datum::TimestampWithTimeZone::try_from(DateTimeNoTz(
                     datetime,
@@ -615,6 +864,17 @@ where
     }
 }
 
+// Timestamps are converted through chrono, whose range is narrower than both
+// Arrow's i64 timestamps and Postgres' timestamps, so surface the raw value
+// rather than failing with an opaque conversion error.
+fn timestamp_out_of_range(value: i64, data_type: &DataType) -> anyhow::Error {
+    anyhow!(
+        "timestamp value {} of type {:?} is outside the range that can be converted to a Postgres timestamp",
+        value,
+        data_type
+    )
+}
+
 pub trait GetUIntValue
 where
     Self: Array + AsArray,
@@ -668,6 +928,8 @@ where
         + GetByteValue
         + GetDateValue
         + GetDecimalValue
+        + GetDecimalListValue
+        + GetDurationValue
         + GetIntervalDayTimeValue
         + GetIntervalMonthDayNanoValue
         + GetIntervalYearMonthValue
@@ -675,6 +937,7 @@ where
         + GetPrimitiveValue
         + GetPrimitiveListValue
         + GetStringListValue
+        + GetStringMapValue
         + GetStructValue
         + GetTimeValue
         + GetTimestampValue
@@ -683,11 +946,62 @@ where
         + GetUuidValue,
 {
     fn get_cell(&self, index: usize, oid: pg_sys::Oid, name: &str) -> Result<Option<Cell>> {
+        // List views are read through a one-row list of the slice they point to, so every
+        // list arm below handles them
+        if let DataType::ListView(_) | DataType::LargeListView(_) = self.data_type() {
+            return list_view_row(self, index)?.get_cell(0, oid, name);
+        }
+
         match oid {
-            pg_sys::BOOLOID => match self.get_primitive_value::<BooleanArray>(index)? {
-                Some(value) => Ok(Some(Cell::Bool(value))),
-                None => Ok(None),
+            // Integers are read as booleans that are true unless they are 0
+            pg_sys::BOOLOID => match self.data_type() {
+                DataType::Boolean => match self.get_primitive_value::<BooleanArray>(index)? {
+                    Some(value) => Ok(Some(Cell::Bool(value))),
+                    None => Ok(None),
+                },
+                DataType::Int8 => match self.get_primitive_value::<Int8Array>(index)? {
+                    Some(value) => Ok(Some(Cell::Bool(value != 0))),
+                    None => Ok(None),
+                },
+                DataType::Int16 => match self.get_primitive_value::<Int16Array>(index)? {
+                    Some(value) => Ok(Some(Cell::Bool(value != 0))),
+                    None => Ok(None),
+                },
+                DataType::Int32 => match self.get_primitive_value::<Int32Array>(index)? {
+                    Some(value) => Ok(Some(Cell::Bool(value != 0))),
+                    None => Ok(None),
+                },
+                DataType::Int64 => match self.get_primitive_value::<Int64Array>(index)? {
+                    Some(value) => Ok(Some(Cell::Bool(value != 0))),
+                    None => Ok(None),
+                },
+                DataType::UInt8 => match self.get_uint_value::<UInt8Type>(index)? {
+                    Some(value) => Ok(Some(Cell::Bool(value != 0))),
+                    None => Ok(None),
+                },
+                DataType::UInt16 => match self.get_uint_value::<UInt16Type>(index)? {
+                    Some(value) => Ok(Some(Cell::Bool(value != 0))),
+                    None => Ok(None),
+                },
+                DataType::UInt32 => match self.get_uint_value::<UInt32Type>(index)? {
+                    Some(value) => Ok(Some(Cell::Bool(value != 0))),
+                    None => Ok(None),
+                },
+                DataType::UInt64 => match self.get_uint_value::<UInt64Type>(index)? {
+                    Some(value) => Ok(Some(Cell::Bool(value != 0))),
+                    None => Ok(None),
+                },
+                unsupported => Err(DataTypeError::DataTypeMismatch(
+                    name.to_string(),
+                    unsupported.clone(),
+                    PgOid::from(oid),
+                )
+                .into()),
             },
+            // CellReader checks bit(n) lengths against the column's typmod, which isn't known here
+            pg_sys::BITOID | pg_sys::VARBITOID => {
+                bit_cell(self, index, oid, TypeInput::lookup(oid), -1, name)
+            }
             pg_sys::BYTEAOID => match self.data_type() {
                 DataType::Binary => match self.get_byte_value::<BinaryArray>(index)? {
                     Some(value) => Ok(Some(Cell::Bytea(value.into_pg()))),
@@ -711,6 +1025,10 @@ where
                 .into()),
             },
             pg_sys::INT2OID => match self.data_type() {
+                DataType::Boolean => match self.get_primitive_value::<BooleanArray>(index)? {
+                    Some(value) => Ok(Some(Cell::I16(value as i16))),
+                    None => Ok(None),
+                },
                 DataType::Int8 => match self.get_primitive_value::<Int8Array>(index)? {
                     Some(value) => Ok(Some(Cell::I16(value as i16))),
                     None => Ok(None),
@@ -769,6 +1087,10 @@ where
                 .into()),
             },
             pg_sys::INT4OID => match self.data_type() {
+                DataType::Boolean => match self.get_primitive_value::<BooleanArray>(index)? {
+                    Some(value) => Ok(Some(Cell::I32(value as i32))),
+                    None => Ok(None),
+                },
                 DataType::Int8 => match self.get_primitive_value::<Int8Array>(index)? {
                     Some(value) => Ok(Some(Cell::I32(value as i32))),
                     None => Ok(None),
@@ -827,6 +1149,10 @@ where
                 .into()),
             },
             pg_sys::INT8OID => match self.data_type() {
+                DataType::Boolean => match self.get_primitive_value::<BooleanArray>(index)? {
+                    Some(value) => Ok(Some(Cell::I64(value as i64))),
+                    None => Ok(None),
+                },
                 DataType::Int8 => match self.get_primitive_value::<Int8Array>(index)? {
                     Some(value) => Ok(Some(Cell::I64(value as i64))),
                     None => Ok(None),
@@ -926,7 +1252,7 @@ where
                     None => Ok(None),
                 },
                 DataType::Float64 => match self.get_primitive_value::<Float64Array>(index)? {
-                    Some(value) => Ok(Some(Cell::F32(value as f32))),
+                    Some(value) => Ok(Some(Cell::F32(float4_from_float8(value)?))),
                     None => Ok(None),
                 },
                 DataType::Decimal128(p, s) => {
@@ -1060,8 +1386,14 @@ where
                 )
                 .into()),
             },
+            // CellReader reads the fraction digits once per column
+            pg_sys::CASHOID => money_cell(self, index, oid, money_fraction_digits(), name),
             pg_sys::TEXTOID | pg_sys::VARCHAROID | pg_sys::BPCHAROID | pg_sys::NAMEOID => {
                 match self.data_type() {
+                    DataType::Boolean => match self.get_primitive_value::<BooleanArray>(index)? {
+                        Some(value) => Ok(Some(Cell::String(value.to_string()))),
+                        None => Ok(None),
+                    },
                     DataType::Utf8 => match self.get_primitive_value::<StringArray>(index)? {
                         Some(value) => Ok(Some(Cell::String(value.to_string()))),
                         None => Ok(None),
@@ -1082,6 +1414,10 @@ where
                             None => Ok(None),
                         }
                     }
+                    DataType::Dictionary(_, _) => match self.get_dictionary_string_value(index)? {
+                        Some(value) => Ok(Some(Cell::String(value))),
+                        None => Ok(None),
+                    },
                     unsupported => Err(DataTypeError::DataTypeMismatch(
                         name.to_string(),
                         unsupported.clone(),
@@ -1090,6 +1426,24 @@ where
                     .into()),
                 }
             }
+            // The internal "char" type holds a single byte, so strings are read as their first
+            // byte, as the text to "char" cast does
+            pg_sys::CHAROID => match self.data_type() {
+                DataType::Utf8 => match self.get_primitive_value::<StringArray>(index)? {
+                    Some(value) => Ok(Some(Cell::I8(first_byte(value)))),
+                    None => Ok(None),
+                },
+                DataType::LargeUtf8 => match self.get_primitive_value::<LargeStringArray>(index)? {
+                    Some(value) => Ok(Some(Cell::I8(first_byte(value)))),
+                    None => Ok(None),
+                },
+                unsupported => Err(DataTypeError::DataTypeMismatch(
+                    name.to_string(),
+                    unsupported.clone(),
+                    PgOid::from(oid),
+                )
+                .into()),
+            },
             pg_sys::DATEOID => match self.data_type() {
                 DataType::Date32 => match self.get_date_value::<i32, Date32Type>(index)? {
                     Some(value) => Ok(Some(Cell::Date(value))),
@@ -1099,6 +1453,22 @@ where
                     Some(value) => Ok(Some(Cell::Date(value))),
                     None => Ok(None),
                 },
+                DataType::Timestamp(unit, _) => {
+                    let strict = GUCS.strict_timestamp_to_date.get();
+                    let date = match unit {
+                        TimeUnit::Second => {
+                            self.get_timestamp_date_value::<TimestampSecondType>(index, strict)?
+                        }
+                        TimeUnit::Millisecond => self
+                            .get_timestamp_date_value::<TimestampMillisecondType>(index, strict)?,
+                        TimeUnit::Microsecond => self
+                            .get_timestamp_date_value::<TimestampMicrosecondType>(index, strict)?,
+                        TimeUnit::Nanosecond => {
+                            self.get_timestamp_date_value::<TimestampNanosecondType>(index, strict)?
+                        }
+                    };
+                    Ok(date.map(Cell::Date))
+                }
                 unsupported => Err(DataTypeError::DataTypeMismatch(
                     name.to_string(),
                     unsupported.clone(),
@@ -1125,6 +1495,30 @@ where
                         None => Ok(None),
                     }
                 }
+                DataType::Duration(TimeUnit::Second) => {
+                    match self.get_duration_value::<DurationSecondType>(index)? {
+                        Some(value) => Ok(Some(Cell::Interval(value))),
+                        None => Ok(None),
+                    }
+                }
+                DataType::Duration(TimeUnit::Millisecond) => {
+                    match self.get_duration_value::<DurationMillisecondType>(index)? {
+                        Some(value) => Ok(Some(Cell::Interval(value))),
+                        None => Ok(None),
+                    }
+                }
+                DataType::Duration(TimeUnit::Microsecond) => {
+                    match self.get_duration_value::<DurationMicrosecondType>(index)? {
+                        Some(value) => Ok(Some(Cell::Interval(value))),
+                        None => Ok(None),
+                    }
+                }
+                DataType::Duration(TimeUnit::Nanosecond) => {
+                    match self.get_duration_value::<DurationNanosecondType>(index)? {
+                        Some(value) => Ok(Some(Cell::Interval(value))),
+                        None => Ok(None),
+                    }
+                }
                 unsupported => Err(DataTypeError::DataTypeMismatch(
                     name.to_string(),
                     unsupported.clone(),
@@ -1343,35 +1737,116 @@ where
                 }
             }
             pg_sys::INT2ARRAYOID => {
-                match self.get_primitive_list_value::<Int16Array, Option<i16>>(index)? {
-                    Some(value) => Ok(Some(Cell::I16Array(value))),
-                    None => Ok(None),
-                }
+                let value = match self.as_list::<i32>().value_type() {
+                    DataType::Int8 => widen_list_value(
+                        self.get_primitive_list_value::<Int8Array, Option<i8>>(index)?,
+                    ),
+                    DataType::UInt8 => widen_list_value(
+                        self.get_primitive_list_value::<UInt8Array, Option<u8>>(index)?,
+                    ),
+                    _ => self.get_primitive_list_value::<Int16Array, Option<i16>>(index)?,
+                };
+                Ok(value.map(Cell::I16Array))
             }
             pg_sys::INT4ARRAYOID => {
-                match self.get_primitive_list_value::<Int32Array, Option<i32>>(index)? {
-                    Some(value) => Ok(Some(Cell::I32Array(value))),
-                    None => Ok(None),
-                }
+                let value = match self.as_list::<i32>().value_type() {
+                    DataType::Int8 => widen_list_value(
+                        self.get_primitive_list_value::<Int8Array, Option<i8>>(index)?,
+                    ),
+                    DataType::Int16 => widen_list_value(
+                        self.get_primitive_list_value::<Int16Array, Option<i16>>(index)?,
+                    ),
+                    DataType::UInt8 => widen_list_value(
+                        self.get_primitive_list_value::<UInt8Array, Option<u8>>(index)?,
+                    ),
+                    DataType::UInt16 => widen_list_value(
+                        self.get_primitive_list_value::<UInt16Array, Option<u16>>(index)?,
+                    ),
+                    _ => self.get_primitive_list_value::<Int32Array, Option<i32>>(index)?,
+                };
+                Ok(value.map(Cell::I32Array))
             }
             pg_sys::INT8ARRAYOID => {
-                match self.get_primitive_list_value::<Int64Array, Option<i64>>(index)? {
-                    Some(value) => Ok(Some(Cell::I64Array(value))),
-                    None => Ok(None),
-                }
+                let value = match self.as_list::<i32>().value_type() {
+                    DataType::Int8 => widen_list_value(
+                        self.get_primitive_list_value::<Int8Array, Option<i8>>(index)?,
+                    ),
+                    DataType::Int16 => widen_list_value(
+                        self.get_primitive_list_value::<Int16Array, Option<i16>>(index)?,
+                    ),
+                    DataType::Int32 => widen_list_value(
+                        self.get_primitive_list_value::<Int32Array, Option<i32>>(index)?,
+                    ),
+                    DataType::UInt8 => widen_list_value(
+                        self.get_primitive_list_value::<UInt8Array, Option<u8>>(index)?,
+                    ),
+                    DataType::UInt16 => widen_list_value(
+                        self.get_primitive_list_value::<UInt16Array, Option<u16>>(index)?,
+                    ),
+                    DataType::UInt32 => widen_list_value(
+                        self.get_primitive_list_value::<UInt32Array, Option<u32>>(index)?,
+                    ),
+                    _ => self.get_primitive_list_value::<Int64Array, Option<i64>>(index)?,
+                };
+                Ok(value.map(Cell::I64Array))
             }
             pg_sys::FLOAT4ARRAYOID => {
-                match self.get_primitive_list_value::<Float32Array, Option<f32>>(index)? {
-                    Some(value) => Ok(Some(Cell::F32Array(value))),
-                    None => Ok(None),
-                }
+                let value = match self.as_list::<i32>().value_type() {
+                    DataType::Float16 => widen_list_value(
+                        self.get_primitive_list_value::<Float16Array, Option<f16>>(index)?,
+                    ),
+                    DataType::Int8 => widen_list_value(
+                        self.get_primitive_list_value::<Int8Array, Option<i8>>(index)?,
+                    ),
+                    DataType::Int16 => widen_list_value(
+                        self.get_primitive_list_value::<Int16Array, Option<i16>>(index)?,
+                    ),
+                    DataType::UInt8 => widen_list_value(
+                        self.get_primitive_list_value::<UInt8Array, Option<u8>>(index)?,
+                    ),
+                    DataType::UInt16 => widen_list_value(
+                        self.get_primitive_list_value::<UInt16Array, Option<u16>>(index)?,
+                    ),
+                    _ => self.get_primitive_list_value::<Float32Array, Option<f32>>(index)?,
+                };
+                Ok(value.map(Cell::F32Array))
             }
             pg_sys::FLOAT8ARRAYOID => {
-                match self.get_primitive_list_value::<Float64Array, Option<f64>>(index)? {
-                    Some(value) => Ok(Some(Cell::F64Array(value))),
-                    None => Ok(None),
-                }
+                let value = match self.as_list::<i32>().value_type() {
+                    DataType::Float16 => widen_list_value(
+                        self.get_primitive_list_value::<Float16Array, Option<f16>>(index)?,
+                    ),
+                    DataType::Float32 => widen_list_value(
+                        self.get_primitive_list_value::<Float32Array, Option<f32>>(index)?,
+                    ),
+                    DataType::Int8 => widen_list_value(
+                        self.get_primitive_list_value::<Int8Array, Option<i8>>(index)?,
+                    ),
+                    DataType::Int16 => widen_list_value(
+                        self.get_primitive_list_value::<Int16Array, Option<i16>>(index)?,
+                    ),
+                    DataType::Int32 => widen_list_value(
+                        self.get_primitive_list_value::<Int32Array, Option<i32>>(index)?,
+                    ),
+                    DataType::UInt8 => widen_list_value(
+                        self.get_primitive_list_value::<UInt8Array, Option<u8>>(index)?,
+                    ),
+                    DataType::UInt16 => widen_list_value(
+                        self.get_primitive_list_value::<UInt16Array, Option<u16>>(index)?,
+                    ),
+                    DataType::UInt32 => widen_list_value(
+                        self.get_primitive_list_value::<UInt32Array, Option<u32>>(index)?,
+                    ),
+                    _ => self.get_primitive_list_value::<Float64Array, Option<f64>>(index)?,
+                };
+                Ok(value.map(Cell::F64Array))
             }
+            pg_sys::NUMERICARRAYOID => Ok(self
+                .get_decimal_list_value(index)?
+                .and_then(|value| value.into_datum())
+                .map(datum_cell)),
+            unsupported if is_composite(unsupported) => Err(composite_error(name)),
+            unsupported if is_composite_array(unsupported) => Err(composite_array_error(name)),
             unsupported => Err(DataTypeError::DataTypeMismatch(
                 name.to_string(),
                 self.data_type().clone(),
@@ -1382,11 +1857,763 @@ where
     }
 }
 
+fn first_byte(value: &str) -> i8 {
+    value.as_bytes().first().map_or(0, |byte| *byte as i8)
+}
+
+// Lists of narrower element types are read into wider Postgres arrays, but only where every
+// element converts exactly
+fn widen_list_value<S, T: From<S>>(value: Option<Vec<Option<S>>>) -> Option<Vec<Option<T>>> {
+    value.map(|values| {
+        values
+            .into_iter()
+            .map(|element| element.map(T::from))
+            .collect()
+    })
+}
+
+// Dates, times and timestamps are written to JSON as ISO 8601 strings, with an offset for
+// timestamps that carry a time zone
+fn temporal_json_value(column: &ArrayRef, index: usize) -> Result<Option<Value>> {
+    if column.is_null(index) {
+        return Ok(None);
+    }
+
+    let value = match column.data_type() {
+        DataType::Date32 => column
+            .as_primitive::<Date32Type>()
+            .value_as_date(index)
+            .map(|date| date.format("%Y-%m-%d").to_string()),
+        DataType::Date64 => column
+            .as_primitive::<Date64Type>()
+            .value_as_date(index)
+            .map(|date| date.format("%Y-%m-%d").to_string()),
+        DataType::Time32(TimeUnit::Second) => column
+            .as_primitive::<Time32SecondType>()
+            .value_as_time(index)
+            .map(|time| time.format("%H:%M:%S%.f").to_string()),
+        DataType::Time32(TimeUnit::Millisecond) => column
+            .as_primitive::<Time32MillisecondType>()
+            .value_as_time(index)
+            .map(|time| time.format("%H:%M:%S%.f").to_string()),
+        DataType::Time64(TimeUnit::Microsecond) => column
+            .as_primitive::<Time64MicrosecondType>()
+            .value_as_time(index)
+            .map(|time| time.format("%H:%M:%S%.f").to_string()),
+        DataType::Time64(TimeUnit::Nanosecond) => column
+            .as_primitive::<Time64NanosecondType>()
+            .value_as_time(index)
+            .map(|time| time.format("%H:%M:%S%.f").to_string()),
+        DataType::Timestamp(TimeUnit::Second, tz) => {
+            timestamp_json_string::<TimestampSecondType>(column, index, tz.as_deref())?
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, tz) => {
+            timestamp_json_string::<TimestampMillisecondType>(column, index, tz.as_deref())?
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, tz) => {
+            timestamp_json_string::<TimestampMicrosecondType>(column, index, tz.as_deref())?
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, tz) => {
+            timestamp_json_string::<TimestampNanosecondType>(column, index, tz.as_deref())?
+        }
+        unsupported => bail!("{:?} is not a temporal type", unsupported),
+    }
+    .ok_or_else(|| anyhow!("{:?} value is out of range", column.data_type()))?;
+
+    Ok(Some(Value::String(value)))
+}
+
+fn timestamp_json_string<T>(
+    column: &ArrayRef,
+    index: usize,
+    tz: Option<&str>,
+) -> Result<Option<String>>
+where
+    T: ArrowPrimitiveType<Native = i64> + ArrowTemporalType,
+{
+    let downcast_array = column.as_primitive::<T>();
+
+    Ok(match tz {
+        Some(tz) => downcast_array
+            .value_as_datetime_with_tz(index, Tz::from_str(tz)?)
+            .map(|datetime| datetime.format("%Y-%m-%dT%H:%M:%S%.f%:z").to_string()),
+        None => downcast_array
+            .value_as_datetime(index)
+            .map(|datetime| datetime.format("%Y-%m-%dT%H:%M:%S%.f").to_string()),
+    })
+}
+
+// Durations are written to JSON as ISO 8601 durations in seconds, e.g. PT5400.5S
+fn duration_json_value(column: &ArrayRef, index: usize, unit: &TimeUnit) -> Result<Option<Value>> {
+    if column.is_null(index) {
+        return Ok(None);
+    }
+
+    let duration = match unit {
+        TimeUnit::Second => column
+            .as_primitive::<DurationSecondType>()
+            .value_as_duration(index),
+        TimeUnit::Millisecond => column
+            .as_primitive::<DurationMillisecondType>()
+            .value_as_duration(index),
+        TimeUnit::Microsecond => column
+            .as_primitive::<DurationMicrosecondType>()
+            .value_as_duration(index),
+        TimeUnit::Nanosecond => column
+            .as_primitive::<DurationNanosecondType>()
+            .value_as_duration(index),
+    }
+    .ok_or_else(|| anyhow!("duration is out of range"))?;
+
+    Ok(Some(Value::String(duration.to_string())))
+}
+
+// hstore is an extension type, so it has no fixed oid and is recognized by its name
+fn is_hstore(oid: pg_sys::Oid) -> bool {
+    unsafe {
+        let type_name = pg_sys::format_type_be(oid);
+        let is_hstore = CStr::from_ptr(type_name)
+            .to_str()
+            .is_ok_and(|type_name| type_name.rsplit('.').next() == Some("hstore"));
+        pg_sys::pfree(type_name.cast());
+        is_hstore
+    }
+}
+
+fn is_string_map(field: &FieldRef) -> bool {
+    match field.data_type() {
+        DataType::Struct(fields) => {
+            fields.len() == 2
+                && fields
+                    .iter()
+                    .all(|field| field.data_type() == &DataType::Utf8)
+        }
+        _ => false,
+    }
+}
+
+// Cells have no variant for some Postgres types, such as numeric[], so their values are built
+// as datums of the column's type and carried by a Cell::Bytea. Converting a Cell::Bytea to a
+// datum passes its pointer through unchanged, which is the only way cells read from DuckDB are
+// converted, so by-value datums such as enum oids are carried the same way.
+fn datum_cell(datum: pg_sys::Datum) -> Cell {
+    Cell::Bytea(datum.cast_mut_ptr())
+}
+
+// Builds an hstore from its text representation with the type's input function, which does the
+// parsing and validation that a hand-built hstore would have to repeat
+fn hstore_cell<A>(
+    array: &A,
+    index: usize,
+    oid: pg_sys::Oid,
+    input: TypeInput,
+    name: &str,
+) -> Result<Option<Cell>>
+where
+    A: Array + AsArray + GetStringMapValue,
+{
+    let pairs = match array.data_type() {
+        DataType::Map(field, _) if is_string_map(field) => array.get_string_map_value(index)?,
+        unsupported => {
+            return Err(DataTypeError::DataTypeMismatch(
+                name.to_string(),
+                unsupported.clone(),
+                PgOid::from(oid),
+            )
+            .into())
+        }
+    };
+    let Some(pairs) = pairs else {
+        return Ok(None);
+    };
+
+    let quote = |value: &str| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    let text = pairs
+        .iter()
+        .map(|(key, value)| match value {
+            Some(value) => format!("{}=>{}", quote(key), quote(value)),
+            None => format!("{}=>NULL", quote(key)),
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Ok(Some(datum_cell(input.call(&text, -1)?)))
+}
+
+// Whether paradedb.type_overrides reads an Arrow type as text when it is read into a Postgres
+// type, which CellReader decides once per column
+fn type_override(data_type: &DataType, oid: pg_sys::Oid) -> Result<bool> {
+    let Some(overrides) = GUCS.type_overrides.get() else {
+        return Ok(false);
+    };
+
+    for pair in overrides
+        .to_str()?
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+    {
+        let Some((arrow_type, pg_type)) = pair.split_once(':') else {
+            bail!("paradedb.type_overrides entries must look like arrow_type:postgres_type, got '{pair}'");
+        };
+        if !is_arrow_type(data_type, arrow_type.trim())? {
+            continue;
+        }
+
+        let pg_type = pg_type.trim().to_lowercase();
+        let type_name = CString::new(pg_type.as_str())?;
+        let override_oid = unsafe { pg_sys::TypenameGetTypid(type_name.as_ptr()) };
+        if override_oid == pg_sys::InvalidOid {
+            bail!("type {pg_type} in paradedb.type_overrides does not exist");
+        }
+        if !matches!(
+            override_oid,
+            pg_sys::TEXTOID | pg_sys::VARCHAROID | pg_sys::BPCHAROID
+        ) {
+            bail!("paradedb.type_overrides can only read values as text, varchar or bpchar, not {pg_type}");
+        }
+        if override_oid == oid {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+// Arrow types are named without their parameters, and decimal names every decimal type
+fn is_arrow_type(data_type: &DataType, name: &str) -> Result<bool> {
+    Ok(match name.to_lowercase().as_str() {
+        "null" => matches!(data_type, DataType::Null),
+        "boolean" => matches!(data_type, DataType::Boolean),
+        "int8" => matches!(data_type, DataType::Int8),
+        "int16" => matches!(data_type, DataType::Int16),
+        "int32" => matches!(data_type, DataType::Int32),
+        "int64" => matches!(data_type, DataType::Int64),
+        "uint8" => matches!(data_type, DataType::UInt8),
+        "uint16" => matches!(data_type, DataType::UInt16),
+        "uint32" => matches!(data_type, DataType::UInt32),
+        "uint64" => matches!(data_type, DataType::UInt64),
+        "float16" => matches!(data_type, DataType::Float16),
+        "float32" => matches!(data_type, DataType::Float32),
+        "float64" => matches!(data_type, DataType::Float64),
+        "timestamp" => matches!(data_type, DataType::Timestamp(_, _)),
+        "date32" => matches!(data_type, DataType::Date32),
+        "date64" => matches!(data_type, DataType::Date64),
+        "time32" => matches!(data_type, DataType::Time32(_)),
+        "time64" => matches!(data_type, DataType::Time64(_)),
+        "duration" => matches!(data_type, DataType::Duration(_)),
+        "interval" => matches!(data_type, DataType::Interval(_)),
+        "binary" => matches!(data_type, DataType::Binary),
+        "fixedsizebinary" => matches!(data_type, DataType::FixedSizeBinary(_)),
+        "largebinary" => matches!(data_type, DataType::LargeBinary),
+        "binaryview" => matches!(data_type, DataType::BinaryView),
+        "utf8" => matches!(data_type, DataType::Utf8),
+        "largeutf8" => matches!(data_type, DataType::LargeUtf8),
+        "utf8view" => matches!(data_type, DataType::Utf8View),
+        "list" => matches!(data_type, DataType::List(_)),
+        "listview" => matches!(data_type, DataType::ListView(_)),
+        "fixedsizelist" => matches!(data_type, DataType::FixedSizeList(_, _)),
+        "largelist" => matches!(data_type, DataType::LargeList(_)),
+        "largelistview" => matches!(data_type, DataType::LargeListView(_)),
+        "struct" => matches!(data_type, DataType::Struct(_)),
+        "union" => matches!(data_type, DataType::Union(_, _)),
+        "dictionary" => matches!(data_type, DataType::Dictionary(_, _)),
+        "decimal128" => matches!(data_type, DataType::Decimal128(_, _)),
+        "decimal256" => matches!(data_type, DataType::Decimal256(_, _)),
+        "decimal" => matches!(
+            data_type,
+            DataType::Decimal128(_, _) | DataType::Decimal256(_, _)
+        ),
+        "map" => matches!(data_type, DataType::Map(_, _)),
+        "runendencoded" => matches!(data_type, DataType::RunEndEncoded(_, _)),
+        other => bail!("paradedb.type_overrides names an unknown Arrow type '{other}'"),
+    })
+}
+
+// Formats a value the way Arrow displays it
+fn text_value<A: Array + ?Sized>(array: &A, index: usize) -> Result<Option<String>> {
+    if array.is_null(index) {
+        return Ok(None);
+    }
+
+    let value = array.slice(index, 1);
+    let formatter = ArrayFormatter::try_new(value.as_ref(), &FormatOptions::default())?;
+
+    Ok(Some(formatter.value(0).to_string()))
+}
+
+// Copies the slice a list view row points to into a one-row list, since view offsets can
+// overlap and appear in any order
+fn list_view_row<A: Array + ?Sized>(array: &A, index: usize) -> Result<ArrayRef> {
+    let (field, values) = match array.data_type() {
+        DataType::ListView(field) => {
+            let downcast_array = array
+                .as_any()
+                .downcast_ref::<ListViewArray>()
+                .ok_or_else(|| anyhow!("failed to downcast list view array"))?;
+            (field.clone(), downcast_array.value(index))
+        }
+        DataType::LargeListView(field) => {
+            let downcast_array = array
+                .as_any()
+                .downcast_ref::<LargeListViewArray>()
+                .ok_or_else(|| anyhow!("failed to downcast large list view array"))?;
+            (field.clone(), downcast_array.value(index))
+        }
+        unsupported => bail!("{:?} is not a list view type", unsupported),
+    };
+
+    let nulls = array.is_null(index).then(|| NullBuffer::new_null(1));
+    let offsets = OffsetBuffer::<i32>::from_lengths([values.len()]);
+
+    Ok(Arc::new(ListArray::try_new(field, offsets, values, nulls)?))
+}
+
+// Reads an enum from its label with the type's input function, which errors if the label is not
+// one of the enum's
+fn enum_cell<A>(
+    array: &A,
+    index: usize,
+    oid: pg_sys::Oid,
+    input: TypeInput,
+    name: &str,
+) -> Result<Option<Cell>>
+where
+    A: Array + AsArray + GetPrimitiveValue + GetDictionaryStringValue,
+{
+    let label = match array.data_type() {
+        DataType::Utf8 => array
+            .get_primitive_value::<StringArray>(index)?
+            .map(|value| value.to_string()),
+        DataType::LargeUtf8 => array
+            .get_primitive_value::<LargeStringArray>(index)?
+            .map(|value| value.to_string()),
+        DataType::Dictionary(_, _) => array.get_dictionary_string_value(index)?,
+        unsupported => {
+            return Err(DataTypeError::DataTypeMismatch(
+                name.to_string(),
+                unsupported.clone(),
+                PgOid::from(oid),
+            )
+            .into())
+        }
+    };
+
+    match label {
+        Some(label) => Ok(Some(datum_cell(input.call(&label, -1)?))),
+        None => Ok(None),
+    }
+}
+
+// Cells can't hold composite values, so composite types and arrays of them are rejected with a hint
+fn is_composite(oid: pg_sys::Oid) -> bool {
+    (unsafe { pg_sys::get_typtype(oid) }) as u8 == pg_sys::TYPTYPE_COMPOSITE
+}
+
+fn is_composite_array(oid: pg_sys::Oid) -> bool {
+    let element_oid = unsafe { pg_sys::get_element_type(oid) };
+    element_oid != pg_sys::InvalidOid && is_composite(element_oid)
+}
+
+fn composite_error(name: &str) -> anyhow::Error {
+    anyhow!(
+        "Column {} is a composite type, which is not supported. Declare it as jsonb to read structs as JSON objects.",
+        name
+    )
+}
+
+fn composite_array_error(name: &str) -> anyhow::Error {
+    anyhow!(
+        "Column {} is an array of a composite type, which is not supported. Declare it as jsonb to read lists of structs as JSON arrays of objects.",
+        name
+    )
+}
+
+// Binary values are read as raw bits, integers as their big-endian bit pattern. bit and varbit
+// values are built by their input functions, which check the length against the typmod.
+fn bit_cell<A: Array + ?Sized>(
+    array: &A,
+    index: usize,
+    oid: pg_sys::Oid,
+    input: TypeInput,
+    typmod: i32,
+    name: &str,
+) -> Result<Option<Cell>> {
+    if array.is_null(index) {
+        return Ok(None);
+    }
+
+    let any = array.as_any();
+    let downcast_error = || anyhow!("failed to downcast {} array", array.data_type());
+    let bytes = match array.data_type() {
+        DataType::Binary => any
+            .downcast_ref::<BinaryArray>()
+            .ok_or_else(downcast_error)?
+            .value(index)
+            .to_vec(),
+        DataType::LargeBinary => any
+            .downcast_ref::<LargeBinaryArray>()
+            .ok_or_else(downcast_error)?
+            .value(index)
+            .to_vec(),
+        DataType::Int8 => any
+            .downcast_ref::<Int8Array>()
+            .ok_or_else(downcast_error)?
+            .value(index)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::Int16 => any
+            .downcast_ref::<Int16Array>()
+            .ok_or_else(downcast_error)?
+            .value(index)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::Int32 => any
+            .downcast_ref::<Int32Array>()
+            .ok_or_else(downcast_error)?
+            .value(index)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::Int64 => any
+            .downcast_ref::<Int64Array>()
+            .ok_or_else(downcast_error)?
+            .value(index)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::UInt8 => any
+            .downcast_ref::<UInt8Array>()
+            .ok_or_else(downcast_error)?
+            .value(index)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::UInt16 => any
+            .downcast_ref::<UInt16Array>()
+            .ok_or_else(downcast_error)?
+            .value(index)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::UInt32 => any
+            .downcast_ref::<UInt32Array>()
+            .ok_or_else(downcast_error)?
+            .value(index)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::UInt64 => any
+            .downcast_ref::<UInt64Array>()
+            .ok_or_else(downcast_error)?
+            .value(index)
+            .to_be_bytes()
+            .to_vec(),
+        unsupported => {
+            return Err(DataTypeError::DataTypeMismatch(
+                name.to_string(),
+                unsupported.clone(),
+                PgOid::from(oid),
+            )
+            .into())
+        }
+    };
+
+    let bits = bytes
+        .iter()
+        .map(|byte| format!("{byte:08b}"))
+        .collect::<String>();
+    if oid == pg_sys::BITOID && typmod >= 0 && bits.len() != typmod as usize {
+        bail!(
+            "column {name} is bit({typmod}) but its value has {} bits",
+            bits.len()
+        );
+    }
+
+    Ok(Some(datum_cell(input.call(&bits, typmod)?)))
+}
+
+// Postgres stores money as a count of the smallest currency unit, whose size is given by the
+// fraction digits of lc_monetary
+fn money_fraction_digits() -> u32 {
+    let fraction_digits = unsafe { (*pg_sys::PGLC_localeconv()).frac_digits } as i32;
+
+    // Locales such as C don't set the fraction digits, in which case cash_in assumes 2
+    match fraction_digits {
+        0..=10 => fraction_digits as u32,
+        _ => 2,
+    }
+}
+
+// Money is read with the fraction digits of lc_monetary, which CellReader reads once per column
+fn money_cell<A>(
+    array: &A,
+    index: usize,
+    oid: pg_sys::Oid,
+    fraction_digits: u32,
+    name: &str,
+) -> Result<Option<Cell>>
+where
+    A: Array + AsArray + GetPrimitiveValue + GetUIntValue,
+{
+    let cash = match array.data_type() {
+        DataType::Int8 => array
+            .get_primitive_value::<Int8Array>(index)?
+            .map(|value| cash_from_decimal(value as i128, 0, fraction_digits)),
+        DataType::Int16 => array
+            .get_primitive_value::<Int16Array>(index)?
+            .map(|value| cash_from_decimal(value as i128, 0, fraction_digits)),
+        DataType::Int32 => array
+            .get_primitive_value::<Int32Array>(index)?
+            .map(|value| cash_from_decimal(value as i128, 0, fraction_digits)),
+        DataType::Int64 => array
+            .get_primitive_value::<Int64Array>(index)?
+            .map(|value| cash_from_decimal(value as i128, 0, fraction_digits)),
+        DataType::UInt8 => array
+            .get_uint_value::<UInt8Type>(index)?
+            .map(|value| cash_from_decimal(value as i128, 0, fraction_digits)),
+        DataType::UInt16 => array
+            .get_uint_value::<UInt16Type>(index)?
+            .map(|value| cash_from_decimal(value as i128, 0, fraction_digits)),
+        DataType::UInt32 => array
+            .get_uint_value::<UInt32Type>(index)?
+            .map(|value| cash_from_decimal(value as i128, 0, fraction_digits)),
+        DataType::UInt64 => array
+            .get_uint_value::<UInt64Type>(index)?
+            .map(|value| cash_from_decimal(value as i128, 0, fraction_digits)),
+        DataType::Float32 => array
+            .get_primitive_value::<Float32Array>(index)?
+            .map(|value| cash_from_float(value as f64, fraction_digits)),
+        DataType::Float64 => array
+            .get_primitive_value::<Float64Array>(index)?
+            .map(|value| cash_from_float(value, fraction_digits)),
+        DataType::Decimal128(_, s) => array
+            .get_primitive_value::<Decimal128Array>(index)?
+            .map(|value| cash_from_decimal(value, *s, fraction_digits)),
+        unsupported => {
+            return Err(DataTypeError::DataTypeMismatch(
+                name.to_string(),
+                unsupported.clone(),
+                PgOid::from(oid),
+            )
+            .into())
+        }
+    };
+
+    match cash.transpose()? {
+        // money is stored as an int8, so it shares its datum representation
+        Some(value) => Ok(Some(Cell::I64(value))),
+        None => Ok(None),
+    }
+}
+
+// Converts a decimal with the given scale to money, rounding half away from zero
+fn cash_from_decimal(value: i128, scale: i8, fraction_digits: u32) -> Result<i64> {
+    let shift = fraction_digits as i32 - scale as i32;
+    let cash = if shift >= 0 {
+        10_i128
+            .checked_pow(shift as u32)
+            .and_then(|factor| value.checked_mul(factor))
+    } else {
+        10_i128.checked_pow(shift.unsigned_abs()).map(|factor| {
+            let rounding = if value < 0 { -factor / 2 } else { factor / 2 };
+            (value + rounding) / factor
+        })
+    };
+
+    cash.and_then(|cash| i64::try_from(cash).ok())
+        .ok_or_else(|| anyhow!("value is out of range for type money"))
+}
+
+// Warns when a column of decimals is read into a float with fewer significant digits than the
+// decimals' precision, which callers do once per scan rather than for every value
+pub fn warn_decimal_precision_loss(data_type: &DataType, oid: pg_sys::Oid, name: &str) {
+    if !GUCS.warn_decimal_precision_loss.get() {
+        return;
+    }
+
+    let (float_type, digits) = match oid {
+        pg_sys::FLOAT4OID => ("real", f32::DIGITS),
+        pg_sys::FLOAT8OID => ("double precision", f64::DIGITS),
+        _ => return,
+    };
+
+    if let DataType::Decimal128(precision, scale) = data_type {
+        if *precision as u32 > digits {
+            warning!(
+                "column \"{name}\" of type decimal({precision}, {scale}) is read as {float_type}, which only represents {digits} significant digits exactly"
+            );
+        }
+    }
+}
+
+// Narrows like Postgres' float8 to float4 cast: NaN and infinities carry over, but finite values
+// that would overflow to infinity or underflow to zero are errors
+fn float4_from_float8(value: f64) -> Result<f32> {
+    let narrowed = value as f32;
+
+    if narrowed.is_infinite() && value.is_finite() {
+        bail!("value {value} is out of range for type real: overflow");
+    }
+    if narrowed == 0.0 && value != 0.0 {
+        bail!("value {value} is out of range for type real: underflow");
+    }
+
+    Ok(narrowed)
+}
+
+fn cash_from_float(value: f64, fraction_digits: u32) -> Result<i64> {
+    let cash = (value * 10_f64.powi(fraction_digits as i32)).round();
+
+    // i64::MAX isn't representable as an f64, so compare against 2^63 instead
+    if !cash.is_finite() || cash < i64::MIN as f64 || cash >= 9_223_372_036_854_775_808.0 {
+        bail!("value is out of range for type money");
+    }
+
+    Ok(cash as i64)
+}
+
+// The input function of a Postgres type, looked up once per column for types whose values are
+// built from their text representation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TypeInput {
+    function: pg_sys::Oid,
+    io_param: pg_sys::Oid,
+}
+
+impl TypeInput {
+    fn lookup(oid: pg_sys::Oid) -> Self {
+        let mut function = pg_sys::InvalidOid;
+        let mut io_param = pg_sys::InvalidOid;
+        unsafe { pg_sys::getTypeInputInfo(oid, &mut function, &mut io_param) };
+        Self { function, io_param }
+    }
+
+    fn call(&self, text: &str, typmod: i32) -> Result<pg_sys::Datum> {
+        let text = CString::new(text)?;
+        Ok(unsafe {
+            pg_sys::OidInputFunctionCall(
+                self.function,
+                text.as_ptr().cast_mut(),
+                self.io_param,
+                typmod,
+            )
+        })
+    }
+}
+
+// How the values of a column are read into cells, resolved once per batch from the column's Arrow
+// type and the Postgres type it is read into. Exact matches between the two, which most columns
+// are, skip get_cell's dispatch on both types for every value and read the same cells it would.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CellReader {
+    Bool,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+    String,
+    // Formatted the way Arrow displays values, for types that paradedb.type_overrides reads as text
+    Text,
+    Bit { input: TypeInput, typmod: i32 },
+    Enum { input: TypeInput },
+    Hstore { input: TypeInput },
+    Money { fraction_digits: u32 },
+    Composite,
+    CompositeArray,
+    Generic,
+}
+
+impl CellReader {
+    pub fn resolve(data_type: &DataType, oid: pg_sys::Oid, typmod: i32) -> Result<Self> {
+        if type_override(data_type, oid)? {
+            return Ok(Self::Text);
+        }
+
+        Ok(match (data_type, oid) {
+            (DataType::Boolean, pg_sys::BOOLOID) => Self::Bool,
+            (DataType::Int16, pg_sys::INT2OID) => Self::I16,
+            (DataType::Int32, pg_sys::INT4OID) => Self::I32,
+            (DataType::Int64, pg_sys::INT8OID) => Self::I64,
+            (DataType::Float32, pg_sys::FLOAT4OID) => Self::F32,
+            (DataType::Float64, pg_sys::FLOAT8OID) => Self::F64,
+            (
+                DataType::Utf8,
+                pg_sys::TEXTOID | pg_sys::VARCHAROID | pg_sys::BPCHAROID | pg_sys::NAMEOID,
+            ) => Self::String,
+            (_, pg_sys::CASHOID) => Self::Money {
+                fraction_digits: money_fraction_digits(),
+            },
+            (_, pg_sys::BITOID | pg_sys::VARBITOID) => Self::Bit {
+                input: TypeInput::lookup(oid),
+                typmod,
+            },
+            // Built-in types are never enums, so only user-defined types are looked up
+            _ if oid.as_u32() >= pg_sys::FirstNormalObjectId
+                && unsafe { pg_sys::type_is_enum(oid) } =>
+            {
+                Self::Enum {
+                    input: TypeInput::lookup(oid),
+                }
+            }
+            _ if oid.as_u32() >= pg_sys::FirstNormalObjectId && is_hstore(oid) => Self::Hstore {
+                input: TypeInput::lookup(oid),
+            },
+            _ if is_composite(oid) => Self::Composite,
+            _ if is_composite_array(oid) => Self::CompositeArray,
+            _ => Self::Generic,
+        })
+    }
+
+    pub fn read(
+        &self,
+        column: &ArrayRef,
+        index: usize,
+        oid: pg_sys::Oid,
+        name: &str,
+    ) -> Result<Option<Cell>> {
+        match self {
+            Self::Bool => Ok(column
+                .get_primitive_value::<BooleanArray>(index)?
+                .map(Cell::Bool)),
+            Self::I16 => Ok(column
+                .get_primitive_value::<Int16Array>(index)?
+                .map(Cell::I16)),
+            Self::I32 => Ok(column
+                .get_primitive_value::<Int32Array>(index)?
+                .map(Cell::I32)),
+            Self::I64 => Ok(column
+                .get_primitive_value::<Int64Array>(index)?
+                .map(Cell::I64)),
+            Self::F32 => Ok(column
+                .get_primitive_value::<Float32Array>(index)?
+                .map(Cell::F32)),
+            Self::F64 => Ok(column
+                .get_primitive_value::<Float64Array>(index)?
+                .map(Cell::F64)),
+            Self::String => Ok(column
+                .get_primitive_value::<StringArray>(index)?
+                .map(|value| Cell::String(value.to_string()))),
+            Self::Text => Ok(text_value(column.as_ref(), index)?.map(Cell::String)),
+            Self::Bit { input, typmod } => {
+                bit_cell(column.as_ref(), index, oid, *input, *typmod, name)
+            }
+            Self::Enum { input } => enum_cell(column, index, oid, *input, name),
+            Self::Hstore { input } => hstore_cell(column, index, oid, *input, name),
+            Self::Money { fraction_digits } => {
+                money_cell(column, index, oid, *fraction_digits, name)
+            }
+            Self::Composite => Err(composite_error(name)),
+            Self::CompositeArray => Err(composite_array_error(name)),
+            Self::Generic => column.get_cell(index, oid, name),
+        }
+    }
+}
+
 impl GetBinaryValue for ArrayRef {}
 impl GetByteValue for ArrayRef {}
 impl GetCell for ArrayRef {}
 impl GetDateValue for ArrayRef {}
 impl GetDecimalValue for ArrayRef {}
+impl GetDecimalListValue for ArrayRef {}
+impl GetDictionaryStringValue for ArrayRef {}
+impl GetDurationValue for ArrayRef {}
 impl GetIntervalDayTimeValue for ArrayRef {}
 impl GetIntervalMonthDayNanoValue for ArrayRef {}
 impl GetIntervalYearMonthValue for ArrayRef {}
@@ -1394,6 +2621,7 @@ impl GetListValue for ArrayRef {}
 impl GetPrimitiveValue for ArrayRef {}
 impl GetPrimitiveListValue for ArrayRef {}
 impl GetStringListValue for ArrayRef {}
+impl GetStringMapValue for ArrayRef {}
 impl GetStructValue for ArrayRef {}
 impl GetTimeValue for ArrayRef {}
 impl GetTimestampValue for ArrayRef {}
@@ -1409,9 +2637,528 @@ pub enum DataTypeError {
 impl std::fmt::Display for DataTypeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            DataTypeError::DataTypeMismatch(arg1, arg2, arg3) => write!(f, "Column {} has Arrow data type {:?} but is mapped to the {:?} type in Postgres, which are incompatible. If you believe this conversion should be supported, please submit a request at https://github.com/paradedb/paradedb/issues.", arg1, arg2, arg3),
+            DataTypeError::DataTypeMismatch(arg1, arg2, arg3) => {
+                write!(f, "Column {} has Arrow data type {:?} but is mapped to the {:?} type in Postgres, which are incompatible.", arg1, arg2, arg3)?;
+                if let Some(suggestion) = suggested_pg_type(arg2) {
+                    write!(f, " Did you mean {}?", suggestion)?;
+                }
+                write!(f, " If you believe this conversion should be supported, please submit a request at https://github.com/paradedb/paradedb/issues.")
+            }
         }
     }
 }
 
+// The Postgres type that get_cell reads an Arrow type into most directly
+fn suggested_pg_type(data_type: &DataType) -> Option<String> {
+    let suggestion = match data_type {
+        DataType::Boolean => "boolean",
+        DataType::Int8 | DataType::Int16 | DataType::UInt8 => "smallint",
+        DataType::Int32 | DataType::UInt16 => "integer",
+        DataType::Int64 | DataType::UInt32 => "bigint",
+        DataType::UInt64 | DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => "numeric",
+        DataType::Float16 | DataType::Float32 => "real",
+        DataType::Float64 => "double precision",
+        DataType::Utf8 | DataType::LargeUtf8 => "text",
+        DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => "bytea",
+        DataType::Date32 | DataType::Date64 => "date",
+        DataType::Time32(_) | DataType::Time64(_) => "time",
+        DataType::Timestamp(_, None) => "timestamp",
+        DataType::Timestamp(_, Some(_)) => "timestamptz",
+        DataType::Interval(_) | DataType::Duration(_) => "interval",
+        DataType::Struct(_) | DataType::Map(_, _) => "jsonb",
+        DataType::Dictionary(_, value_type) => return suggested_pg_type(value_type),
+        DataType::List(field) | DataType::ListView(field) | DataType::LargeListView(field) => {
+            return match field.data_type() {
+                DataType::Boolean
+                | DataType::Utf8
+                | DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::Float32
+                | DataType::Float64
+                | DataType::Decimal128(_, _) => {
+                    suggested_pg_type(field.data_type()).map(|element| format!("{element}[]"))
+                }
+                _ => Some("jsonb".to_string()),
+            }
+        }
+        _ => return None,
+    };
+
+    Some(suggestion.to_string())
+}
+
 impl std::error::Error for DataTypeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use duckdb::arrow::array::IntervalDayTimeArray;
+    use duckdb::arrow::datatypes::IntervalDayTime;
+
+    #[test]
+    fn test_data_type_mismatch_suggestion() {
+        use duckdb::arrow::datatypes::Field;
+
+        let err = DataTypeError::DataTypeMismatch(
+            "name".to_string(),
+            DataType::Utf8,
+            PgOid::from(pg_sys::INT4OID),
+        );
+        assert!(err
+            .to_string()
+            .contains("which are incompatible. Did you mean text?"));
+
+        let field = Arc::new(Field::new("item", DataType::Int64, true));
+        assert_eq!(
+            suggested_pg_type(&DataType::List(field)),
+            Some("bigint[]".to_string())
+        );
+
+        // numeric[] is read with the scale of the decimals
+        let field = Arc::new(Field::new("item", DataType::Decimal128(10, 2), true));
+        assert_eq!(
+            suggested_pg_type(&DataType::List(field)),
+            Some("numeric[]".to_string())
+        );
+        assert_eq!(suggested_pg_type(&DataType::Null), None);
+    }
+
+    #[test]
+    fn test_cash_from_decimal() {
+        assert_eq!(cash_from_decimal(1234, 2, 2).unwrap(), 1234);
+        assert_eq!(cash_from_decimal(12, 0, 2).unwrap(), 1200);
+        assert_eq!(cash_from_decimal(12345, 3, 2).unwrap(), 1235);
+        assert_eq!(cash_from_decimal(-12345, 3, 2).unwrap(), -1235);
+        assert!(cash_from_decimal(i64::MAX as i128, 0, 2).is_err());
+
+        assert_eq!(cash_from_float(12.34, 2).unwrap(), 1234);
+        assert!(cash_from_float(f64::NAN, 2).is_err());
+    }
+
+    #[test]
+    fn test_interval_day_time_milliseconds_overflow() {
+        let array: ArrayRef = Arc::new(IntervalDayTimeArray::from(vec![
+            IntervalDayTime::new(1, 3_000_000),
+            IntervalDayTime::new(0, i32::MAX),
+        ]));
+
+        let interval = array.get_interval_day_time_value(0).unwrap().unwrap();
+        assert_eq!(interval.days(), 1);
+        assert_eq!(interval.micros(), 3_000_000_000);
+
+        let interval = array.get_interval_day_time_value(1).unwrap().unwrap();
+        assert_eq!(interval.days(), 0);
+        assert_eq!(interval.micros(), i32::MAX as i64 * 1_000);
+    }
+
+    #[test]
+    fn test_duration_interval() {
+        use duckdb::arrow::array::{DurationMillisecondArray, DurationNanosecondArray};
+
+        let array: ArrayRef =
+            Arc::new(DurationMillisecondArray::from(vec![Some(90_061_500), None]));
+
+        let interval = array
+            .get_duration_value::<DurationMillisecondType>(0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(interval.months(), 0);
+        assert_eq!(interval.days(), 0);
+        assert_eq!(interval.micros(), 90_061_500_000);
+
+        assert!(array
+            .get_duration_value::<DurationMillisecondType>(1)
+            .unwrap()
+            .is_none());
+
+        let array: ArrayRef = Arc::new(DurationNanosecondArray::from(vec![1_500]));
+        let interval = array
+            .get_duration_value::<DurationNanosecondType>(0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(interval.micros(), 1);
+    }
+
+    #[test]
+    fn test_boolean_list_nulls() {
+        use duckdb::arrow::array::{BooleanBuilder, ListArray, ListBuilder, NullArray};
+        use duckdb::arrow::buffer::OffsetBuffer;
+        use duckdb::arrow::datatypes::Field;
+
+        let mut builder = ListBuilder::new(BooleanBuilder::new());
+        builder.values().append_value(true);
+        builder.values().append_null();
+        builder.values().append_value(false);
+        builder.append(true);
+        builder.append(false);
+        builder.append(true);
+        let array: ArrayRef = Arc::new(builder.finish());
+
+        assert_eq!(
+            array
+                .get_primitive_list_value::<BooleanArray, Option<bool>>(0)
+                .unwrap(),
+            Some(vec![Some(true), None, Some(false)])
+        );
+        assert_eq!(
+            array
+                .get_primitive_list_value::<BooleanArray, Option<bool>>(1)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            array
+                .get_primitive_list_value::<BooleanArray, Option<bool>>(2)
+                .unwrap(),
+            Some(vec![])
+        );
+
+        let array: ArrayRef = Arc::new(ListArray::new(
+            Arc::new(Field::new("item", DataType::Null, true)),
+            OffsetBuffer::from_lengths([2]),
+            Arc::new(NullArray::new(2)),
+            None,
+        ));
+        assert_eq!(
+            array
+                .get_primitive_list_value::<BooleanArray, Option<bool>>(0)
+                .unwrap(),
+            Some(vec![None, None])
+        );
+    }
+
+    #[test]
+    fn test_struct_value_duration_field() {
+        use duckdb::arrow::array::{DurationMicrosecondArray, StructArray};
+        use duckdb::arrow::datatypes::Field;
+
+        let array: ArrayRef = Arc::new(StructArray::from(vec![(
+            Arc::new(Field::new(
+                "d",
+                DataType::Duration(TimeUnit::Microsecond),
+                true,
+            )),
+            Arc::new(DurationMicrosecondArray::from(vec![
+                Some(5_400_500_000),
+                None,
+            ])) as ArrayRef,
+        )]));
+
+        let value = array.get_struct_value(0).unwrap().unwrap();
+        assert_eq!(value.0, serde_json::json!({"d": "PT5400.5S"}));
+
+        let value = array.get_struct_value(1).unwrap().unwrap();
+        assert_eq!(value.0, serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_struct_value_temporal_fields() {
+        use duckdb::arrow::array::{
+            Date32Array, StructArray, Time64MicrosecondArray, TimestampMicrosecondArray,
+        };
+        use duckdb::arrow::datatypes::Field;
+
+        let array: ArrayRef = Arc::new(StructArray::from(vec![
+            (
+                Arc::new(Field::new("d", DataType::Date32, true)),
+                Arc::new(Date32Array::from(vec![Some(19724)])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new(
+                    "t",
+                    DataType::Time64(TimeUnit::Microsecond),
+                    true,
+                )),
+                Arc::new(Time64MicrosecondArray::from(vec![Some(45_296_500_000)])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new(
+                    "ts",
+                    DataType::Timestamp(TimeUnit::Microsecond, None),
+                    true,
+                )),
+                Arc::new(TimestampMicrosecondArray::from(vec![Some(
+                    1_704_198_896_000_000,
+                )])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new(
+                    "tstz",
+                    DataType::Timestamp(TimeUnit::Microsecond, Some("+02:00".into())),
+                    true,
+                )),
+                Arc::new(
+                    TimestampMicrosecondArray::from(vec![Some(1_704_198_896_000_000)])
+                        .with_timezone("+02:00"),
+                ) as ArrayRef,
+            ),
+        ]));
+
+        let value = array.get_struct_value(0).unwrap().unwrap();
+        assert_eq!(
+            value.0,
+            serde_json::json!({
+                "d": "2024-01-02",
+                "t": "12:34:56.500",
+                "ts": "2024-01-02T12:34:56",
+                "tstz": "2024-01-02T14:34:56+02:00"
+            })
+        );
+    }
+
+    #[test]
+    fn test_struct_value_nested_struct() {
+        use duckdb::arrow::array::StructArray;
+        use duckdb::arrow::datatypes::Field;
+
+        let inner = StructArray::from(vec![(
+            Arc::new(Field::new("a", DataType::Int32, true)),
+            Arc::new(Int32Array::from(vec![Some(1), None])) as ArrayRef,
+        )]);
+        let array: ArrayRef = Arc::new(StructArray::from(vec![(
+            Arc::new(Field::new("inner", inner.data_type().clone(), true)),
+            Arc::new(inner) as ArrayRef,
+        )]));
+
+        let value = array.get_struct_value(0).unwrap().unwrap();
+        assert_eq!(value.0, serde_json::json!({"inner": {"a": 1}}));
+
+        let value = array.get_struct_value(1).unwrap().unwrap();
+        assert_eq!(value.0, serde_json::json!({"inner": {}}));
+    }
+
+    #[test]
+    fn test_struct_value_list_field() {
+        use duckdb::arrow::array::{ListBuilder, StringBuilder, StructArray};
+        use duckdb::arrow::datatypes::Field;
+
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        builder.values().append_value("a");
+        builder.values().append_null();
+        builder.values().append_value("b");
+        builder.append(true);
+        builder.append(false);
+        let tags = builder.finish();
+
+        let array: ArrayRef = Arc::new(StructArray::from(vec![(
+            Arc::new(Field::new("tags", tags.data_type().clone(), true)),
+            Arc::new(tags) as ArrayRef,
+        )]));
+
+        let value = array.get_struct_value(0).unwrap().unwrap();
+        assert_eq!(value.0, serde_json::json!({"tags": ["a", null, "b"]}));
+
+        let value = array.get_struct_value(1).unwrap().unwrap();
+        assert_eq!(value.0, serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_struct_value_repeated_field_names() {
+        use duckdb::arrow::array::StructArray;
+        use duckdb::arrow::datatypes::Field;
+
+        let array: ArrayRef = Arc::new(StructArray::from(vec![
+            (
+                Arc::new(Field::new("id", DataType::Int32, true)),
+                Arc::new(Int32Array::from(vec![1])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new("name", DataType::Utf8, true)),
+                Arc::new(StringArray::from(vec!["a"])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new("id", DataType::Int32, true)),
+                Arc::new(Int32Array::from(vec![2])) as ArrayRef,
+            ),
+        ]));
+
+        let value = array.get_struct_value(0).unwrap().unwrap();
+        assert_eq!(value.0, serde_json::json!({"id": 1, "name": "a"}));
+    }
+
+    #[test]
+    fn test_dictionary_string_value() {
+        let array: ArrayRef = Arc::new(
+            vec![Some("small"), None, Some("large"), Some("small")]
+                .into_iter()
+                .collect::<DictionaryArray<UInt8Type>>(),
+        );
+
+        assert_eq!(
+            array.get_dictionary_string_value(0).unwrap(),
+            Some("small".to_string())
+        );
+        assert_eq!(array.get_dictionary_string_value(1).unwrap(), None);
+        assert_eq!(
+            array.get_dictionary_string_value(2).unwrap(),
+            Some("large".to_string())
+        );
+        assert_eq!(
+            array.get_dictionary_string_value(3).unwrap(),
+            Some("small".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cell_reader() {
+        // Only exact matches are resolved without looking the type up in the catalog, which
+        // needs a backend
+        let columns: Vec<(ArrayRef, pg_sys::Oid)> = vec![
+            (
+                Arc::new(BooleanArray::from(vec![Some(true), None, Some(false)])),
+                pg_sys::BOOLOID,
+            ),
+            (
+                Arc::new(Int16Array::from(vec![Some(-1), None, Some(i16::MAX)])),
+                pg_sys::INT2OID,
+            ),
+            (
+                Arc::new(Int32Array::from(vec![Some(-1), None, Some(i32::MAX)])),
+                pg_sys::INT4OID,
+            ),
+            (
+                Arc::new(Int64Array::from(vec![Some(-1), None, Some(i64::MAX)])),
+                pg_sys::INT8OID,
+            ),
+            (
+                Arc::new(Float32Array::from(vec![Some(1.5), None, Some(f32::MAX)])),
+                pg_sys::FLOAT4OID,
+            ),
+            (
+                Arc::new(Float64Array::from(vec![Some(1.5), None, Some(f64::MAX)])),
+                pg_sys::FLOAT8OID,
+            ),
+            (
+                Arc::new(StringArray::from(vec![Some("a"), None, Some("")])),
+                pg_sys::TEXTOID,
+            ),
+            (
+                Arc::new(StringArray::from(vec![Some("a"), None, Some("")])),
+                pg_sys::VARCHAROID,
+            ),
+        ];
+
+        for (column, oid) in &columns {
+            let reader = CellReader::resolve(column.data_type(), *oid, -1).unwrap();
+            assert_ne!(reader, CellReader::Generic, "{:?}", column.data_type());
+
+            for index in 0..column.len() {
+                let expected = column
+                    .get_cell(index, *oid, "col")
+                    .map(|cell| cell.map(|cell| cell.to_string()))
+                    .map_err(|err| err.to_string());
+                let actual = reader
+                    .read(column, index, *oid, "col")
+                    .map(|cell| cell.map(|cell| cell.to_string()))
+                    .map_err(|err| err.to_string());
+
+                assert_eq!(actual, expected, "{:?} as {:?}", column.data_type(), oid);
+            }
+        }
+
+        assert_eq!(
+            CellReader::resolve(&DataType::Int32, pg_sys::INT4OID, -1).unwrap(),
+            CellReader::I32
+        );
+    }
+
+    #[test]
+    fn test_is_arrow_type() {
+        assert!(is_arrow_type(&DataType::Decimal128(20, 10), "decimal").unwrap());
+        assert!(is_arrow_type(&DataType::Decimal128(20, 10), "Decimal128").unwrap());
+        assert!(is_arrow_type(&DataType::Int64, "int64").unwrap());
+        assert!(is_arrow_type(
+            &DataType::Timestamp(TimeUnit::Microsecond, None),
+            "timestamp"
+        )
+        .unwrap());
+        assert!(!is_arrow_type(&DataType::Int64, "int32").unwrap());
+        assert!(!is_arrow_type(&DataType::Int64, "decimal").unwrap());
+        assert!(is_arrow_type(&DataType::Int64, "int").is_err());
+
+        let array: ArrayRef = Arc::new(
+            Decimal128Array::from(vec![Some(123_456_700), None])
+                .with_precision_and_scale(20, 4)
+                .unwrap(),
+        );
+        assert_eq!(
+            text_value(array.as_ref(), 0).unwrap(),
+            Some("12345.6700".to_string())
+        );
+        assert_eq!(text_value(array.as_ref(), 1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_float4_from_float8() {
+        assert!(float4_from_float8(f64::NAN).unwrap().is_nan());
+        assert_eq!(float4_from_float8(f64::INFINITY).unwrap(), f32::INFINITY);
+        assert_eq!(
+            float4_from_float8(f64::NEG_INFINITY).unwrap(),
+            f32::NEG_INFINITY
+        );
+        assert_eq!(float4_from_float8(-0.0).unwrap(), 0.0);
+        assert_eq!(float4_from_float8(1.5).unwrap(), 1.5);
+        assert!(float4_from_float8(f64::MAX).is_err());
+        assert!(float4_from_float8(f64::MIN_POSITIVE).is_err());
+    }
+
+    #[test]
+    fn test_list_view_value() {
+        use duckdb::arrow::buffer::ScalarBuffer;
+        use duckdb::arrow::datatypes::Field;
+
+        let field = Arc::new(Field::new("item", DataType::Int32, true));
+        let values: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, Some(3), Some(4)]));
+        // Views can overlap and point backwards into the values
+        let offsets = ScalarBuffer::from(vec![2, 0, 1, 0]);
+        let sizes = ScalarBuffer::from(vec![2, 3, 0, 0]);
+        let nulls = NullBuffer::from(vec![true, true, true, false]);
+
+        let list_view: ArrayRef = Arc::new(ListViewArray::new(
+            field.clone(),
+            offsets.clone(),
+            sizes.clone(),
+            values.clone(),
+            Some(nulls.clone()),
+        ));
+        let large_list_view: ArrayRef = Arc::new(LargeListViewArray::new(
+            field,
+            ScalarBuffer::from(offsets.iter().map(|&o| o as i64).collect::<Vec<i64>>()),
+            ScalarBuffer::from(sizes.iter().map(|&s| s as i64).collect::<Vec<i64>>()),
+            values,
+            Some(nulls),
+        ));
+
+        for array in [list_view, large_list_view] {
+            let cells = (0..array.len())
+                .map(
+                    |index| match array.get_cell(index, pg_sys::INT4ARRAYOID, "a").unwrap() {
+                        Some(Cell::I32Array(value)) => Some(value),
+                        None => None,
+                        _ => panic!("expected an integer array"),
+                    },
+                )
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                cells,
+                vec![
+                    Some(vec![Some(3), Some(4)]),
+                    Some(vec![Some(1), None, Some(3)]),
+                    Some(vec![]),
+                    None,
+                ]
+            );
+        }
+
+        assert_eq!(
+            suggested_pg_type(&DataType::ListView(Arc::new(Field::new(
+                "item",
+                DataType::Int32,
+                true
+            )))),
+            Some("integer[]".to_string())
+        );
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2023-2024 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{bail, Result};
+
+// DuckDB resolves column names case-insensitively, so declared columns are matched the same way
+pub fn check_declared_columns(
+    table_name: &str,
+    declared_columns: &[String],
+    file_columns: &[String],
+) -> Result<()> {
+    let missing_columns = declared_columns
+        .iter()
+        .filter(|declared| {
+            !file_columns
+                .iter()
+                .any(|file_column| file_column.eq_ignore_ascii_case(declared))
+        })
+        .map(|column| format!("\"{column}\""))
+        .collect::<Vec<String>>();
+
+    if missing_columns.is_empty() {
+        return Ok(());
+    }
+
+    bail!(
+        "foreign table {table_name} declares columns that are not in its files: {}. The files have columns: {}",
+        missing_columns.join(", "),
+        file_columns
+            .iter()
+            .map(|column| format!("\"{column}\""))
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_declared_columns() {
+        let file_columns = vec!["id".to_string(), "Name".to_string()];
+
+        assert!(check_declared_columns(
+            "test",
+            &["name".to_string(), "id".to_string()],
+            &file_columns
+        )
+        .is_ok());
+
+        let err = check_declared_columns(
+            "test",
+            &["id".to_string(), "email".to_string()],
+            &file_columns,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "foreign table test declares columns that are not in its files: \"email\". The files have columns: \"id\", \"Name\""
+        );
+    }
+}
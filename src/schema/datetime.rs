@@ -16,7 +16,8 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use chrono::{
-    DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Timelike,
+    DateTime, Datelike, DurationRound, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone,
+    Timelike,
 };
 use pgrx::*;
 use std::fmt::Debug;
@@ -60,11 +61,26 @@ impl<Tz: TimeZone> DateTimeTz<Tz> {
     }
 }
 
+// Seconds including their fraction, which Postgres rounds to its microsecond precision
+fn seconds(time: &impl Timelike) -> f64 {
+    time.second() as f64 + time.nanosecond() as f64 / NANOSECONDS_IN_SECOND as f64
+}
+
+// Postgres timestamps only have microsecond precision, so nanoseconds are rounded to the nearest
+// microsecond before the seconds become a float, with halves rounded to the later microsecond
+fn round_to_microseconds<T: DurationRound + Clone>(datetime: T) -> T {
+    datetime
+        .clone()
+        .duration_round(TimeDelta::microseconds(1))
+        .unwrap_or(datetime)
+}
+
 impl TryFrom<DateTimeNoTz> for datum::Timestamp {
     type Error = datum::datetime_support::DateTimeConversionError;
 
     fn try_from(datetime: DateTimeNoTz) -> Result<Self, Self::Error> {
         let DateTimeNoTz(datetime) = datetime;
+        let datetime = round_to_microseconds(datetime);
 
         datum::Timestamp::new(
             datetime.year(),
@@ -72,7 +88,7 @@ impl TryFrom<DateTimeNoTz> for datum::Timestamp {
             datetime.day() as u8,
             datetime.hour() as u8,
             datetime.minute() as u8,
-            (datetime.second() + datetime.nanosecond() / NANOSECONDS_IN_SECOND).into(),
+            seconds(&datetime),
         )
     }
 }
@@ -82,6 +98,7 @@ impl TryFrom<DateTimeNoTz> for datum::TimestampWithTimeZone {
 
     fn try_from(datetime: DateTimeNoTz) -> Result<Self, Self::Error> {
         let DateTimeNoTz(datetime) = datetime;
+        let datetime = round_to_microseconds(datetime);
 
         datum::TimestampWithTimeZone::new(
             datetime.year(),
@@ -89,7 +106,7 @@ impl TryFrom<DateTimeNoTz> for datum::TimestampWithTimeZone {
             datetime.day() as u8,
             datetime.hour() as u8,
             datetime.minute() as u8,
-            (datetime.second() + datetime.nanosecond() / NANOSECONDS_IN_SECOND).into(),
+            seconds(&datetime),
         )
     }
 }
@@ -101,7 +118,7 @@ where
     type Error = datum::datetime_support::DateTimeConversionError;
 
     fn try_from(datetimetz: DateTimeTz<Tz>) -> Result<Self, Self::Error> {
-        let datetime = datetimetz.datetime();
+        let datetime = round_to_microseconds(datetimetz.datetime());
         let tz = datetimetz.tz();
 
         datum::TimestampWithTimeZone::with_timezone(
@@ -110,7 +127,7 @@ where
             datetime.day() as u8,
             datetime.hour() as u8,
             datetime.minute() as u8,
-            (datetime.second() + datetime.nanosecond() / NANOSECONDS_IN_SECOND).into(),
+            seconds(&datetime),
             tz,
         )
     }
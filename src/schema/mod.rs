@@ -16,4 +16,5 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 pub mod cell;
+pub mod columns;
 pub mod datetime;
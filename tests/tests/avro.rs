@@ -0,0 +1,99 @@
+// Copyright (c) 2023-2024 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+//! Tests for the DuckDB Avro Extension
+
+mod fixtures;
+
+use crate::fixtures::arrow::{primitive_create_foreign_data_wrapper, primitive_create_server};
+use crate::fixtures::{conn, db::Query, tempdir};
+use anyhow::Result;
+use rstest::rstest;
+use sqlx::PgConnection;
+use std::path::Path;
+use tempfile::TempDir;
+
+const AVRO_SYNC_MARKER: [u8; 16] = *b"pg_analytics_syn";
+
+// Avro encodes longs as zigzag varints
+fn write_avro_long(buf: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        buf.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+fn write_avro_bytes(buf: &mut Vec<u8>, value: &[u8]) {
+    write_avro_long(buf, value.len() as i64);
+    buf.extend_from_slice(value);
+}
+
+// Writes an uncompressed Avro object container file with a single block of
+// records of type {id: long, name: string}
+fn write_avro_file(path: &Path, rows: &[(i64, &str)]) -> Result<()> {
+    let schema = r#"{"type": "record", "name": "user", "fields": [{"name": "id", "type": "long"}, {"name": "name", "type": "string"}]}"#;
+
+    let mut buf = b"Obj\x01".to_vec();
+    write_avro_long(&mut buf, 2);
+    write_avro_bytes(&mut buf, b"avro.schema");
+    write_avro_bytes(&mut buf, schema.as_bytes());
+    write_avro_bytes(&mut buf, b"avro.codec");
+    write_avro_bytes(&mut buf, b"null");
+    write_avro_long(&mut buf, 0);
+    buf.extend_from_slice(&AVRO_SYNC_MARKER);
+
+    let mut block = vec![];
+    for (id, name) in rows {
+        write_avro_long(&mut block, *id);
+        write_avro_bytes(&mut block, name.as_bytes());
+    }
+
+    write_avro_long(&mut buf, rows.len() as i64);
+    write_avro_bytes(&mut buf, &block);
+    buf.extend_from_slice(&AVRO_SYNC_MARKER);
+
+    std::fs::write(path, buf)?;
+    Ok(())
+}
+
+#[rstest]
+async fn test_avro_local_file(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let avro_path = tempdir.path().join("users.avro");
+    write_avro_file(&avro_path, &[(1, "alice"), (2, "bob"), (-3, "carol")])?;
+
+    primitive_create_foreign_data_wrapper("avro_wrapper", "avro_fdw_handler", "avro_fdw_validator")
+        .execute(&mut conn);
+    primitive_create_server("avro_server", "avro_wrapper").execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE users () SERVER avro_server OPTIONS (files '{}')",
+        avro_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i64, String)> = "SELECT id, name FROM users ORDER BY id".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (-3, "carol".to_string()),
+            (1, "alice".to_string()),
+            (2, "bob".to_string())
+        ]
+    );
+
+    Ok(())
+}
@@ -17,17 +17,64 @@
 
 mod fixtures;
 
+use crate::fixtures::arrow::setup_fdw_local_parquet_file_listing;
 use crate::fixtures::db::Query;
 use crate::fixtures::duckdb_conn;
 use crate::fixtures::tables::duckdb_types::DuckdbTypesTable;
 use crate::fixtures::{conn, tempdir};
 use anyhow::Result;
+use datafusion::parquet::data_type::{Int96, Int96Type};
+use datafusion::parquet::file::properties::WriterProperties;
+use datafusion::parquet::file::writer::SerializedFileWriter;
+use datafusion::parquet::schema::parser::parse_message_type;
 use rstest::*;
 use sqlx::PgConnection;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
 use tempfile::TempDir;
 use time::macros::datetime;
 use time::PrimitiveDateTime;
 
+const JULIAN_DAY_OF_EPOCH: i32 = 2_440_588;
+const NANOSECONDS_IN_SECOND: u64 = 1_000_000_000;
+
+// Legacy Spark/Impala writers store timestamps as INT96: 8 bytes of nanoseconds
+// within the day followed by 4 bytes of Julian day. Neither the Arrow writer nor
+// DuckDB can produce these, so we write the column with the low-level parquet API.
+fn write_int96_timestamps(path: &Path, values: &[(i32, u64)]) -> Result<()> {
+    let schema = Arc::new(parse_message_type(
+        "message schema { REQUIRED INT96 int96_col; }",
+    )?);
+    let props = Arc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(File::create(path)?, schema, props)?;
+
+    let int96_values = values
+        .iter()
+        .map(|(days_since_epoch, nanos_of_day)| {
+            let mut value = Int96::new();
+            value.set_data(
+                *nanos_of_day as u32,
+                (*nanos_of_day >> 32) as u32,
+                (JULIAN_DAY_OF_EPOCH + days_since_epoch) as u32,
+            );
+            value
+        })
+        .collect::<Vec<Int96>>();
+
+    let mut row_group_writer = writer.next_row_group()?;
+    if let Some(mut column_writer) = row_group_writer.next_column()? {
+        column_writer
+            .typed::<Int96Type>()
+            .write_batch(&int96_values, None, None)?;
+        column_writer.close()?;
+    }
+    row_group_writer.close()?;
+    writer.close()?;
+
+    Ok(())
+}
+
 #[rstest]
 async fn test_date_trunc(
     mut conn: PgConnection,
@@ -58,3 +105,73 @@ async fn test_date_trunc(
 
     Ok(())
 }
+
+#[rstest]
+async fn test_int96_timestamp(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let parquet_path = tempdir.path().join("test_int96_timestamp.parquet");
+
+    // 2023-06-27 12:34:56 and 1969-12-31 23:59:59
+    write_int96_timestamps(
+        &parquet_path,
+        &[
+            (19535, 45_296 * NANOSECONDS_IN_SECOND),
+            (-1, 86_399 * NANOSECONDS_IN_SECOND),
+        ],
+    )?;
+
+    setup_fdw_local_parquet_file_listing(
+        parquet_path.to_str().unwrap(),
+        "int96_test",
+        &[("int96_col", "timestamp")],
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(PrimitiveDateTime,)> = "SELECT int96_col FROM int96_test".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (datetime!(2023-06-27 12:34:56),),
+            (datetime!(1969-12-31 23:59:59),)
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_timestamp_out_of_range(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("test_timestamp_out_of_range.parquet");
+
+    // DuckDB can represent timestamps well past the range supported by chrono
+    duckdb_conn
+        .execute(
+            &format!(
+                "COPY (SELECT TIMESTAMP '290000-01-01 00:00:00' AS timestamp_col) TO '{}' (FORMAT PARQUET)",
+                parquet_path.to_str().unwrap()
+            ),
+            [],
+        )
+        .unwrap();
+
+    setup_fdw_local_parquet_file_listing(
+        parquet_path.to_str().unwrap(),
+        "timestamp_out_of_range",
+        &[("timestamp_col", "timestamp")],
+    )
+    .execute(&mut conn);
+
+    match "SELECT timestamp_col FROM timestamp_out_of_range".execute_result(&mut conn) {
+        Ok(_) => panic!("out of range timestamp should not be converted"),
+        Err(e) => assert!(
+            e.to_string().contains("is outside the range"),
+            "unexpected error: {}",
+            e
+        ),
+    }
+
+    Ok(())
+}
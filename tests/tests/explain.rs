@@ -17,11 +17,14 @@
 
 mod fixtures;
 
+use crate::fixtures::arrow::setup_parquet_wrapper_and_server;
 use crate::fixtures::db::Query;
-use crate::fixtures::{conn, s3, S3};
+use crate::fixtures::{conn, duckdb_conn, s3, tempdir, S3};
 use anyhow::Result;
 use rstest::*;
+use sqlx::types::Json;
 use sqlx::PgConnection;
+use tempfile::TempDir;
 
 use crate::fixtures::tables::nyc_trips::NycTripsTable;
 
@@ -326,3 +329,202 @@ async fn test_explain_foreign_table_duckdb_style(
     }
     Ok(())
 }
+
+#[rstest]
+async fn test_explain_merged_style(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("orders.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT range AS customer_id, range * 10 AS amount FROM range(5)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE orders () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+    "CREATE TABLE customers (id bigint, name text)".execute(&mut conn);
+    "INSERT INTO customers VALUES (1, 'alice'), (2, 'bob')".execute(&mut conn);
+
+    let explain: Vec<(String,)> = "EXPLAIN (ANALYZE, STYLE merged) SELECT name, amount FROM customers JOIN orders ON customers.id = orders.customer_id WHERE amount > 10"
+        .fetch(&mut conn);
+    let lines = explain.into_iter().map(|(line,)| line).collect::<Vec<_>>();
+
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.contains("Join") || line.contains("Nested Loop")),
+        "{lines:#?}"
+    );
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.contains("Foreign Scan on orders")),
+        "{lines:#?}"
+    );
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.contains("DuckDB Scan: SELECT") && line.contains("orders")),
+        "{lines:#?}"
+    );
+
+    // Without ANALYZE the scan never begins, but its SQL is built when the query is planned
+    let explain: Vec<(String,)> =
+        "EXPLAIN (STYLE merged) SELECT amount FROM orders WHERE customer_id > 1".fetch(&mut conn);
+    let lines = explain.into_iter().map(|(line,)| line).collect::<Vec<_>>();
+
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.contains("Foreign Scan on orders")),
+        "{lines:#?}"
+    );
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.contains("DuckDB Scan: SELECT") && line.contains("orders")),
+        "{lines:#?}"
+    );
+
+    Ok(())
+}
+
+// The plan nodes of a JSON EXPLAIN, depth first
+fn plan_nodes(plan: &serde_json::Value) -> Vec<&serde_json::Value> {
+    let mut nodes = vec![plan];
+    if let Some(children) = plan.get("Plans").and_then(|plans| plans.as_array()) {
+        for child in children {
+            nodes.extend(plan_nodes(child));
+        }
+    }
+    nodes
+}
+
+#[rstest]
+async fn test_explain_json_format(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("orders.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT range AS customer_id, range * 10 AS amount FROM range(5)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE orders () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let (Json(explain),): (Json<serde_json::Value>,) =
+        "EXPLAIN (ANALYZE, FORMAT JSON) SELECT amount FROM orders WHERE customer_id > 1"
+            .fetch_one(&mut conn);
+
+    let foreign_scan = plan_nodes(&explain[0]["Plan"])
+        .into_iter()
+        .find(|node| node["Node Type"] == "Foreign Scan")
+        .unwrap_or_else(|| panic!("no Foreign Scan in {explain:#}"));
+    let duckdb_query = foreign_scan["duckdb_query"]
+        .as_str()
+        .unwrap_or_else(|| panic!("no duckdb_query in {foreign_scan:#}"));
+    assert!(duckdb_query.starts_with("SELECT"), "{duckdb_query}");
+    assert!(duckdb_query.contains("orders"), "{duckdb_query}");
+
+    // Without ANALYZE the scan never begins, but its SQL is built when the query is planned
+    let (Json(explain),): (Json<serde_json::Value>,) =
+        "EXPLAIN (FORMAT JSON) SELECT amount FROM orders WHERE customer_id > 1"
+            .fetch_one(&mut conn);
+
+    let foreign_scan = plan_nodes(&explain[0]["Plan"])
+        .into_iter()
+        .find(|node| node["Node Type"] == "Foreign Scan")
+        .unwrap_or_else(|| panic!("no Foreign Scan in {explain:#}"));
+    assert!(
+        foreign_scan["duckdb_query"].is_string(),
+        "no duckdb_query in {foreign_scan:#}"
+    );
+
+    // A text EXPLAIN that follows a JSON one labels the SQL as before
+    let explain: Vec<(String,)> =
+        "EXPLAIN (STYLE merged) SELECT amount FROM orders WHERE customer_id > 1".fetch(&mut conn);
+    let lines = explain.into_iter().map(|(line,)| line).collect::<Vec<_>>();
+
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.contains("DuckDB Scan: SELECT")),
+        "{lines:#?}"
+    );
+    assert!(
+        !lines.iter().any(|line| line.contains("duckdb_query")),
+        "{lines:#?}"
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_explain_quals_use_statistics(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("numbers.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT range AS id FROM range(10000)) TO '{}' (FORMAT PARQUET, ROW_GROUP_SIZE 1000)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE numbers (id bigint) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+    "CREATE TABLE t1 (a int); INSERT INTO t1 VALUES (1)".execute(&mut conn);
+
+    // The join keeps the query in Postgres, so the range filter reaches DuckDB as the quals of
+    // the foreign scan
+    let explain: Vec<(String,)> = "EXPLAIN (ANALYZE, STYLE merged) SELECT id FROM numbers LEFT JOIN t1 ON true WHERE id >= 2000 AND id < 2100"
+        .fetch(&mut conn);
+    let scan_sql = explain
+        .iter()
+        .find_map(|(line,)| {
+            line.split_once("DuckDB Scan: ")
+                .map(|(_, sql)| sql.to_string())
+        })
+        .unwrap_or_else(|| panic!("no DuckDB Scan in {explain:#?}"));
+    assert!(scan_sql.contains("WHERE"), "{scan_sql}");
+
+    // DuckDB only filters inside the scan, where row groups outside the range are skipped by
+    // their statistics, if the quals compare the bare column
+    let explain: Vec<(String,)> =
+        format!("EXPLAIN (style duckdb, analyze) {scan_sql}").fetch(&mut conn);
+    let boxes = explain
+        .iter()
+        .map(|(line,)| line.trim_matches(|c: char| c == '│' || c.is_whitespace()))
+        .collect::<Vec<_>>();
+    assert!(!boxes.contains(&"FILTER"), "{boxes:#?}");
+    assert!(boxes.contains(&"100 Rows"), "{boxes:#?}");
+    assert!(!boxes.contains(&"10000 Rows"), "{boxes:#?}");
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+// Copyright (c) 2023-2024 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+//! Tests for reading whole files as rows
+
+mod fixtures;
+
+use crate::fixtures::arrow::{primitive_create_foreign_data_wrapper, primitive_create_server};
+use crate::fixtures::{conn, db::Query, tempdir};
+use anyhow::Result;
+use rstest::rstest;
+use sqlx::PgConnection;
+use tempfile::TempDir;
+
+#[rstest]
+async fn test_text_files(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let docs_path = tempdir.path().join("docs");
+    std::fs::create_dir_all(&docs_path)?;
+    std::fs::write(docs_path.join("a.txt"), "first document")?;
+    std::fs::write(docs_path.join("b.txt"), "second\ndocument")?;
+    std::fs::write(docs_path.join("c.csv"), "not,matched")?;
+
+    primitive_create_foreign_data_wrapper("text_wrapper", "text_fdw_handler", "text_fdw_validator")
+        .execute(&mut conn);
+    primitive_create_server("text_server", "text_wrapper").execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE docs () SERVER text_server OPTIONS (files '{}/*.txt')",
+        docs_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(String, String)> =
+        "SELECT filename, content FROM docs ORDER BY filename".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (
+                docs_path.join("a.txt").to_str().unwrap().to_string(),
+                "first document".to_string()
+            ),
+            (
+                docs_path.join("b.txt").to_str().unwrap().to_string(),
+                "second\ndocument".to_string()
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_blob_files(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let images_path = tempdir.path().join("images");
+    std::fs::create_dir_all(&images_path)?;
+    std::fs::write(images_path.join("a.bin"), [0x89, 0x50, 0x4e, 0x47])?;
+    std::fs::write(images_path.join("b.bin"), [0x00, 0xff])?;
+
+    primitive_create_foreign_data_wrapper("blob_wrapper", "blob_fdw_handler", "blob_fdw_validator")
+        .execute(&mut conn);
+    primitive_create_server("blob_server", "blob_wrapper").execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE images () SERVER blob_server OPTIONS (files '{}/*.bin')",
+        images_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(String, i64, Vec<u8>)> =
+        "SELECT filename, size, content FROM images ORDER BY filename".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (
+                images_path.join("a.bin").to_str().unwrap().to_string(),
+                4,
+                vec![0x89, 0x50, 0x4e, 0x47]
+            ),
+            (
+                images_path.join("b.bin").to_str().unwrap().to_string(),
+                2,
+                vec![0x00, 0xff]
+            ),
+        ]
+    );
+
+    Ok(())
+}
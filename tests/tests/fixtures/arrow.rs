@@ -19,7 +19,7 @@
 
 use std::sync::Arc;
 
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
 use bigdecimal::{BigDecimal, ToPrimitive};
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 use datafusion::arrow::array::*;
@@ -678,7 +678,14 @@ pub fn schema_to_batch(schema: &SchemaRef, rows: &[PgRow]) -> Result<RecordBatch
                         rows.iter()
                             .map(|row| decode::<Option<NaiveDateTime>>(field, row))
                             .map(|row| {
-                                row.map(|o| o.and_then(|n| n.and_utc().timestamp_nanos_opt()))
+                                row.and_then(|o| {
+                                    o.map(|n| {
+                                        n.and_utc().timestamp_nanos_opt().ok_or_else(|| {
+                                            anyhow!("{n} is out of range for nanosecond timestamps")
+                                        })
+                                    })
+                                    .transpose()
+                                })
                             })
                             .collect::<Result<Vec<_>>>()?,
                     )) as ArrayRef,
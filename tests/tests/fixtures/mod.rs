@@ -17,6 +17,7 @@
 
 pub mod arrow;
 pub mod db;
+pub mod notices;
 pub mod tables;
 
 use anyhow::Result;
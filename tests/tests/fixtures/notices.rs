@@ -0,0 +1,64 @@
+#![allow(dead_code)]
+
+// Copyright (c) 2023-2024 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::{Mutex, Once};
+
+// sqlx forwards the notices and warnings Postgres sends over a connection to the log crate
+const NOTICE_TARGET: &str = "sqlx::postgres::notice";
+
+static NOTICES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static INIT: Once = Once::new();
+
+struct NoticeLogger;
+
+impl log::Log for NoticeLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target() == NOTICE_TARGET
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            NOTICES.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Starts capturing the notices that Postgres sends to test connections.
+#[allow(unused)]
+pub fn capture_notices() {
+    INIT.call_once(|| {
+        log::set_logger(&NoticeLogger).expect("could not install notice logger");
+        log::set_max_level(log::LevelFilter::Trace);
+    });
+}
+
+/// Returns the captured notices that contain `pattern`. Notices from tests
+/// running in parallel are captured too, so patterns should be unique to a test.
+#[allow(unused)]
+pub fn notices_containing(pattern: &str) -> Vec<String> {
+    NOTICES
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|notice| notice.contains(pattern))
+        .cloned()
+        .collect()
+}
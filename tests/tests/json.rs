@@ -36,7 +36,7 @@ use tempfile::TempDir;
 
 use crate::fixtures::arrow::{primitive_create_foreign_data_wrapper, primitive_create_server};
 use crate::fixtures::db::Query;
-use crate::fixtures::{conn, tempdir};
+use crate::fixtures::{conn, duckdb_conn, tempdir};
 
 pub fn json_string_record_batch() -> Result<RecordBatch> {
     let fields = vec![
@@ -391,3 +391,177 @@ fn test_json_cast_from_struct_list(mut conn: PgConnection, tempdir: TempDir) ->
 
     Ok(())
 }
+
+#[rstest]
+async fn test_json_gzip_compressed(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let json_path = tempdir.path().join("logs.jsonl.gz");
+
+    duckdb_conn
+        .execute(
+            &format!(
+                "COPY (SELECT i AS id, 'event ' || i AS message FROM range(3) t(i)) TO '{}' (FORMAT JSON, COMPRESSION GZIP)",
+                json_path.to_str().unwrap()
+            ),
+            [],
+        )
+        .unwrap();
+
+    primitive_create_foreign_data_wrapper("json_wrapper", "json_fdw_handler", "json_fdw_validator")
+        .execute(&mut conn);
+    primitive_create_server("json_server", "json_wrapper").execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE logs () SERVER json_server OPTIONS (files '{}', format 'newline_delimited', compression 'gzip')",
+        json_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i64, String)> = "SELECT id, message FROM logs ORDER BY id".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (0, "event 0".to_string()),
+            (1, "event 1".to_string()),
+            (2, "event 2".to_string())
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_jsonb_function_pushdown(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("orders.parquet");
+
+    duckdb_conn
+        .execute(
+            &format!(
+                r#"COPY (SELECT * FROM (VALUES (1, '[1, 2, 3, 4]'), (2, '[1]'), (3, '[{{"sku": "a"}}, {{"sku": "b"}}, {{"sku": "c"}}, {{"sku": "d"}}]')) t(id, items)) TO '{}' (FORMAT PARQUET)"#,
+                parquet_path.to_str().unwrap()
+            ),
+            [],
+        )
+        .unwrap();
+
+    primitive_create_foreign_data_wrapper(
+        "parquet_wrapper",
+        "parquet_fdw_handler",
+        "parquet_fdw_validator",
+    )
+    .execute(&mut conn);
+    primitive_create_server("parquet_server", "parquet_wrapper").execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE orders () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let query =
+        "SELECT id FROM orders WHERE jsonb_array_length(items::jsonb) > 3 AND jsonb_typeof(items::jsonb) = 'array' ORDER BY id";
+
+    // DuckDB can only plan the query if it understands the jsonb functions
+    let explain: Result<Vec<(String,)>, sqlx::Error> =
+        format!("EXPLAIN (STYLE duckdb) {query}").fetch_result(&mut conn);
+    assert!(explain.is_ok(), "query was not pushed down: {:?}", explain);
+
+    let rows: Vec<(i32,)> = query.fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (3,)]);
+
+    let rows: Vec<(String,)> =
+        "SELECT items::jsonb->1->>'sku' FROM orders WHERE id = 3".fetch(&mut conn);
+    assert_eq!(rows, vec![("b".to_string(),)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_unnest_json_array(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("baskets.parquet");
+
+    duckdb_conn
+        .execute(
+            &format!(
+                r#"COPY (SELECT * FROM (VALUES (1, '[{{"sku": "a", "qty": 1}}, {{"sku": "b", "qty": 2}}]'), (2, '[{{"sku": "c", "qty": 5}}]'), (3, '[]')) t(id, basket_items)) TO '{}' (FORMAT PARQUET)"#,
+                parquet_path.to_str().unwrap()
+            ),
+            [],
+        )
+        .unwrap();
+
+    primitive_create_foreign_data_wrapper(
+        "parquet_wrapper",
+        "parquet_fdw_handler",
+        "parquet_fdw_validator",
+    )
+    .execute(&mut conn);
+    primitive_create_server("parquet_server", "parquet_wrapper").execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE baskets () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let count: (i64,) =
+        "SELECT COUNT(*) FROM unnest_json_array('baskets'::regclass, 'basket_items')"
+            .fetch_one(&mut conn);
+    assert_eq!(count.0, 3);
+
+    let rows: Vec<(i32, String)> = "
+        SELECT (item->>'id')::int, item->'basket_items'->>'sku'
+        FROM unnest_json_array('baskets'::regclass, 'basket_items') item
+        WHERE (item->'basket_items'->>'qty')::int > 1
+        ORDER BY 1"
+        .fetch(&mut conn);
+    assert_eq!(rows, vec![(1, "b".to_string()), (2, "c".to_string())]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_json_maximum_object_size(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let json_path = tempdir.path().join("documents.json");
+    let payload = "x".repeat(4096);
+    std::fs::write(
+        &json_path,
+        format!(
+            "{{\"id\": 1, \"payload\": \"small\"}}\n{{\"id\": 2, \"payload\": \"{payload}\"}}\n"
+        ),
+    )?;
+
+    primitive_create_foreign_data_wrapper("json_wrapper", "json_fdw_handler", "json_fdw_validator")
+        .execute(&mut conn);
+    primitive_create_server("json_server", "json_wrapper").execute(&mut conn);
+
+    // The object that is larger than the limit fails the read, whether the files are first read
+    // to create the table or when it is queried
+    let result = format!(
+        "CREATE FOREIGN TABLE documents (id bigint, payload text) SERVER json_server OPTIONS (files '{}', format 'newline_delimited', maximum_object_size '1024')",
+        json_path.to_str().unwrap()
+    )
+    .execute_result(&mut conn)
+    .and_then(|_| "SELECT id, payload FROM documents".execute_result(&mut conn));
+    let err = result.unwrap_err();
+    assert!(err.to_string().contains("maximum_object_size"), "{err}");
+
+    format!(
+        "CREATE FOREIGN TABLE large_documents (id bigint, payload text) SERVER json_server OPTIONS (files '{}', format 'newline_delimited', maximum_object_size '8192')",
+        json_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+    let rows: Vec<(i64, i32)> =
+        "SELECT id, length(payload) FROM large_documents ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, vec![(1, 5), (2, 4096)]);
+
+    Ok(())
+}
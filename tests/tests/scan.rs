@@ -18,25 +18,34 @@
 mod fixtures;
 
 use crate::fixtures::arrow::{
-    delta_primitive_record_batch, primitive_create_foreign_data_wrapper, primitive_create_server,
-    primitive_create_table, primitive_create_user_mapping_options, primitive_record_batch,
-    primitive_record_batch_single, primitive_setup_fdw_local_file_delta,
+    create_foreign_table, delta_primitive_record_batch, primitive_create_foreign_data_wrapper,
+    primitive_create_server, primitive_create_table, primitive_create_user_mapping_options,
+    primitive_record_batch, primitive_record_batch_single, primitive_setup_fdw_local_file_delta,
     primitive_setup_fdw_local_file_listing, primitive_setup_fdw_s3_delta,
-    primitive_setup_fdw_s3_listing, setup_parquet_wrapper_and_server,
+    primitive_setup_fdw_s3_listing, setup_fdw_local_parquet_file_listing,
+    setup_parquet_wrapper_and_server,
 };
-use crate::fixtures::db::Query;
-use crate::fixtures::{conn, duckdb_conn, s3, tempdir, S3};
+use crate::fixtures::db::{Db, Query};
+use crate::fixtures::{conn, database, duckdb_conn, s3, tempdir, S3};
 use anyhow::Result;
+use datafusion::arrow::array::{Int32Array, Int64Array, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::parquet::arrow::ArrowWriter;
+use datafusion::parquet::basic::Encoding;
+use datafusion::parquet::file::properties::{WriterProperties, WriterVersion};
+use datafusion::parquet::schema::types::ColumnPath;
 use deltalake::operations::create::CreateBuilder;
 use deltalake::writer::{DeltaWriter, RecordBatchWriter};
 use rstest::*;
-use sqlx::postgres::types::PgInterval;
+use sqlx::postgres::types::{PgInterval, PgMoney};
 use sqlx::types::{BigDecimal, Json, Uuid};
 use sqlx::PgConnection;
 use std::collections::HashMap;
 use std::fs::File;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 use tempfile::TempDir;
 use time::macros::{date, datetime, time};
 
@@ -689,3 +698,2003 @@ async fn test_view_foreign_table(mut conn: PgConnection, tempdir: TempDir) -> Re
     assert_eq!(res.0, 1);
     Ok(())
 }
+
+#[rstest]
+async fn test_boolean_to_text_and_integer(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    let file_path = parquet_path.as_path().to_str().unwrap();
+    setup_fdw_local_parquet_file_listing(file_path, "bool_as_text", &[("boolean_col", "text")])
+        .execute(&mut conn);
+
+    let rows: Vec<(String,)> = "SELECT boolean_col FROM bool_as_text".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            ("true".to_string(),),
+            ("true".to_string(),),
+            ("false".to_string(),)
+        ]
+    );
+
+    let create_table = create_foreign_table(
+        "parquet_server",
+        "bool_as_int",
+        &[
+            ("boolean_col", "int"),
+            ("int16_col", "smallint"),
+            ("int64_col", "bigint"),
+        ],
+    );
+    (&format!("{create_table} OPTIONS (files '{file_path}');")).execute(&mut conn);
+
+    let rows: Vec<(i32,)> = "SELECT boolean_col FROM bool_as_int".fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (1,), (0,)]);
+
+    // Force the cast through the FDW rather than the executor hook
+    "CREATE TABLE t1 (a int);".execute(&mut conn);
+    let rows: Vec<(i32,)> =
+        "SELECT boolean_col FROM bool_as_int LEFT JOIN t1 ON true".fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (1,), (0,)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_load_parquet(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    "CREATE TABLE primitive_heap (boolean_col boolean, int32_col int, utf8_col text NOT NULL)"
+        .execute(&mut conn);
+
+    let loaded: (i64,) = format!(
+        "SELECT load_parquet('{}', 'primitive_heap'::regclass)",
+        parquet_path.to_str().unwrap()
+    )
+    .fetch_one(&mut conn);
+    assert_eq!(loaded.0, 3);
+
+    let count: (i64,) = "SELECT COUNT(*) FROM primitive_heap".fetch_one(&mut conn);
+    assert_eq!(count.0, stored_batch.num_rows() as i64);
+
+    let rows: Vec<(bool, i32, String)> =
+        "SELECT boolean_col, int32_col, utf8_col FROM primitive_heap ORDER BY int32_col"
+            .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (true, -1, "There".to_string()),
+            (false, 0, "World".to_string()),
+            (true, 1, "Hello".to_string())
+        ]
+    );
+
+    "CREATE TABLE indexed_heap (int32_col int PRIMARY KEY)".execute(&mut conn);
+    let result = format!(
+        "SELECT load_parquet('{}', 'indexed_heap'::regclass)",
+        parquet_path.to_str().unwrap()
+    )
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    // Tables whose rows the executor would check or fill in are rejected
+    "CREATE TABLE rls_heap (int32_col int); ALTER TABLE rls_heap ENABLE ROW LEVEL SECURITY"
+        .execute(&mut conn);
+    "CREATE TABLE generated_heap (int32_col int, doubled int GENERATED ALWAYS AS (int32_col * 2) STORED)"
+        .execute(&mut conn);
+    "CREATE TABLE identity_heap (int32_col int GENERATED ALWAYS AS IDENTITY)".execute(&mut conn);
+    for table in ["rls_heap", "generated_heap", "identity_heap"] {
+        let result = format!(
+            "SELECT load_parquet('{}', '{table}'::regclass)",
+            parquet_path.to_str().unwrap()
+        )
+        .execute_result(&mut conn);
+        assert!(result.is_err(), "{table}");
+    }
+
+    // Users who may only insert into some of the columns can't load every column
+    "CREATE TABLE column_grant_heap (boolean_col boolean, int32_col int)".execute(&mut conn);
+    "CREATE ROLE column_grant_user".execute(&mut conn);
+    "GRANT INSERT (int32_col) ON column_grant_heap TO column_grant_user".execute(&mut conn);
+    "SET ROLE column_grant_user".execute(&mut conn);
+    let result = format!(
+        "SELECT load_parquet('{}', 'column_grant_heap'::regclass)",
+        parquet_path.to_str().unwrap()
+    )
+    .execute_result(&mut conn);
+    "RESET ROLE".execute(&mut conn);
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_load_parquet_quoted_column(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("quoted.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT 1 AS \"say \"\"hi\"\"\") TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    "CREATE TABLE quoted_heap (\"say \"\"hi\"\"\" int)".execute(&mut conn);
+    let loaded: (i64,) = format!(
+        "SELECT load_parquet('{}', 'quoted_heap'::regclass)",
+        parquet_path.to_str().unwrap()
+    )
+    .fetch_one(&mut conn);
+    assert_eq!(loaded.0, 1);
+
+    let value: (i32,) = "SELECT \"say \"\"hi\"\"\" FROM quoted_heap".fetch_one(&mut conn);
+    assert_eq!(value.0, 1);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_load_parquet_coercion(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("coercion.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT 'ab' AS name, 1.25::DECIMAL(4, 2) AS amount, -1 AS id) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    // Values are brought to the typmod of the column, like an INSERT would
+    "CREATE TABLE typmod_heap (name char(3), amount numeric(4, 1))".execute(&mut conn);
+    format!(
+        "SELECT load_parquet('{}', 'typmod_heap'::regclass)",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+    let row: (String, String) = "SELECT name, amount::text FROM typmod_heap".fetch_one(&mut conn);
+    assert_eq!(row, ("ab ".to_string(), "1.3".to_string()));
+
+    "CREATE TABLE short_heap (name varchar(1))".execute(&mut conn);
+    let result = format!(
+        "SELECT load_parquet('{}', 'short_heap'::regclass)",
+        parquet_path.to_str().unwrap()
+    )
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    "CREATE DOMAIN positive_int AS int CHECK (VALUE > 0)".execute(&mut conn);
+    "CREATE TABLE domain_heap (id positive_int)".execute(&mut conn);
+    let result = format!(
+        "SELECT load_parquet('{}', 'domain_heap'::regclass)",
+        parquet_path.to_str().unwrap()
+    )
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    let count: (i64,) = "SELECT COUNT(*) FROM domain_heap".fetch_one(&mut conn);
+    assert_eq!(count.0, 0);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_scan_progress(
+    database: Db,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("test_scan_progress.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT range AS id FROM range(5000000)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    let mut scan_conn = database.connection().await;
+    let mut progress_conn = database.connection().await;
+
+    "CREATE EXTENSION pg_analytics".execute(&mut scan_conn);
+    setup_fdw_local_parquet_file_listing(
+        parquet_path.to_str().unwrap(),
+        "progress_test",
+        &[("id", "bigint")],
+    )
+    .execute(&mut scan_conn);
+    "CREATE TABLE t1 (a int)".execute(&mut scan_conn);
+
+    // Join against a heap table so the scan goes through the FDW
+    let scan =
+        sqlx::query_as::<_, (i64,)>("SELECT COUNT(*) FROM progress_test LEFT JOIN t1 ON true")
+            .fetch_one(&mut scan_conn);
+
+    let poll = async {
+        for _ in 0..500 {
+            let rows: Vec<(i64,)> = sqlx::query_as("SELECT rows_processed FROM scan_progress")
+                .fetch_all(&mut progress_conn)
+                .await
+                .unwrap();
+            if rows.iter().any(|(rows_processed,)| *rows_processed > 0) {
+                // The scan is not reported as a COPY
+                #[cfg(not(feature = "pg13"))]
+                {
+                    let copies: (i64,) =
+                        sqlx::query_as("SELECT COUNT(*) FROM pg_stat_progress_copy")
+                            .fetch_one(&mut progress_conn)
+                            .await
+                            .unwrap();
+                    assert_eq!(copies.0, 0);
+                }
+                return true;
+            }
+            async_std::task::sleep(Duration::from_millis(10)).await;
+        }
+        false
+    };
+
+    let (count, progressed) = futures::join!(scan, poll);
+    assert_eq!(count?.0, 5000000);
+    assert!(progressed, "scan progress was never reported");
+
+    let rows: Vec<(i64,)> = "SELECT rows_processed FROM scan_progress".fetch(&mut progress_conn);
+    assert!(rows.is_empty());
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_hive_partition_pruning(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let hive_path = tempdir.path().join("events");
+
+    duckdb_conn
+        .execute(
+            &format!(
+                "COPY (SELECT i AS id, 2020 AS year FROM range(10) t(i)) TO '{}' (FORMAT PARQUET, PARTITION_BY (year))",
+                hive_path.to_str().unwrap()
+            ),
+            [],
+        )
+        .unwrap();
+
+    // The 2021 partition is not a valid parquet file, so any scan that reads it fails
+    std::fs::create_dir_all(hive_path.join("year=2021"))?;
+    std::fs::write(
+        hive_path.join("year=2021/data_0.parquet"),
+        b"not a parquet file",
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE events () SERVER parquet_server OPTIONS (files '{}/**/*.parquet', hive_partitioning 'true')",
+        hive_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+
+    // Plain partition key comparisons are pushed down through the FDW and prune the 2021 partition
+    let query = "SELECT id FROM events LEFT JOIN t1 ON true WHERE year = 2020";
+    let explain: Vec<(String,)> = format!("EXPLAIN {query}").fetch(&mut conn);
+    assert!(explain
+        .iter()
+        .any(|(line,)| line.contains("DuckDB Scan") && line.contains("WHERE year = 2020")));
+
+    let rows: Vec<(i64,)> = query.fetch(&mut conn);
+    assert_eq!(rows.len(), 10);
+
+    // Queries that are fully pushed down to DuckDB also prune on function-wrapped partition keys
+    let count: (i64,) =
+        "SELECT COUNT(*) FROM events WHERE year::text = '2020'".fetch_one(&mut conn);
+    assert_eq!(count.0, 10);
+
+    let count: (i64,) =
+        "SELECT COUNT(*) FROM events WHERE EXTRACT(YEAR FROM make_date(year::int, 1, 1)) = 2020"
+            .fetch_one(&mut conn);
+    assert_eq!(count.0, 10);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_scanned_files(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let hive_path = tempdir.path().join("events");
+
+    duckdb_conn
+        .execute(
+            &format!(
+                "COPY (SELECT i AS id, 2020 + i % 2 AS year FROM range(10) t(i)) TO '{}' (FORMAT PARQUET, PARTITION_BY (year))",
+                hive_path.to_str().unwrap()
+            ),
+            [],
+        )
+        .unwrap();
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE events () SERVER parquet_server OPTIONS (files '{}/**/*.parquet', hive_partitioning 'true')",
+        hive_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let files: Vec<(String,)> =
+        "SELECT * FROM scanned_files('SELECT id FROM events')".fetch(&mut conn);
+    assert_eq!(files.len(), 2);
+
+    let files: Vec<(String,)> =
+        "SELECT * FROM scanned_files('SELECT id FROM events WHERE year = 2020')".fetch(&mut conn);
+    assert_eq!(files.len(), 1);
+    assert!(files[0].0.contains("year=2020"));
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_table_checksum(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let batches = [
+        ("original", primitive_record_batch()?),
+        ("copy", primitive_record_batch()?),
+        ("changed", primitive_record_batch_single()?),
+    ];
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+
+    for (table_name, batch) in batches.iter() {
+        let parquet_path = tempdir.path().join(format!("{table_name}.parquet"));
+        let parquet_file = File::create(&parquet_path)?;
+
+        let mut writer = ArrowWriter::try_new(parquet_file, batch.schema(), None).unwrap();
+        writer.write(batch)?;
+        writer.close()?;
+
+        format!(
+            "CREATE FOREIGN TABLE {table_name} () SERVER parquet_server OPTIONS (files '{}')",
+            parquet_path.to_str().unwrap()
+        )
+        .execute(&mut conn);
+    }
+
+    let checksum: (String,) = "SELECT table_checksum('original'::regclass)".fetch_one(&mut conn);
+    let checksum_again: (String,) =
+        "SELECT table_checksum('original'::regclass)".fetch_one(&mut conn);
+    assert_eq!(checksum, checksum_again);
+
+    let copy_checksum: (String,) = "SELECT table_checksum('copy'::regclass)".fetch_one(&mut conn);
+    assert_eq!(checksum, copy_checksum);
+
+    let changed_checksum: (String,) =
+        "SELECT table_checksum('changed'::regclass)".fetch_one(&mut conn);
+    assert_ne!(checksum, changed_checksum);
+
+    "CREATE TABLE heap_table (a int)".execute(&mut conn);
+    let result = "SELECT table_checksum('heap_table'::regclass)".execute_result(&mut conn);
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_approx_count_distinct(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("distinct.parquet");
+
+    duckdb_conn
+        .execute(
+            &format!(
+                "COPY (SELECT i % 5000 AS id FROM range(100000) t(i)) TO '{}' (FORMAT PARQUET)",
+                parquet_path.to_str().unwrap()
+            ),
+            [],
+        )
+        .unwrap();
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE distinct_table () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let exact: (i64,) = "SELECT COUNT(DISTINCT id) FROM distinct_table".fetch_one(&mut conn);
+    assert_eq!(exact.0, 5000);
+
+    let approx: (i64,) =
+        "SELECT approx_count_distinct('distinct_table'::regclass, 'id')".fetch_one(&mut conn);
+    assert!(
+        (approx.0 - exact.0).abs() <= exact.0 / 10,
+        "approx count {} is not within 10% of {}",
+        approx.0,
+        exact.0
+    );
+
+    let result = "SELECT approx_count_distinct('distinct_table'::regclass, 'missing')"
+        .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_window_function_pushdown(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    primitive_setup_fdw_local_file_listing(parquet_path.as_path().to_str().unwrap(), "primitive")
+        .execute(&mut conn);
+
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+
+    // Queries over a single foreign table are executed entirely by DuckDB, window included
+    let query = "SELECT int32_col, ROW_NUMBER() OVER (PARTITION BY boolean_col ORDER BY int32_col) AS rn FROM primitive ORDER BY int32_col";
+    let explain: Vec<(String,)> = format!("EXPLAIN {query}").fetch(&mut conn);
+    assert!(explain[0].0.contains("DuckDB Scan"));
+    assert!(explain[0].0.contains("ROW_NUMBER() OVER"));
+
+    // Joining a Postgres table forces the window to be evaluated by Postgres
+    let postgres_query = "SELECT int32_col, ROW_NUMBER() OVER (PARTITION BY boolean_col ORDER BY int32_col) AS rn FROM primitive LEFT JOIN t1 ON true ORDER BY int32_col";
+    let explain: Vec<(String,)> = format!("EXPLAIN {postgres_query}").fetch(&mut conn);
+    assert!(explain.iter().any(|(line,)| line.contains("WindowAgg")));
+
+    let duckdb_rows: Vec<(i32, i64)> = query.fetch(&mut conn);
+    let postgres_rows: Vec<(i32, i64)> = postgres_query.fetch(&mut conn);
+    assert_eq!(duckdb_rows, postgres_rows);
+    assert_eq!(duckdb_rows, vec![(-1, 1), (0, 1), (1, 2)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_struct_subfield_filter(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("struct_filter.parquet");
+
+    duckdb_conn
+        .execute(
+            &format!(
+                "COPY (SELECT i AS id, {{'user_id': (i % 10)::INTEGER}} AS payload FROM range(100) t(i)) TO '{}' (FORMAT PARQUET)",
+                parquet_path.to_str().unwrap()
+            ),
+            [],
+        )
+        .unwrap();
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE struct_filter () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let query = "SELECT id FROM struct_filter WHERE payload->>'user_id' = '5' ORDER BY id";
+    let explain: Vec<(String,)> = format!("EXPLAIN {query}").fetch(&mut conn);
+    assert!(explain[0].0.contains("DuckDB Scan"));
+    assert!(explain[0].0.contains("payload->>'user_id' = '5'"));
+
+    let rows: Vec<(i64,)> = query.fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (5,),
+            (15,),
+            (25,),
+            (35,),
+            (45,),
+            (55,),
+            (65,),
+            (75,),
+            (85,),
+            (95,)
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_table_credentials_s3_listing(
+    #[future(awt)] s3: S3,
+    mut conn: PgConnection,
+) -> Result<()> {
+    let s3_endpoint = s3.url.clone();
+    let stored_batch = primitive_record_batch()?;
+
+    for s3_bucket in [
+        "test-table-credentials-a",
+        "test-table-credentials-b",
+        "test-table-credentials-c",
+    ] {
+        s3.create_bucket(s3_bucket).await?;
+        s3.put_batch(s3_bucket, "test_arrow_types.parquet", &stored_batch)
+            .await?;
+    }
+
+    let create_foreign_data_wrapper = primitive_create_foreign_data_wrapper(
+        "parquet_wrapper",
+        "parquet_fdw_handler",
+        "parquet_fdw_validator",
+    );
+    let create_user_mapping_options =
+        primitive_create_user_mapping_options("public", "parquet_server");
+    let create_server = primitive_create_server("parquet_server", "parquet_wrapper");
+
+    // The user mapping points at an endpoint that does not exist, so tables can only be read
+    // with their own credentials
+    format!(
+        r#"
+        {create_foreign_data_wrapper};
+        {create_server};
+        {create_user_mapping_options} OPTIONS (type 'S3', region 'us-east-1', endpoint 'localhost:1', use_ssl 'false', url_style 'path');
+        "#
+    )
+    .execute(&mut conn);
+
+    for (table, s3_bucket, key_id) in [
+        ("primitive_a", "test-table-credentials-a", "key_a"),
+        ("primitive_b", "test-table-credentials-b", "key_b"),
+    ] {
+        format!(
+            r#"
+            {} OPTIONS (
+                files 's3://{s3_bucket}/test_arrow_types.parquet',
+                key_id '{key_id}',
+                secret 'secret',
+                region 'us-east-1',
+                endpoint '{s3_endpoint}',
+                use_ssl 'false',
+                url_style 'path'
+            )
+            "#,
+            primitive_create_table("parquet_server", table)
+        )
+        .execute(&mut conn);
+    }
+
+    for table in ["primitive_a", "primitive_b"] {
+        let count: (i64,) = format!("SELECT COUNT(*) FROM {table}").fetch_one(&mut conn);
+        assert_eq!(count.0, stored_batch.num_rows() as i64);
+    }
+
+    let result = format!(
+        "{} OPTIONS (files 's3://test-table-credentials-c/test_arrow_types.parquet')",
+        primitive_create_table("parquet_server", "primitive_default")
+    )
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    // Every role can read table options, so only superusers can put credentials in them
+    "CREATE ROLE table_credentials_user".execute(&mut conn);
+    "GRANT USAGE ON FOREIGN SERVER parquet_server TO table_credentials_user".execute(&mut conn);
+    "GRANT CREATE ON SCHEMA public TO table_credentials_user".execute(&mut conn);
+    "SET ROLE table_credentials_user".execute(&mut conn);
+    let result = format!(
+        "{} OPTIONS (files 's3://test-table-credentials-c/test_arrow_types.parquet', key_id 'key_c', secret 'secret')",
+        primitive_create_table("parquet_server", "primitive_user")
+    )
+    .execute_result(&mut conn);
+    "RESET ROLE".execute(&mut conn);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("only superusers can set the key_id option"));
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_scoped_secrets_s3_listing(
+    #[future(awt)] s3: S3,
+    mut conn: PgConnection,
+) -> Result<()> {
+    let s3_endpoint = s3.url.clone();
+    let stored_batch = primitive_record_batch()?;
+
+    for s3_bucket in ["test-scoped-secrets-a", "test-scoped-secrets-b"] {
+        s3.create_bucket(s3_bucket).await?;
+        s3.put_batch(s3_bucket, "test_arrow_types.parquet", &stored_batch)
+            .await?;
+    }
+
+    primitive_create_foreign_data_wrapper(
+        "parquet_wrapper",
+        "parquet_fdw_handler",
+        "parquet_fdw_validator",
+    )
+    .execute(&mut conn);
+
+    for (server, table, s3_bucket, key_id) in [
+        ("server_a", "primitive_a", "test-scoped-secrets-a", "key_a"),
+        ("server_b", "primitive_b", "test-scoped-secrets-b", "key_b"),
+    ] {
+        format!(
+            r#"
+            {};
+            {} OPTIONS (type 'S3', key_id '{key_id}', secret 'secret', region 'us-east-1', endpoint '{s3_endpoint}', use_ssl 'false', url_style 'path');
+            {} OPTIONS (files 's3://{s3_bucket}/test_arrow_types.parquet');
+            "#,
+            primitive_create_server(server, "parquet_wrapper"),
+            primitive_create_user_mapping_options("public", server),
+            primitive_create_table(server, table)
+        )
+        .execute(&mut conn);
+    }
+
+    // A third server whose credentials don't work must not replace the secrets of the others
+    format!(
+        r#"
+        {};
+        {} OPTIONS (type 'S3', region 'us-east-1', endpoint 'localhost:1', use_ssl 'false', url_style 'path');
+        "#,
+        primitive_create_server("server_c", "parquet_wrapper"),
+        primitive_create_user_mapping_options("public", "server_c"),
+    )
+    .execute(&mut conn);
+
+    let result = format!(
+        "{} OPTIONS (files 's3://test-scoped-secrets-c/test_arrow_types.parquet')",
+        primitive_create_table("server_c", "primitive_c")
+    )
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    let count: (i64,) =
+        "SELECT COUNT(*) FROM primitive_a a JOIN primitive_b b ON a.int32_col = b.int32_col"
+            .fetch_one(&mut conn);
+    assert_eq!(count.0, stored_batch.num_rows() as i64);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_memory_table(mut conn: PgConnection) -> Result<()> {
+    primitive_create_foreign_data_wrapper(
+        "memory_wrapper",
+        "memory_fdw_handler",
+        "memory_fdw_validator",
+    )
+    .execute(&mut conn);
+    primitive_create_server("memory_server", "memory_wrapper").execute(&mut conn);
+
+    r#"
+    CREATE FOREIGN TABLE memory_table () SERVER memory_server
+    OPTIONS (values '[[1, ''a''], [2, ''b'']]', select 'col0 AS id, col1 AS name')
+    "#
+    .execute(&mut conn);
+
+    let rows: Vec<(i32, String)> = "SELECT id, name FROM memory_table ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, vec![(1, "a".to_string()), (2, "b".to_string())]);
+
+    let result = "CREATE FOREIGN TABLE invalid_memory_table () SERVER memory_server OPTIONS (values '[1, 2]')"
+        .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_delta_multiple_tables(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let batch = delta_primitive_record_batch()?;
+    let delta_schema = deltalake::kernel::Schema::try_from(batch.schema().as_ref())?;
+    let mut delta_paths = vec![];
+
+    for table_name in ["delta1", "delta2"] {
+        let delta_path = tempdir.path().join(table_name);
+        std::fs::create_dir(&delta_path)?;
+
+        let mut table = CreateBuilder::new()
+            .with_location(delta_path.to_string_lossy().as_ref())
+            .with_columns(delta_schema.fields().to_vec())
+            .await?;
+        let mut writer = RecordBatchWriter::for_table(&table)?;
+        writer.write(batch.clone()).await?;
+        writer.flush_and_commit(&mut table).await?;
+
+        delta_paths.push(delta_path.to_string_lossy().to_string());
+    }
+
+    primitive_setup_fdw_local_file_delta(&delta_paths.join(", "), "delta_union").execute(&mut conn);
+
+    let count: (i64,) = "SELECT COUNT(*) FROM delta_union".fetch_one(&mut conn);
+    assert_eq!(count.0, 2 * batch.num_rows() as i64);
+
+    let rows: Vec<(i32,)> = "SELECT int32_col FROM delta_union ORDER BY int32_col".fetch(&mut conn);
+    assert_eq!(rows, vec![(-1,), (-1,), (0,), (0,), (1,), (1,)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_foreign_table_files(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let mut parquet_paths = vec![];
+
+    for file_name in ["part1.parquet", "part2.parquet"] {
+        let parquet_path = tempdir.path().join(file_name);
+        let parquet_file = File::create(&parquet_path)?;
+
+        let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+        writer.write(&stored_batch)?;
+        writer.close()?;
+
+        parquet_paths.push((parquet_path.to_str().unwrap().to_string(),));
+    }
+    std::fs::write(tempdir.path().join("README.md"), "not a parquet file")?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE multi_file () SERVER parquet_server OPTIONS (files '{}/*.parquet')",
+        tempdir.path().to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let files: Vec<(String,)> =
+        "SELECT * FROM foreign_table_files('multi_file'::regclass)".fetch(&mut conn);
+    assert_eq!(files, parquet_paths);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_money_column(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("prices.parquet");
+
+    duckdb_conn
+        .execute(
+            &format!(
+                "COPY (SELECT * FROM (VALUES (1, 12.34::DECIMAL(10, 2)), (2, 0.5::DECIMAL(10, 2)), (3, -7.125::DECIMAL(10, 3))) t(id, price)) TO '{}' (FORMAT PARQUET)",
+                parquet_path.to_str().unwrap()
+            ),
+            [],
+        )
+        .unwrap();
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE prices (id INT, price MONEY) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    // Fully pushed down queries convert the decimals returned by DuckDB
+    let rows: Vec<(i32, PgMoney)> = "SELECT id, price FROM prices ORDER BY id".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![(1, PgMoney(1234)), (2, PgMoney(50)), (3, PgMoney(-713))]
+    );
+
+    // Through the FDW, the money values can be used by Postgres functions
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+    let rows: Vec<(i32, String)> =
+        "SELECT id, price::numeric::text FROM prices LEFT JOIN t1 ON true ORDER BY id"
+            .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (1, "12.34".to_string()),
+            (2, "0.50".to_string()),
+            (3, "-7.13".to_string())
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_bit_columns(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("flags.parquet");
+
+    duckdb_conn
+        .execute(
+            &format!(
+                "COPY (SELECT * FROM (VALUES (1, '\\xA5\\x0F'::BLOB, 5::UTINYINT), (2, NULL, 255::UTINYINT)) t(id, flags, mask)) TO '{}' (FORMAT PARQUET)",
+                parquet_path.to_str().unwrap()
+            ),
+            [],
+        )
+        .unwrap();
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE flags (id INT, flags VARBIT, mask BIT(8)) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+    let rows: Vec<(i32, Option<String>, String)> =
+        "SELECT id, flags::text, mask::text FROM flags LEFT JOIN t1 ON true ORDER BY id"
+            .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (
+                1,
+                Some("1010010100001111".to_string()),
+                "00000101".to_string()
+            ),
+            (2, None, "11111111".to_string())
+        ]
+    );
+
+    // bit(n) only takes values of exactly n bits
+    format!(
+        "CREATE FOREIGN TABLE short_flags (id INT, flags BIT(4)) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+    let err = "SELECT flags::text FROM short_flags LEFT JOIN t1 ON true WHERE id = 1"
+        .fetch_result::<(Option<String>,)>(&mut conn)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("column flags is bit(4) but its value has 16 bits"),
+        "{err}"
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_preview(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    let rows: Vec<(i32, String)> = format!(
+        "SELECT (row->>'int32_col')::int, row->>'utf8_col' FROM preview('{}') row",
+        parquet_path.to_str().unwrap()
+    )
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (1, "Hello".to_string()),
+            (-1, "There".to_string()),
+            (0, "World".to_string())
+        ]
+    );
+
+    let csv_path = tempdir.path().join("people.csv");
+    std::fs::write(&csv_path, "id,name\n1,Ada\n2,Grace\n3,Edsger\n")?;
+
+    let rows: Vec<(i32, String)> = format!(
+        "SELECT (row->>'id')::int, row->>'name' FROM preview('{}', 2) row",
+        csv_path.to_str().unwrap()
+    )
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1, "Ada".to_string()), (2, "Grace".to_string())]);
+
+    let result = format!(
+        "SELECT * FROM preview('{}')",
+        tempdir.path().join("data.unknown").to_str().unwrap()
+    )
+    .fetch_result::<(Json<serde_json::Value>,)>(&mut conn);
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_describe(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("scores.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT * FROM (VALUES (1, 'a'), (2, NULL), (3, 'c'), (4, 'd')) t(id, name)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    let rows: Vec<(String, String, String, String, i64, f64)> = format!(
+        "SELECT column_name, column_type, min, max, count, null_percentage FROM describe('{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (
+                "id".to_string(),
+                "INTEGER".to_string(),
+                "1".to_string(),
+                "4".to_string(),
+                4,
+                0.0
+            ),
+            (
+                "name".to_string(),
+                "VARCHAR".to_string(),
+                "a".to_string(),
+                "d".to_string(),
+                4,
+                25.0
+            ),
+        ]
+    );
+
+    let (avg,): (Option<String>,) = format!(
+        "SELECT avg FROM describe('{}') WHERE column_name = 'id'",
+        parquet_path.to_str().unwrap()
+    )
+    .fetch_one(&mut conn);
+    assert_eq!(avg, Some("2.5".to_string()));
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_struct_field_name_collision(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("struct_collision.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT i AS id, {{'id': i * 10, 'name': 'item' || i}} AS info FROM range(3) t(i)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE struct_collision () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let expected = vec![
+        (0, Json(serde_json::json!({"id": 0, "name": "item0"}))),
+        (1, Json(serde_json::json!({"id": 10, "name": "item1"}))),
+        (2, Json(serde_json::json!({"id": 20, "name": "item2"}))),
+    ];
+
+    let rows: Vec<(i64, Json<serde_json::Value>)> =
+        "SELECT id, info FROM struct_collision ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, expected);
+
+    // Scans through the FDW read the top-level id and the struct's id from their own columns
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+    let rows: Vec<(i64, Json<serde_json::Value>)> =
+        "SELECT id, info FROM struct_collision LEFT JOIN t1 ON true ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, expected);
+
+    let rows: Vec<(i64, i64)> =
+        "SELECT id, (info->>'id')::bigint FROM struct_collision LEFT JOIN t1 ON true WHERE id = 2"
+            .fetch(&mut conn);
+    assert_eq!(rows, vec![(2, 20)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_composite_array_column(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("baskets.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT 1 AS id, [{{'sku': 'a', 'qty': 1}}, {{'sku': 'b', 'qty': 2}}] AS items) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    "CREATE TYPE basket_item AS (sku text, qty int)".execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE composite_baskets (id int, items basket_item[]) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let err = "SELECT items FROM composite_baskets"
+        .fetch_result::<(String,)>(&mut conn)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Column items is an array of a composite type, which is not supported"),
+        "{err}"
+    );
+
+    // Declared as jsonb, the list of structs is read as a JSON array of objects
+    format!(
+        "CREATE FOREIGN TABLE json_baskets (id int, items jsonb) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(Json<serde_json::Value>,)> = "SELECT items FROM json_baskets".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![(Json(serde_json::json!([
+            {"sku": "a", "qty": 1},
+            {"sku": "b", "qty": 2}
+        ])),)]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_composite_struct_column(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("pairs.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT {{'a': 1, 'b': 'one'}} AS pair) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    "CREATE TYPE int_text_pair AS (a int, b text)".execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE composite_pairs (pair int_text_pair) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let err = "SELECT pair FROM composite_pairs"
+        .fetch_result::<(String,)>(&mut conn)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Column pair is a composite type, which is not supported"),
+        "{err}"
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_struct_temporal_fields(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("events.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT {{'d': DATE '2024-01-02', 't': TIME '12:34:56.5', 'ts': TIMESTAMP '2024-01-02 12:34:56'}} AS event) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE events (event jsonb) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let expected = vec![(Json(serde_json::json!({
+        "d": "2024-01-02",
+        "t": "12:34:56.500",
+        "ts": "2024-01-02T12:34:56"
+    })),)];
+
+    let rows: Vec<(Json<serde_json::Value>,)> = "SELECT event FROM events".fetch(&mut conn);
+    assert_eq!(rows, expected);
+
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+    "INSERT INTO t1 VALUES (1)".execute(&mut conn);
+    let rows: Vec<(Json<serde_json::Value>,)> =
+        "SELECT event FROM events LEFT JOIN t1 ON true".fetch(&mut conn);
+    assert_eq!(rows, expected);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_nested_struct_column(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("nested.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT {{'inner': {{'a': 1}}, 'b': 'x', 'tags': ['red', 'blue']}} AS outer_struct) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE nested (outer_struct jsonb) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(Json<serde_json::Value>,)> = "SELECT outer_struct FROM nested".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![(Json(serde_json::json!({
+            "inner": {"a": 1},
+            "b": "x",
+            "tags": ["red", "blue"]
+        })),)]
+    );
+
+    let rows: Vec<(i32,)> =
+        "SELECT (outer_struct->'inner'->>'a')::int FROM nested".fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_boolean_list_column(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("flags.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT * FROM (VALUES (1, [true, NULL, false]), (2, NULL), (3, []), (4, [NULL])) v(id, flags)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE flags (id int, flags boolean[]) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    // Join a local table so that the cast to text runs in Postgres rather than DuckDB
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+    "INSERT INTO t1 VALUES (1)".execute(&mut conn);
+
+    let rows: Vec<(i32, Option<String>)> =
+        "SELECT id, flags::text FROM flags LEFT JOIN t1 ON true ORDER BY id".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (1, Some("{t,NULL,f}".to_string())),
+            (2, None),
+            (3, Some("{}".to_string())),
+            (4, Some("{NULL}".to_string())),
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_narrow_list_columns(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("narrow.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT [1, -2, NULL]::TINYINT[] AS tiny, [3, NULL]::SMALLINT[] AS small, [1.5, NULL]::FLOAT[] AS float) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE narrow (tiny smallint[], small bigint[], float double precision[]) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    // Join a local table so that the cast to text runs in Postgres rather than DuckDB
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+    "INSERT INTO t1 VALUES (1)".execute(&mut conn);
+
+    let row: (String, String, String) =
+        "SELECT tiny::text, small::text, float::text FROM narrow LEFT JOIN t1 ON true"
+            .fetch_one(&mut conn);
+    assert_eq!(
+        row,
+        (
+            "{1,-2,NULL}".to_string(),
+            "{3,NULL}".to_string(),
+            "{1.5,NULL}".to_string()
+        )
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_delta_encoded_columns(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("delta.parquet");
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("big", DataType::Int64, true),
+        Field::new("small", DataType::Int32, true),
+        Field::new("name", DataType::Utf8, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![
+                Some(1_000_000_000_000),
+                None,
+                Some(-5),
+                Some(1_000_000_000_003),
+            ])),
+            Arc::new(Int32Array::from(vec![Some(10), Some(11), None, Some(13)])),
+            Arc::new(StringArray::from(vec![
+                Some("prefix_a"),
+                Some("prefix_b"),
+                None,
+                Some("prefix_d"),
+            ])),
+        ],
+    )?;
+
+    let props = WriterProperties::builder()
+        .set_writer_version(WriterVersion::PARQUET_2_0)
+        .set_dictionary_enabled(false)
+        .set_column_encoding(ColumnPath::from("big"), Encoding::DELTA_BINARY_PACKED)
+        .set_column_encoding(ColumnPath::from("small"), Encoding::DELTA_BINARY_PACKED)
+        .set_column_encoding(ColumnPath::from("name"), Encoding::DELTA_BYTE_ARRAY)
+        .build();
+    let mut writer = ArrowWriter::try_new(File::create(&parquet_path)?, schema, Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    // Check that the columns were actually written with delta encodings
+    let mut statement = duckdb_conn.prepare(&format!(
+        "SELECT path_in_schema, encodings FROM parquet_metadata('{}') ORDER BY column_id",
+        parquet_path.to_str().unwrap()
+    ))?;
+    let encodings = statement
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<(String, String)>, _>>()?;
+    assert!(
+        encodings[0].1.contains("DELTA_BINARY_PACKED"),
+        "{encodings:?}"
+    );
+    assert!(
+        encodings[1].1.contains("DELTA_BINARY_PACKED"),
+        "{encodings:?}"
+    );
+    assert!(encodings[2].1.contains("DELTA_BYTE_ARRAY"), "{encodings:?}");
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE delta_encoded (big bigint, small bigint, name text) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let expected = vec![
+        (
+            Some(1_000_000_000_000),
+            Some(10),
+            Some("prefix_a".to_string()),
+        ),
+        (None, Some(11), Some("prefix_b".to_string())),
+        (Some(-5), None, None),
+        (
+            Some(1_000_000_000_003),
+            Some(13),
+            Some("prefix_d".to_string()),
+        ),
+    ];
+
+    let rows: Vec<(Option<i64>, Option<i64>, Option<String>)> =
+        "SELECT big, small, name FROM delta_encoded".fetch(&mut conn);
+    assert_eq!(rows, expected);
+
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+    "INSERT INTO t1 VALUES (1)".execute(&mut conn);
+    let rows: Vec<(Option<i64>, Option<i64>, Option<String>)> =
+        "SELECT big, small, name FROM delta_encoded LEFT JOIN t1 ON true".fetch(&mut conn);
+    assert_eq!(rows, expected);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_interval_with_months(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("intervals.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT * FROM (VALUES (1, INTERVAL '1 year 2 months 3 days 04:05:06.5'), (2, INTERVAL '-1 month'), (3, NULL)) t(id, span)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE intervals (id int, span interval) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let expected = vec![
+        (
+            1,
+            Some(PgInterval {
+                months: 14,
+                days: 3,
+                microseconds: 14_706_500_000,
+            }),
+        ),
+        (
+            2,
+            Some(PgInterval {
+                months: -1,
+                days: 0,
+                microseconds: 0,
+            }),
+        ),
+        (3, None),
+    ];
+
+    let rows: Vec<(i32, Option<PgInterval>)> =
+        "SELECT id, span FROM intervals ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, expected);
+
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+    "INSERT INTO t1 VALUES (1)".execute(&mut conn);
+    let rows: Vec<(i32, Option<PgInterval>)> =
+        "SELECT id, span FROM intervals LEFT JOIN t1 ON true ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, expected);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_char_column(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("grades.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT * FROM (VALUES (1, 'A'), (2, 'b'), (3, NULL)) t(id, grade)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE grades (id int, grade \"char\") SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i32, Option<i8>)> = "SELECT id, grade FROM grades ORDER BY id".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![(1, Some(b'A' as i8)), (2, Some(b'b' as i8)), (3, None)]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_hstore_column(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("attributes.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT * FROM (VALUES (1, MAP {{'color': 'red', 'note': NULL, 'quote': 'say \"hi\"'}}), (2, NULL)) t(id, attrs)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    "CREATE EXTENSION IF NOT EXISTS hstore".execute(&mut conn);
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE attributes (id int, attrs hstore) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    // Join a local table so that the hstore operators run in Postgres rather than DuckDB
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+    "INSERT INTO t1 VALUES (1)".execute(&mut conn);
+
+    let rows: Vec<(i32, Option<String>, Option<String>, Option<bool>, Option<String>)> =
+        "SELECT id, attrs -> 'color', attrs -> 'note', attrs ? 'note', attrs -> 'quote' FROM attributes LEFT JOIN t1 ON true ORDER BY id"
+            .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (
+                1,
+                Some("red".to_string()),
+                None,
+                Some(true),
+                Some("say \"hi\"".to_string())
+            ),
+            (2, None, None, None, None),
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_decimal_list_column(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("amounts.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT [1.5, -2.25, NULL]::DECIMAL(10, 2)[] AS amounts UNION ALL SELECT NULL) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE amounts (amounts numeric(10, 2)[]) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    // Join a local table so that the cast to text runs in Postgres rather than DuckDB
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+    "INSERT INTO t1 VALUES (1)".execute(&mut conn);
+
+    let rows: Vec<(Option<String>,)> =
+        "SELECT amounts::text FROM amounts LEFT JOIN t1 ON true ORDER BY 1 NULLS LAST"
+            .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![(Some("{1.50,-2.25,NULL}".to_string()),), (None,)]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_integer_boolean_column(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("flags.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT * FROM (VALUES (1, 0::TINYINT), (2, 1::TINYINT), (3, 2::TINYINT), (4, NULL::TINYINT)) t(id, flag)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE flags (id int, flag boolean) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i32, Option<bool>)> = "SELECT id, flag FROM flags ORDER BY id".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (1, Some(false)),
+            (2, Some(true)),
+            (3, Some(true)),
+            (4, None)
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_timestamp_date_column(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("events.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT * FROM (VALUES (1, TIMESTAMP '2024-03-01 00:00:00'), (2, TIMESTAMP '2024-03-02 13:45:10')) t(id, happened_at)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE events (id int, happened_at date) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i32, time::Date)> =
+        "SELECT id, happened_at FROM events ORDER BY id".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![(1, date!(2024 - 03 - 01)), (2, date!(2024 - 03 - 02))]
+    );
+
+    "SET paradedb.strict_timestamp_to_date = true".execute(&mut conn);
+
+    let rows: Vec<(i32, time::Date)> =
+        "SELECT id, happened_at FROM events WHERE id = 1".fetch(&mut conn);
+    assert_eq!(rows, vec![(1, date!(2024 - 03 - 01))]);
+
+    let err = "SELECT id, happened_at FROM events ORDER BY id"
+        .fetch_result::<(i32, time::Date)>(&mut conn)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("timestamp 2024-03-02 13:45:10 has a time of day"),
+        "{err}"
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_copy_with_rownum(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE primitive () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let copied: (i64,) =
+        "SELECT copy_with_rownum('primitive'::regclass, 'primitive_copy')".fetch_one(&mut conn);
+    assert_eq!(copied, (3,));
+
+    let rows: Vec<(i64, i32)> =
+        "SELECT row_id, int32_col FROM primitive_copy ORDER BY row_id".fetch(&mut conn);
+    assert_eq!(
+        rows.iter().map(|(row_id, _)| *row_id).collect::<Vec<i64>>(),
+        vec![1, 2, 3]
+    );
+    let mut int32_values = rows.iter().map(|(_, value)| *value).collect::<Vec<i32>>();
+    int32_values.sort();
+    assert_eq!(int32_values, vec![-1, 0, 1]);
+
+    // The id column can be renamed, e.g. if the foreign table has a row_id column itself
+    "SELECT copy_with_rownum('primitive'::regclass, 'public.primitive_ids', 'id')"
+        .execute(&mut conn);
+    let ids: Vec<(i64,)> = "SELECT id FROM public.primitive_ids ORDER BY id".fetch(&mut conn);
+    assert_eq!(ids, vec![(1,), (2,), (3,)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_parquet_json_and_enum_logical_types(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("documents.parquet");
+    duckdb_conn.execute_batch(&format!(
+        r#"CREATE TYPE size AS ENUM ('small', 'large');
+        COPY (SELECT 1 AS id, '{{"a": 1, "b": [true, null]}}'::JSON AS doc, 'large'::size AS size) TO '{}' (FORMAT PARQUET)"#,
+        parquet_path.to_str().unwrap()
+    ))?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    "CREATE TABLE t1 (a int); INSERT INTO t1 VALUES (1)".execute(&mut conn);
+
+    // Columns are created as jsonb for the JSON logical type and text for enums
+    format!(
+        "CREATE FOREIGN TABLE documents () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+    let column_types: Vec<(String, String)> = "SELECT column_name::text, data_type::text FROM information_schema.columns WHERE table_name = 'documents' ORDER BY ordinal_position"
+        .fetch(&mut conn);
+    assert_eq!(
+        column_types,
+        vec![
+            ("id".to_string(), "integer".to_string()),
+            ("doc".to_string(), "jsonb".to_string()),
+            ("size".to_string(), "text".to_string()),
+        ]
+    );
+
+    let rows: Vec<(Json<serde_json::Value>, String)> =
+        "SELECT doc, size FROM documents LEFT JOIN t1 ON true".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![(
+            Json(serde_json::json!({"a": 1, "b": [true, null]})),
+            "large".to_string()
+        )]
+    );
+
+    // Enums can also be read into a Postgres enum
+    "CREATE TYPE size AS ENUM ('small', 'large')".execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE sized_documents (id int, size size) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+    let rows: Vec<(String, bool)> =
+        "SELECT size::text, size > 'small' FROM sized_documents LEFT JOIN t1 ON true"
+            .fetch(&mut conn);
+    assert_eq!(rows, vec![("large".to_string(), true)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_not_null_columns_over_nullable_fields(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    // DuckDB writes every parquet field as optional, even if none of its values are null
+    let parquet_path = tempdir.path().join("users.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT range AS id, 'user ' || range AS name FROM range(3)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+    let nullable: (bool,) = duckdb_conn.query_row(
+        &format!(
+            "SELECT bool_and(repetition_type = 'OPTIONAL') FROM parquet_schema('{}') WHERE name IN ('id', 'name')",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+        |row| Ok((row.get(0)?,)),
+    )?;
+    assert_eq!(nullable, (true,));
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE users (id bigint NOT NULL, name text NOT NULL) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+    "CREATE TABLE t1 (a int); INSERT INTO t1 VALUES (1)".execute(&mut conn);
+
+    let expected = vec![
+        (0, "user 0".to_string()),
+        (1, "user 1".to_string()),
+        (2, "user 2".to_string()),
+    ];
+
+    let rows: Vec<(i64, String)> = "SELECT id, name FROM users ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, expected);
+
+    let rows: Vec<(i64, String)> =
+        "SELECT id, name FROM users LEFT JOIN t1 ON true ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, expected);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_float_nan_and_infinity(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("floats.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT * FROM (VALUES (1, 'nan'::double, 'nan'::float), (2, 'inf'::double, 'inf'::float), (3, '-inf'::double, '-inf'::float), (4, 1.5::double, 1.5::float)) t(id, d, f)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE floats (id int, d float8, f float8, d4 float4) SERVER parquet_server OPTIONS (files '{}', select 'id, d, f, d AS d4')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i32, f64, f64, f32)> =
+        "SELECT id, d, f, d4 FROM floats ORDER BY id".fetch(&mut conn);
+    assert!(rows[0].1.is_nan() && rows[0].2.is_nan() && rows[0].3.is_nan());
+    assert_eq!(
+        rows[1..],
+        [
+            (2, f64::INFINITY, f64::INFINITY, f32::INFINITY),
+            (3, f64::NEG_INFINITY, f64::NEG_INFINITY, f32::NEG_INFINITY),
+            (4, 1.5, 1.5, 1.5),
+        ]
+    );
+
+    // NaN and the infinities are also ordinary values in Postgres filters
+    let ids: Vec<(i32,)> =
+        "SELECT id FROM floats WHERE d = 'NaN'::float8 OR d = '-Infinity'::float8 ORDER BY id"
+            .fetch(&mut conn);
+    assert_eq!(ids, vec![(1,), (3,)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_check_schema_drift(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("contract.parquet");
+    let write_file = |select: &str| {
+        duckdb_conn.execute(
+            &format!(
+                "COPY ({select}) TO '{}' (FORMAT PARQUET)",
+                parquet_path.to_str().unwrap()
+            ),
+            [],
+        )
+    };
+
+    write_file("SELECT 1::int AS id, 'a' AS name, 1.5::double AS amount")?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE contract (id int, name text, amount float8) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let drift: Vec<(String, String, Option<String>, String)> =
+        "SELECT * FROM check_schema_drift('contract'::regclass)".fetch(&mut conn);
+    assert!(drift.is_empty(), "{drift:?}");
+
+    // The amount column becomes text and the name column is dropped
+    write_file("SELECT 1::int AS id, 'unknown' AS amount")?;
+
+    let drift: Vec<(String, String, Option<String>, String)> =
+        "SELECT * FROM check_schema_drift('contract'::regclass) ORDER BY column_name"
+            .fetch(&mut conn);
+    assert_eq!(drift.len(), 2, "{drift:?}");
+
+    assert_eq!(drift[0].0, "amount");
+    assert_eq!(drift[0].1, "double precision");
+    assert_eq!(drift[0].2.as_deref(), Some("VARCHAR"));
+    assert!(drift[0].3.contains("incompatible"), "{drift:?}");
+
+    assert_eq!(
+        drift[1],
+        (
+            "name".to_string(),
+            "text".to_string(),
+            None,
+            "column is not in the files".to_string()
+        )
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_zoned_timestamptz_column(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    use datafusion::arrow::array::TimestampMicrosecondArray;
+    use datafusion::arrow::datatypes::TimeUnit;
+
+    // 2023-11-05 01:30:00.123456 happens twice in New York, this is the second time, in EST
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new(
+            "ts",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("America/New_York".into())),
+            false,
+        ),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2])),
+            Arc::new(
+                TimestampMicrosecondArray::from(vec![1_699_162_200_123_456, 1_719_849_600_500_000])
+                    .with_timezone("America/New_York"),
+            ),
+        ],
+    )?;
+
+    let parquet_path = tempdir.path().join("zoned.parquet");
+    let mut writer = ArrowWriter::try_new(File::create(&parquet_path)?, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE zoned (id int, ts timestamptz) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    // DuckDB returns timestamptz values in its own time zone, which makes them zoned in New York
+    "SET paradedb.duckdb_timezone = 'America/New_York'".execute(&mut conn);
+
+    let rows: Vec<(i32, time::OffsetDateTime)> =
+        "SELECT id, ts FROM zoned ORDER BY id".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (1, datetime!(2023-11-05 05:30:00.123456 UTC)),
+            (2, datetime!(2024-07-01 16:00:00.5 UTC)),
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_timestamp_resolutions(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("resolutions.parquet");
+
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT * FROM (VALUES
+                (1, '2023-06-27 12:34:56'::TIMESTAMP_S, '2023-06-27 12:34:56.789'::TIMESTAMP_MS, '2023-06-27 12:34:56.123456789'::TIMESTAMP_NS),
+                (2, '2023-06-27 12:34:57'::TIMESTAMP_S, '2023-06-27 12:34:57.001'::TIMESTAMP_MS, '2023-06-27 12:34:57.123456500'::TIMESTAMP_NS),
+                (3, '1969-12-31 23:59:59'::TIMESTAMP_S, '1969-12-31 23:59:59.999'::TIMESTAMP_MS, '2023-06-27 23:59:59.999999600'::TIMESTAMP_NS)
+            ) t(id, ts_s, ts_ms, ts_ns)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE resolutions (id int, ts_s timestamp, ts_ms timestamp, ts_ns timestamp, ts_ns_tz timestamptz) SERVER parquet_server OPTIONS (files '{}', select 'id, ts_s, ts_ms, ts_ns, ts_ns AS ts_ns_tz')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+    "SET TimeZone = 'UTC'".execute(&mut conn);
+
+    // Nanoseconds are rounded to the nearest microsecond, halves up, carrying into the next day
+    let rows: Vec<(
+        i32,
+        time::PrimitiveDateTime,
+        time::PrimitiveDateTime,
+        time::PrimitiveDateTime,
+        time::OffsetDateTime,
+    )> = "SELECT id, ts_s, ts_ms, ts_ns, ts_ns_tz FROM resolutions ORDER BY id".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (
+                1,
+                datetime!(2023-06-27 12:34:56),
+                datetime!(2023-06-27 12:34:56.789),
+                datetime!(2023-06-27 12:34:56.123457),
+                datetime!(2023-06-27 12:34:56.123457 UTC),
+            ),
+            (
+                2,
+                datetime!(2023-06-27 12:34:57),
+                datetime!(2023-06-27 12:34:57.001),
+                datetime!(2023-06-27 12:34:57.123457),
+                datetime!(2023-06-27 12:34:57.123457 UTC),
+            ),
+            (
+                3,
+                datetime!(1969-12-31 23:59:59),
+                datetime!(1969-12-31 23:59:59.999),
+                datetime!(2023-06-28 00:00:00),
+                datetime!(2023-06-28 00:00:00 UTC),
+            ),
+        ]
+    );
+
+    Ok(())
+}
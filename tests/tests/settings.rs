@@ -1,10 +1,18 @@
 mod fixtures;
 
-use crate::fixtures::conn;
-use crate::fixtures::db::Query;
+use crate::fixtures::arrow::{
+    primitive_create_foreign_data_wrapper, primitive_create_server, primitive_record_batch,
+    primitive_setup_fdw_s3_listing, setup_parquet_wrapper_and_server,
+};
+use crate::fixtures::db::{Db, Query};
+use crate::fixtures::notices::{capture_notices, notices_containing};
+use crate::fixtures::{conn, database, duckdb_conn, s3, tempdir, S3};
 use anyhow::Result;
+use datafusion::parquet::arrow::ArrowWriter;
 use rstest::*;
 use sqlx::PgConnection;
+use std::fs::File;
+use tempfile::TempDir;
 
 #[rstest]
 async fn test_duckdb_settings(mut conn: PgConnection) -> Result<()> {
@@ -25,3 +33,820 @@ async fn test_duckdb_extensions(mut conn: PgConnection) -> Result<()> {
 
     Ok(())
 }
+
+#[rstest]
+async fn test_max_glob_files(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+
+    for i in 0..3 {
+        let parquet_path = tempdir.path().join(format!("part{i}.parquet"));
+        let parquet_file = File::create(&parquet_path)?;
+
+        let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+        writer.write(&stored_batch)?;
+        writer.close()?;
+    }
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+
+    "SET paradedb.max_glob_files = 3".execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE glob_table () SERVER parquet_server OPTIONS (files '{}/*.parquet')",
+        tempdir.path().to_str().unwrap()
+    )
+    .execute(&mut conn);
+    let count: (i64,) = "SELECT COUNT(*) FROM glob_table".fetch_one(&mut conn);
+    assert_eq!(count.0, 3 * stored_batch.num_rows() as i64);
+
+    // Globs are counted when the view of a table is created, not on every scan
+    "SET paradedb.max_glob_files = 2".execute(&mut conn);
+    match format!(
+        "CREATE FOREIGN TABLE large_glob_table () SERVER parquet_server OPTIONS (files '{}/*.parquet')",
+        tempdir.path().to_str().unwrap()
+    )
+    .execute_result(&mut conn)
+    {
+        Ok(_) => panic!("glob matching more files than paradedb.max_glob_files should fail"),
+        Err(e) => assert!(e
+            .to_string()
+            .contains("exceeds paradedb.max_glob_files (2)")),
+    }
+
+    let count: (i64,) = "SELECT COUNT(*) FROM glob_table".fetch_one(&mut conn);
+    assert_eq!(count.0, 3 * stored_batch.num_rows() as i64);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_preserve_insertion_order(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE primitive () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    "SET paradedb.preserve_insertion_order = false".execute(&mut conn);
+
+    let rows: Vec<(i32, String)> =
+        "SELECT int32_col, utf8_col FROM primitive ORDER BY int32_col".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (-1, "There".to_string()),
+            (0, "World".to_string()),
+            (1, "Hello".to_string())
+        ]
+    );
+
+    let preserve_insertion_order: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'preserve_insertion_order'"
+            .fetch_one(&mut conn);
+    assert_eq!(preserve_insertion_order.0, Some("false".to_string()));
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_scan_parallelism(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+
+    for i in 0..16 {
+        let parquet_path = tempdir.path().join(format!("part{i}.parquet"));
+        let parquet_file = File::create(&parquet_path)?;
+
+        let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+        writer.write(&stored_batch)?;
+        writer.close()?;
+    }
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE parallel_table () SERVER parquet_server OPTIONS (files '{}/*.parquet')",
+        tempdir.path().to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let default_threads: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'threads'".fetch_one(&mut conn);
+
+    "SET paradedb.scan_parallelism = 4".execute(&mut conn);
+
+    let rows: Vec<(i32, i64)> =
+        "SELECT int32_col, COUNT(*) FROM parallel_table GROUP BY int32_col ORDER BY int32_col"
+            .fetch(&mut conn);
+    assert_eq!(rows, vec![(-1, 16), (0, 16), (1, 16)]);
+
+    // Scans through the FDW return every row as well
+    "CREATE TABLE t1 (a int)".execute(&mut conn);
+    let count: (i64,) =
+        "SELECT COUNT(*) FROM parallel_table LEFT JOIN t1 ON true".fetch_one(&mut conn);
+    assert_eq!(count.0, 16 * stored_batch.num_rows() as i64);
+
+    let threads: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'threads'".fetch_one(&mut conn);
+    assert_eq!(threads.0, Some("4".to_string()));
+
+    // Resetting the setting gives DuckDB back its default number of threads
+    "RESET paradedb.scan_parallelism".execute(&mut conn);
+    "SELECT COUNT(*) FROM parallel_table LEFT JOIN t1 ON true".fetch_one::<(i64,)>(&mut conn);
+    let threads: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'threads'".fetch_one(&mut conn);
+    assert_eq!(threads, default_threads);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_temp_directory(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    let spill_dir = tempdir.path().join("spill");
+    std::fs::create_dir(&spill_dir)?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE primitive () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let default_temp_directory: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'temp_directory'".fetch_one(&mut conn);
+
+    format!(
+        "SET paradedb.temp_directory = '{}'",
+        spill_dir.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(String, i64)> =
+        "SELECT utf8_col, MAX(int64_col) FROM primitive GROUP BY utf8_col ORDER BY utf8_col"
+            .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            ("Hello".to_string(), 1),
+            ("There".to_string(), -1),
+            ("World".to_string(), 0)
+        ]
+    );
+
+    let temp_directory: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'temp_directory'".fetch_one(&mut conn);
+    assert_eq!(
+        temp_directory.0,
+        Some(spill_dir.to_str().unwrap().to_string())
+    );
+
+    // Resetting the setting gives DuckDB back its default temp directory
+    "RESET paradedb.temp_directory".execute(&mut conn);
+    "SELECT COUNT(*) FROM primitive".fetch_one::<(i64,)>(&mut conn);
+    let temp_directory: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'temp_directory'".fetch_one(&mut conn);
+    assert_eq!(temp_directory, default_temp_directory);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_enable_object_cache(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE primitive () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    "SET paradedb.enable_object_cache = true".execute(&mut conn);
+
+    // Repeated scans read the cached metadata and return the same rows
+    for _ in 0..3 {
+        let rows: Vec<(i32, String)> =
+            "SELECT int32_col, utf8_col FROM primitive ORDER BY int32_col".fetch(&mut conn);
+        assert_eq!(
+            rows,
+            vec![
+                (-1, "There".to_string()),
+                (0, "World".to_string()),
+                (1, "Hello".to_string())
+            ]
+        );
+    }
+
+    let object_cache: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'enable_object_cache'"
+            .fetch_one(&mut conn);
+    assert_eq!(object_cache.0, Some("true".to_string()));
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_secret_directory(
+    #[future(awt)] s3: S3,
+    database: Db,
+    tempdir: TempDir,
+) -> Result<()> {
+    let s3_bucket = "test-secret-directory";
+    let s3_key = "test_arrow_types.parquet";
+    let s3_object_path = format!("s3://{s3_bucket}/{s3_key}");
+
+    let stored_batch = primitive_record_batch()?;
+    s3.create_bucket(s3_bucket).await?;
+    s3.put_batch(s3_bucket, s3_key, &stored_batch).await?;
+
+    let secret_directory = tempdir.path().join("secrets");
+    let set_secret_directory = format!(
+        "SET paradedb.secret_directory = '{}'",
+        secret_directory.to_str().unwrap()
+    );
+
+    let mut conn = database.connection().await;
+    "CREATE EXTENSION pg_analytics".execute(&mut conn);
+    set_secret_directory.execute(&mut conn);
+    primitive_setup_fdw_s3_listing(&s3.url, &s3_object_path, "primitive").execute(&mut conn);
+
+    let count: (i64,) = "SELECT COUNT(*) FROM primitive".fetch_one(&mut conn);
+    assert_eq!(count.0, 3);
+
+    // The secret is written to the directory, which only the Postgres OS user can read
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&secret_directory)?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+    let secret_files = || -> Result<Vec<(std::path::PathBuf, std::time::SystemTime)>> {
+        let mut files = std::fs::read_dir(&secret_directory)?
+            .map(|entry| {
+                let path = entry?.path();
+                let modified = std::fs::metadata(&path)?.modified()?;
+                Ok((path, modified))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        files.sort();
+        Ok(files)
+    };
+    let persisted = secret_files()?;
+    assert!(!persisted.is_empty());
+
+    // Later scans reuse the persisted secret instead of writing it again
+    let count: (i64,) = "SELECT COUNT(*) FROM primitive".fetch_one(&mut conn);
+    assert_eq!(count.0, 3);
+    assert_eq!(secret_files()?, persisted);
+
+    // A fresh connection reads the table through a server without a user mapping, which only
+    // works if the persisted secret is picked up
+    let mut fresh_conn = database.connection().await;
+    set_secret_directory.execute(&mut fresh_conn);
+    primitive_create_server("unmapped_server", "parquet_wrapper").execute(&mut fresh_conn);
+    format!(
+        "CREATE FOREIGN TABLE unmapped () SERVER unmapped_server OPTIONS (files '{s3_object_path}')"
+    )
+    .execute(&mut fresh_conn);
+
+    let count: (i64,) = "SELECT COUNT(*) FROM unmapped".fetch_one(&mut fresh_conn);
+    assert_eq!(count.0, 3);
+
+    // Dropping the table drops the secrets persisted for it
+    "DROP FOREIGN TABLE primitive".execute(&mut conn);
+    assert!(secret_files()?.is_empty());
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_force_download(#[future(awt)] s3: S3, mut conn: PgConnection) -> Result<()> {
+    let s3_bucket = "test-force-download";
+    let s3_key = "test_arrow_types.parquet";
+    let s3_object_path = format!("s3://{s3_bucket}/{s3_key}");
+
+    let stored_batch = primitive_record_batch()?;
+    s3.create_bucket(s3_bucket).await?;
+    s3.put_batch(s3_bucket, s3_key, &stored_batch).await?;
+
+    primitive_setup_fdw_s3_listing(&s3.url, &s3_object_path, "primitive").execute(&mut conn);
+
+    "SET paradedb.force_download = true".execute(&mut conn);
+
+    let rows: Vec<(i32, String)> =
+        "SELECT int32_col, utf8_col FROM primitive ORDER BY int32_col".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (-1, "There".to_string()),
+            (0, "World".to_string()),
+            (1, "Hello".to_string())
+        ]
+    );
+
+    let force_download: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'force_download'".fetch_one(&mut conn);
+    assert_eq!(force_download.0, Some("true".to_string()));
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_reset_connection(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE primitive () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let default_memory_limit: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'memory_limit'".fetch_one(&mut conn);
+
+    "SELECT duckdb_execute($$SET memory_limit='10GiB'$$)".execute(&mut conn);
+    let memory_limit: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'memory_limit'".fetch_one(&mut conn);
+    assert_eq!(memory_limit.0, Some("10.0 GiB".to_string()));
+
+    "SELECT reset_connection()".execute(&mut conn);
+
+    let memory_limit: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'memory_limit'".fetch_one(&mut conn);
+    assert_eq!(memory_limit, default_memory_limit);
+
+    // The view of the foreign table is recreated on the fresh connection
+    let count: (i64,) = "SELECT COUNT(*) FROM primitive".fetch_one(&mut conn);
+    assert_eq!(count.0, 3);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_log_duckdb_queries(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    capture_notices();
+
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE logged_primitive () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    "SELECT int32_col FROM logged_primitive WHERE int32_col = 1".fetch::<(i32,)>(&mut conn);
+    assert!(notices_containing("logged_primitive").is_empty());
+
+    "SET paradedb.log_duckdb_queries = true".execute(&mut conn);
+    "SET paradedb.log_duckdb_queries_level = 'notice'".execute(&mut conn);
+
+    let rows: Vec<(i32,)> =
+        "SELECT int32_col FROM logged_primitive WHERE int32_col = 1".fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    let notices = notices_containing("logged_primitive");
+    assert_eq!(notices.len(), 1, "{notices:?}");
+    assert!(
+        notices[0].starts_with("duckdb query: SELECT"),
+        "{notices:?}"
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_duckdb_query_timeout(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    capture_notices();
+
+    let parquet_path = tempdir.path().join("numbers.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT range AS n FROM range(100000)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE numbers () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    "SET paradedb.duckdb_query_timeout_ms = 100".execute(&mut conn);
+
+    // Compares every pair of rows, which takes far longer than the timeout
+    let err = "SELECT COUNT(*) FROM numbers a CROSS JOIN numbers b WHERE a.n + b.n = -1"
+        .fetch_result::<(i64,)>(&mut conn)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("exceeded paradedb.duckdb_query_timeout_ms (100 ms)"),
+        "{err}"
+    );
+    // The interrupted query is not run again through the FDW
+    let notices = notices_containing("duckdb_query_timeout_ms (100 ms)");
+    assert!(notices.is_empty(), "{notices:?}");
+
+    // Queries that finish in time are unaffected
+    let count: (i64,) = "SELECT COUNT(*) FROM numbers".fetch_one(&mut conn);
+    assert_eq!(count, (100000,));
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_duckdb_timezone(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("events.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT TIMESTAMPTZ '2024-01-02 12:00:00+00' AS ts) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE events (ts timestamptz) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+    "SET TimeZone = 'UTC'".execute(&mut conn);
+
+    let default_timezone: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'TimeZone'".fetch_one(&mut conn);
+
+    "SET paradedb.duckdb_timezone = 'Asia/Tokyo'".execute(&mut conn);
+    let rows: Vec<(i32, String)> =
+        "SELECT EXTRACT(hour FROM ts)::int, ts::text FROM events".fetch(&mut conn);
+    assert_eq!(rows, vec![(21, "2024-01-02 21:00:00+09".to_string())]);
+
+    let timezone: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'TimeZone'".fetch_one(&mut conn);
+    assert_eq!(timezone.0, Some("Asia/Tokyo".to_string()));
+
+    "SET paradedb.duckdb_timezone = 'UTC'".execute(&mut conn);
+    let rows: Vec<(i32, String)> =
+        "SELECT EXTRACT(hour FROM ts)::int, ts::text FROM events".fetch(&mut conn);
+    assert_eq!(rows, vec![(12, "2024-01-02 12:00:00+00".to_string())]);
+
+    // Resetting the setting gives DuckDB back its default time zone
+    "RESET paradedb.duckdb_timezone".execute(&mut conn);
+    "SELECT COUNT(*) FROM events".fetch_one::<(i64,)>(&mut conn);
+    let timezone: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'TimeZone'".fetch_one(&mut conn);
+    assert_eq!(timezone, default_timezone);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_autoinstall_extensions(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    primitive_create_foreign_data_wrapper("avro_wrapper", "avro_fdw_handler", "avro_fdw_validator")
+        .execute(&mut conn);
+    primitive_create_server("avro_server", "avro_wrapper").execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE users () SERVER avro_server OPTIONS (files '{}/users.avro')",
+        tempdir.path().to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    // The extension directory is empty, so the avro extension can only be found by installing it
+    format!(
+        "SET paradedb.extension_directory = '{}'",
+        tempdir.path().to_str().unwrap()
+    )
+    .execute(&mut conn);
+    "SET paradedb.autoinstall_extensions = false".execute(&mut conn);
+
+    let err = "SELECT * FROM users"
+        .fetch_result::<(i64,)>(&mut conn)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("the DuckDB avro extension could not be loaded, install it into paradedb.extension_directory or enable paradedb.autoinstall_extensions"),
+        "{err}"
+    );
+
+    // Resetting the setting gives DuckDB back its default extension directory
+    "RESET paradedb.extension_directory".execute(&mut conn);
+    let _ = "SELECT * FROM users".execute_result(&mut conn);
+    let extension_directory: (Option<String>,) =
+        "SELECT value FROM duckdb_settings() WHERE name = 'extension_directory'"
+            .fetch_one(&mut conn);
+    assert_ne!(
+        extension_directory.0,
+        Some(tempdir.path().to_str().unwrap().to_string())
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_allowed_extensions(mut conn: PgConnection) -> Result<()> {
+    match "SELECT load_extension('avro', 'community')".execute_result(&mut conn) {
+        Ok(_) => panic!("extensions that are not allowed should not be loaded"),
+        Err(e) => assert!(e
+            .to_string()
+            .contains("the DuckDB avro extension is not in paradedb.allowed_extensions")),
+    }
+
+    "SET paradedb.allowed_extensions = 'fts, avro'".execute(&mut conn);
+    "SELECT load_extension('avro', 'community')".execute(&mut conn);
+
+    let loaded: (Option<bool>,) =
+        "SELECT loaded FROM duckdb_extensions() WHERE extension_name = 'avro'".fetch_one(&mut conn);
+    assert_eq!(loaded, (Some(true),));
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_max_result_rows(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    capture_notices();
+
+    let parquet_path = tempdir.path().join("numbers.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT range AS n FROM range(100)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE numbers () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+    "CREATE TABLE t1 (a int); INSERT INTO t1 VALUES (1)".execute(&mut conn);
+
+    "SET paradedb.max_result_rows = 10".execute(&mut conn);
+
+    // Scanned through the foreign data wrapper
+    let err = "SELECT n FROM numbers LEFT JOIN t1 ON true"
+        .fetch_result::<(i64,)>(&mut conn)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("more than paradedb.max_result_rows (10) rows"),
+        "{err}"
+    );
+
+    // Pushed down to DuckDB
+    let err = "SELECT n FROM numbers"
+        .fetch_result::<(i64,)>(&mut conn)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("more than paradedb.max_result_rows (10) rows"),
+        "{err}"
+    );
+
+    // Results within the limit are unaffected
+    let rows: Vec<(i64,)> = "SELECT n FROM numbers WHERE n < 10 ORDER BY n".fetch(&mut conn);
+    assert_eq!(rows.len(), 10);
+    let count: (i64,) = "SELECT COUNT(*) FROM numbers".fetch_one(&mut conn);
+    assert_eq!(count, (100,));
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_warn_decimal_precision_loss(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    capture_notices();
+
+    let parquet_path = tempdir.path().join("precise_decimals.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT 1234567890.1234567890::decimal(20, 10) AS precise, 1.5::decimal(4, 1) AS short) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE precise_decimals (precise float8, short float8) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(f64, f64)> = "SELECT precise, short FROM precise_decimals".fetch(&mut conn);
+    assert_eq!(rows, vec![(1234567890.1234567, 1.5)]);
+    assert!(notices_containing("decimal(20, 10)").is_empty());
+
+    "SET paradedb.warn_decimal_precision_loss = true".execute(&mut conn);
+
+    let rows: Vec<(f64, f64)> = "SELECT precise, short FROM precise_decimals".fetch(&mut conn);
+    assert_eq!(rows, vec![(1234567890.1234567, 1.5)]);
+
+    let notices = notices_containing("decimal(20, 10)");
+    assert_eq!(notices.len(), 1, "{notices:?}");
+    assert!(
+        notices[0].contains("column \"precise\"") && notices[0].contains("double precision"),
+        "{notices:?}"
+    );
+    assert!(notices_containing("column \"short\"").is_empty());
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_timestamps_adjusted_to_utc(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    use datafusion::arrow::array::TimestampMicrosecondArray;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use std::sync::Arc;
+    use time::macros::datetime;
+
+    // The writer marks timestamps with a time zone as adjusted to UTC and the others as not
+    let schema = Arc::new(Schema::new(vec![
+        Field::new(
+            "local_ts",
+            DataType::Timestamp(TimeUnit::Microsecond, None),
+            false,
+        ),
+        Field::new(
+            "utc_ts",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            false,
+        ),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(TimestampMicrosecondArray::from(vec![1_704_196_800_000_000])),
+            Arc::new(
+                TimestampMicrosecondArray::from(vec![1_704_196_800_000_000]).with_timezone("UTC"),
+            ),
+        ],
+    )?;
+
+    let parquet_path = tempdir.path().join("flags.parquet");
+    let mut writer = ArrowWriter::try_new(File::create(&parquet_path)?, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    "SET TimeZone = 'America/New_York'".execute(&mut conn);
+
+    let column_types = |table: &str| {
+        format!("SELECT column_name::text, data_type::text FROM information_schema.columns WHERE table_name = '{table}' ORDER BY ordinal_position")
+    };
+    let create_table = |table: &str, columns: &str| {
+        format!(
+            "CREATE FOREIGN TABLE {table} ({columns}) SERVER parquet_server OPTIONS (files '{}')",
+            parquet_path.to_str().unwrap()
+        )
+    };
+
+    create_table("flags", "").execute(&mut conn);
+    let types: Vec<(String, String)> = column_types("flags").fetch(&mut conn);
+    assert_eq!(
+        types,
+        vec![
+            (
+                "local_ts".to_string(),
+                "timestamp without time zone".to_string()
+            ),
+            ("utc_ts".to_string(), "timestamp with time zone".to_string()),
+        ]
+    );
+
+    // Wall clock times are read into timestamptz in the session's time zone
+    create_table("flags_tz", "local_ts timestamptz, utc_ts timestamptz").execute(&mut conn);
+    let rows: Vec<(time::OffsetDateTime, time::OffsetDateTime)> =
+        "SELECT local_ts, utc_ts FROM flags_tz".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![(
+            datetime!(2024-01-02 17:00:00 UTC),
+            datetime!(2024-01-02 12:00:00 UTC)
+        )]
+    );
+
+    "SET paradedb.timestamps_adjusted_to_utc = true".execute(&mut conn);
+
+    let rows: Vec<(time::OffsetDateTime, time::OffsetDateTime)> =
+        "SELECT local_ts, utc_ts FROM flags_tz".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![(
+            datetime!(2024-01-02 12:00:00 UTC),
+            datetime!(2024-01-02 12:00:00 UTC)
+        )]
+    );
+
+    create_table("flags_utc", "").execute(&mut conn);
+    let types: Vec<(String, String)> = column_types("flags_utc").fetch(&mut conn);
+    assert_eq!(
+        types,
+        vec![
+            (
+                "local_ts".to_string(),
+                "timestamp with time zone".to_string()
+            ),
+            ("utc_ts".to_string(), "timestamp with time zone".to_string()),
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_type_overrides(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("amounts.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT * FROM (VALUES (1, 12345.67::decimal(20, 4)), (2, NULL)) t(id, amount)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE amounts (id int, amount text) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    // Decimals can't be read as text by default
+    assert!("SELECT id, amount FROM amounts"
+        .fetch_result::<(i32, Option<String>)>(&mut conn)
+        .is_err());
+
+    "SET paradedb.type_overrides = 'int64:varchar, decimal:text'".execute(&mut conn);
+    let rows: Vec<(i32, Option<String>)> =
+        "SELECT id, amount FROM amounts ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, vec![(1, Some("12345.6700".to_string())), (2, None)]);
+
+    "SET paradedb.type_overrides = 'decimal:int4'".execute(&mut conn);
+    match "SELECT id, amount FROM amounts".fetch_result::<(i32, Option<String>)>(&mut conn) {
+        Ok(_) => panic!("overrides to types other than text should fail"),
+        Err(err) => assert!(
+            err.to_string()
+                .contains("can only read values as text, varchar or bpchar"),
+            "{err}"
+        ),
+    }
+
+    Ok(())
+}
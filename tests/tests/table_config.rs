@@ -18,17 +18,19 @@
 mod fixtures;
 
 use crate::fixtures::arrow::{
-    primitive_record_batch, primitive_setup_fdw_local_file_listing, record_batch_with_casing,
-    reserved_column_record_batch, setup_local_file_listing_with_casing,
-    setup_parquet_wrapper_and_server,
+    primitive_create_foreign_data_wrapper, primitive_create_server, primitive_record_batch,
+    primitive_setup_fdw_local_file_listing, record_batch_with_casing, reserved_column_record_batch,
+    setup_local_file_listing_with_casing, setup_parquet_wrapper_and_server,
 };
 use crate::fixtures::db::Query;
-use crate::fixtures::{conn, tempdir};
+use crate::fixtures::{conn, duckdb_conn, tempdir};
 use anyhow::Result;
 use datafusion::parquet::arrow::ArrowWriter;
 use rstest::*;
+use sqlx::types::BigDecimal;
 use sqlx::PgConnection;
 use std::fs::File;
+use std::str::FromStr;
 use tempfile::TempDir;
 
 #[rstest]
@@ -85,7 +87,7 @@ async fn test_reserved_table_name(mut conn: PgConnection, tempdir: TempDir) -> R
             panic!("should have failed to create table with reserved name")
         }
         Err(e) => {
-            assert_eq!(e.to_string(), "error returned from database: Table name 'duckdb_types' is not allowed because it is reserved by DuckDB")
+            assert_eq!(e.to_string(), "error returned from database: Table name 'duckdb_types' is not allowed because it is reserved by DuckDB, see https://duckdb.org/docs/guides/meta/duckdb_environment#meta-table-functions")
         }
     }
 
@@ -136,6 +138,26 @@ async fn test_invalid_file(mut conn: PgConnection) -> Result<()> {
     Ok(())
 }
 
+#[rstest]
+async fn test_compressed_parquet_file(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet.gz");
+
+    match primitive_setup_fdw_local_file_listing(parquet_path.to_str().unwrap(), "primitive")
+        .execute_result(&mut conn)
+    {
+        Ok(_) => panic!("should have failed to create table over a compressed parquet file"),
+        Err(e) => assert_eq!(
+            e.to_string(),
+            format!(
+                "error returned from database: invalid value for the files option: {} is compressed with .gz, but parquet files are compressed internally and can't be read from a compressed file, decompress it first, see https://docs.paradedb.com/integrations/formats/parquet",
+                parquet_path.to_str().unwrap()
+            )
+        ),
+    }
+
+    Ok(())
+}
+
 #[rstest]
 async fn test_recreated_view(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
     let stored_batch = primitive_record_batch()?;
@@ -151,7 +173,7 @@ async fn test_recreated_view(mut conn: PgConnection, tempdir: TempDir) -> Result
 
     "DROP FOREIGN TABLE primitive".execute(&mut conn);
     format!(
-        "CREATE FOREIGN TABLE primitive (id INT) SERVER parquet_server OPTIONS (files '{}')",
+        "CREATE FOREIGN TABLE primitive (int32_col INT) SERVER parquet_server OPTIONS (files '{}')",
         parquet_path.to_str().unwrap()
     )
     .execute(&mut conn);
@@ -159,6 +181,33 @@ async fn test_recreated_view(mut conn: PgConnection, tempdir: TempDir) -> Result
     Ok(())
 }
 
+#[rstest]
+async fn test_declared_column_not_in_file(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+
+    match format!(
+        "CREATE FOREIGN TABLE primitive (int32_col INT, missing_col TEXT) SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute_result(&mut conn)
+    {
+        Ok(_) => panic!("declaring a column that is not in the file should fail"),
+        Err(e) => assert!(e
+            .to_string()
+            .contains("declares columns that are not in its files: \"missing_col\"")),
+    }
+
+    Ok(())
+}
+
 #[rstest]
 async fn test_preserve_casing(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
     let stored_batch = record_batch_with_casing()?;
@@ -276,3 +325,618 @@ async fn test_configure_columns(mut conn: PgConnection, tempdir: TempDir) -> Res
 
     Ok(())
 }
+
+#[rstest]
+async fn test_column_types_override(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+
+    format!(
+        r#"CREATE FOREIGN TABLE column_types_table () SERVER parquet_server OPTIONS (files '{}', column_types '{{"int32_col": "BIGINT", "boolean_col": "VARCHAR"}}')"#,
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let column_types: Vec<(String, String)> = r#"
+        SELECT column_name::text, data_type::text FROM information_schema.columns
+        WHERE table_name = 'column_types_table' AND column_name IN ('int32_col', 'boolean_col')
+        ORDER BY column_name
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        column_types,
+        vec![
+            ("boolean_col".to_string(), "character varying".to_string()),
+            ("int32_col".to_string(), "bigint".to_string())
+        ]
+    );
+
+    let rows: Vec<(i64, String)> =
+        "SELECT int32_col, boolean_col FROM column_types_table ORDER BY int32_col".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (-1, "true".to_string()),
+            (0, "false".to_string()),
+            (1, "true".to_string())
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_files_from_manifest(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let mut parquet_paths = vec![];
+
+    for file_name in ["part1.parquet", "part2.parquet"] {
+        let parquet_path = tempdir.path().join(file_name);
+        let parquet_file = File::create(&parquet_path)?;
+
+        let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+        writer.write(&stored_batch)?;
+        writer.close()?;
+
+        parquet_paths.push(parquet_path.to_str().unwrap().to_string());
+    }
+
+    let manifest_path = tempdir.path().join("manifest.txt");
+    std::fs::write(
+        &manifest_path,
+        format!("{}\n\n{}\n", parquet_paths[0], parquet_paths[1]),
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+
+    format!(
+        "CREATE FOREIGN TABLE manifest_table () SERVER parquet_server OPTIONS (files_from '{}')",
+        manifest_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let count: (i64,) = "SELECT COUNT(*) FROM manifest_table".fetch_one(&mut conn);
+    assert_eq!(count.0, 2 * stored_batch.num_rows() as i64);
+
+    let rows: Vec<(i32,)> =
+        "SELECT int32_col FROM manifest_table ORDER BY int32_col".fetch(&mut conn);
+    assert_eq!(rows, vec![(-1,), (-1,), (0,), (0,), (1,), (1,)]);
+
+    let result = format!(
+        "CREATE FOREIGN TABLE both_files_table () SERVER parquet_server OPTIONS (files '{}', files_from '{}')",
+        parquet_paths[0],
+        manifest_path.to_str().unwrap()
+    )
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_column_mapping_position(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let csv_path = tempdir.path().join("people.csv");
+    std::fs::write(&csv_path, "1,Alice\n2,Bob\n")?;
+
+    primitive_create_foreign_data_wrapper("csv_wrapper", "csv_fdw_handler", "csv_fdw_validator")
+        .execute(&mut conn);
+    primitive_create_server("csv_server", "csv_wrapper").execute(&mut conn);
+
+    // With name mapping, the headerless file's columns are named column0 and column1
+    let result = format!(
+        "CREATE FOREIGN TABLE people (id INT, name TEXT) SERVER csv_server OPTIONS (files '{}', header 'false', column_mapping 'name')",
+        csv_path.to_str().unwrap()
+    )
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    format!(
+        "CREATE FOREIGN TABLE people (id INT, name TEXT) SERVER csv_server OPTIONS (files '{}', header 'false', column_mapping 'position')",
+        csv_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i32, String)> = "SELECT id, name FROM people ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, vec![(1, "Alice".to_string()), (2, "Bob".to_string())]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_csv_headerless(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let csv_path = tempdir.path().join("people.csv");
+    std::fs::write(&csv_path, "1,Alice,30\n2,Bob,25\n")?;
+
+    primitive_create_foreign_data_wrapper("csv_wrapper", "csv_fdw_handler", "csv_fdw_validator")
+        .execute(&mut conn);
+    primitive_create_server("csv_server", "csv_wrapper").execute(&mut conn);
+
+    // The declared columns are read by position, regardless of their names
+    format!(
+        "CREATE FOREIGN TABLE people (person_id INT, full_name TEXT, age INT) SERVER csv_server OPTIONS (files '{}', header 'false')",
+        csv_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i32, String, i32)> =
+        "SELECT person_id, full_name, age FROM people ORDER BY person_id".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![(1, "Alice".to_string(), 30), (2, "Bob".to_string(), 25)]
+    );
+
+    let rows: Vec<(String,)> = "SELECT full_name FROM people WHERE age > 26".fetch(&mut conn);
+    assert_eq!(rows, vec![("Alice".to_string(),)]);
+
+    let err = format!(
+        "CREATE FOREIGN TABLE too_few (person_id INT, full_name TEXT) SERVER csv_server OPTIONS (files '{}', header 'false')",
+        csv_path.to_str().unwrap()
+    )
+    .execute_result(&mut conn)
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("the CSV files have 3 columns but the table declares 2"),
+        "{err}"
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_parquet_names(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+
+    // Columns past the given names keep the names from the file
+    format!(
+        "CREATE FOREIGN TABLE renamed () SERVER parquet_server OPTIONS (files '{}', names 'flag, tiny')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(bool, i16, i16)> = "SELECT flag, tiny, int16_col FROM renamed".fetch(&mut conn);
+    assert_eq!(rows, vec![(true, 1, 1), (true, -1, -1), (false, 0, 0)]);
+
+    // Positional mapping takes the names from the declared columns
+    format!(
+        "CREATE FOREIGN TABLE positional (flag BOOLEAN, tiny SMALLINT) SERVER parquet_server OPTIONS (files '{}', column_mapping 'position')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(bool, i16)> = "SELECT flag, tiny FROM positional".fetch(&mut conn);
+    assert_eq!(rows, vec![(true, 1), (true, -1), (false, 0)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_parquet_ignore_errors(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+
+    for name in ["b_good.parquet", "c_good.parquet"] {
+        let parquet_file = File::create(tempdir.path().join(name))?;
+        let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+        writer.write(&stored_batch)?;
+        writer.close()?;
+    }
+    std::fs::write(tempdir.path().join("a_bad.parquet"), b"not a parquet file")?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+
+    // Without ignore_errors, the corrupt file fails the table
+    let result = format!(
+        "CREATE FOREIGN TABLE strict_table () SERVER parquet_server OPTIONS (files '{}/*.parquet')",
+        tempdir.path().to_str().unwrap()
+    )
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    format!(
+        "CREATE FOREIGN TABLE resilient_table () SERVER parquet_server OPTIONS (files '{}/*.parquet', ignore_errors 'true')",
+        tempdir.path().to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let count: (i64,) = "SELECT COUNT(*) FROM resilient_table".fetch_one(&mut conn);
+    assert_eq!(count.0, 2 * stored_batch.num_rows() as i64);
+
+    let rows: Vec<(i32,)> =
+        "SELECT int32_col FROM resilient_table ORDER BY int32_col".fetch(&mut conn);
+    assert_eq!(rows, vec![(-1,), (-1,), (0,), (0,), (1,), (1,)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_csv_column_types(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let csv_path = tempdir.path().join("addresses.csv");
+    std::fs::write(&csv_path, "id,zip\n1,02134\n2,94105\n")?;
+
+    primitive_create_foreign_data_wrapper("csv_wrapper", "csv_fdw_handler", "csv_fdw_validator")
+        .execute(&mut conn);
+    primitive_create_server("csv_server", "csv_wrapper").execute(&mut conn);
+
+    // Only zip is overridden, id keeps its detected type
+    format!(
+        r#"CREATE FOREIGN TABLE addresses () SERVER csv_server OPTIONS (files '{}', column_types '{{"zip": "VARCHAR"}}')"#,
+        csv_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let column_types: Vec<(String, String)> = "SELECT column_name::text, data_type::text FROM information_schema.columns WHERE table_name = 'addresses' ORDER BY ordinal_position"
+        .fetch(&mut conn);
+    assert_eq!(
+        column_types,
+        vec![
+            ("id".to_string(), "bigint".to_string()),
+            ("zip".to_string(), "character varying".to_string())
+        ]
+    );
+
+    let rows: Vec<(i64, String)> = "SELECT id, zip FROM addresses ORDER BY id".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![(1, "02134".to_string()), (2, "94105".to_string())]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_csv_thousands(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let csv_path = tempdir.path().join("european_amounts.csv");
+    std::fs::write(&csv_path, "id;amount\n1;1.234,56\n2;12.345.678,9\n")?;
+
+    primitive_create_foreign_data_wrapper("csv_wrapper", "csv_fdw_handler", "csv_fdw_validator")
+        .execute(&mut conn);
+    primitive_create_server("csv_server", "csv_wrapper").execute(&mut conn);
+
+    format!(
+        r#"CREATE FOREIGN TABLE european_amounts () SERVER csv_server OPTIONS (files '{}', delim ';', decimal_separator ',', thousands '.', column_types '{{"amount": "DECIMAL(12, 2)"}}')"#,
+        csv_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i64, BigDecimal)> =
+        "SELECT id, amount FROM european_amounts ORDER BY id".fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (1, BigDecimal::from_str("1234.56").unwrap()),
+            (2, BigDecimal::from_str("12345678.90").unwrap())
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_parquet_lowercase_names(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("users.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT 1 AS \"UserId\", 'Ada' AS \"FullName\") TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+
+    // preserve_casing keeps the names DuckDB reports, which are lowercased by lowercase_names
+    format!(
+        "CREATE FOREIGN TABLE users () SERVER parquet_server OPTIONS (files '{}', lowercase_names 'true', preserve_casing 'true')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let column_names: Vec<(String,)> = "SELECT column_name::text FROM information_schema.columns WHERE table_name = 'users' ORDER BY ordinal_position"
+        .fetch(&mut conn);
+    assert_eq!(
+        column_names,
+        vec![("userid".to_string(),), ("fullname".to_string(),)]
+    );
+
+    let rows: Vec<(i32, String)> = "SELECT userid, fullname FROM users".fetch(&mut conn);
+    assert_eq!(rows, vec![(1, "Ada".to_string())]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_parquet_map_by_field_id(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    // name was renamed to full_name in the second file, keeping its field id
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT 1 AS id, 'Ada' AS name) TO '{}' (FORMAT PARQUET, FIELD_IDS {{id: 1, name: 2}})",
+            tempdir.path().join("people_1.parquet").to_str().unwrap()
+        ),
+        [],
+    )?;
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT 2 AS id, 'Grace' AS full_name) TO '{}' (FORMAT PARQUET, FIELD_IDS {{id: 1, full_name: 2}})",
+            tempdir.path().join("people_2.parquet").to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE people (id int, full_name text) SERVER parquet_server OPTIONS (files '{}', map_by_field_id 'true')",
+        tempdir.path().join("people_*.parquet").to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i32, String)> = "SELECT id, full_name FROM people ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, vec![(1, "Ada".to_string()), (2, "Grace".to_string())]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_parquet_union_by_name_type_coercion(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT 1 AS id, 2147483647::INTEGER AS reading) TO '{}' (FORMAT PARQUET)",
+            tempdir.path().join("readings_1.parquet").to_str().unwrap()
+        ),
+        [],
+    )?;
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT 2 AS id, 8589934592::BIGINT AS reading) TO '{}' (FORMAT PARQUET)",
+            tempdir.path().join("readings_2.parquet").to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE readings () SERVER parquet_server OPTIONS (files '{}', union_by_name_type_coercion 'true')",
+        tempdir.path().join("readings_*.parquet").to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let reading_type: (String,) = "SELECT data_type::text FROM information_schema.columns WHERE table_name = 'readings' AND column_name = 'reading'"
+        .fetch_one(&mut conn);
+    assert_eq!(reading_type, ("bigint".to_string(),));
+
+    let rows: Vec<(i32, i64)> = "SELECT id, reading FROM readings ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, vec![(1, 2147483647), (2, 8589934592)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_parquet_row_groups(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("numbers.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT range AS n FROM range(10000)) TO '{}' (FORMAT PARQUET, ROW_GROUP_SIZE 2048)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    let mut statement = duckdb_conn.prepare(&format!(
+        "SELECT DISTINCT row_group_id, row_group_num_rows FROM parquet_metadata('{}') ORDER BY row_group_id",
+        parquet_path.to_str().unwrap()
+    ))?;
+    let row_group_sizes = statement
+        .query_map([], |row| row.get::<_, i64>(1))?
+        .collect::<Result<Vec<i64>, _>>()?;
+    assert!(row_group_sizes.len() >= 3);
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        "CREATE FOREIGN TABLE numbers () SERVER parquet_server OPTIONS (files '{}', row_groups '1-2')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let start = row_group_sizes[0];
+    let end = start + row_group_sizes[1] + row_group_sizes[2] - 1;
+    let (count, min, max): (i64, i64, i64) =
+        "SELECT COUNT(*), MIN(n), MAX(n) FROM numbers".fetch_one(&mut conn);
+    assert_eq!((count, min, max), (end - start + 1, start, end));
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_parquet_null_column_type(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("sparse.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT * FROM (VALUES (1, NULL::INTEGER, NULL::INTEGER), (2, NULL, NULL)) t(id, note, score)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+    format!(
+        r#"CREATE FOREIGN TABLE sparse () SERVER parquet_server OPTIONS (files '{}', null_column_type 'VARCHAR', column_types '{{"score": "DOUBLE"}}')"#,
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let column_types: Vec<(String, String)> = "SELECT column_name::text, data_type::text FROM information_schema.columns WHERE table_name = 'sparse' ORDER BY ordinal_position"
+        .fetch(&mut conn);
+    assert_eq!(
+        column_types,
+        vec![
+            ("id".to_string(), "integer".to_string()),
+            ("note".to_string(), "text".to_string()),
+            ("score".to_string(), "double precision".to_string()),
+        ]
+    );
+
+    let rows: Vec<(i32, Option<String>, Option<f64>)> =
+        "SELECT id, note, score FROM sparse ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, vec![(1, None, None), (2, None, None)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_disable_glob(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("data[1].parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT 1 AS id) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+
+    // Without disable_glob, [1] is a character class that matches data1.parquet
+    let result = format!(
+        "CREATE FOREIGN TABLE globbed () SERVER parquet_server OPTIONS (files '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    format!(
+        "CREATE FOREIGN TABLE literal () SERVER parquet_server OPTIONS (files '{}', disable_glob 'true')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i32,)> = "SELECT id FROM literal".fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_parquet_computed_columns(
+    mut conn: PgConnection,
+    tempdir: TempDir,
+    duckdb_conn: duckdb::Connection,
+) -> Result<()> {
+    let parquet_path = tempdir.path().join("orders.parquet");
+    duckdb_conn.execute(
+        &format!(
+            "COPY (SELECT * FROM (VALUES (1, 2.5, 4), (2, 10.0, 3)) t(id, price, quantity)) TO '{}' (FORMAT PARQUET)",
+            parquet_path.to_str().unwrap()
+        ),
+        [],
+    )?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+
+    format!(
+        r#"CREATE FOREIGN TABLE orders (id INT, price NUMERIC, quantity INT, total NUMERIC) SERVER parquet_server OPTIONS (files '{}', computed_columns '{{"total": "price * quantity"}}')"#,
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i32, f64)> = "SELECT id, total::float8 FROM orders ORDER BY id".fetch(&mut conn);
+    assert_eq!(rows, vec![(1, 10.0), (2, 30.0)]);
+
+    // Expressions that reference columns the files don't have fail the table
+    let result = format!(
+        r#"CREATE FOREIGN TABLE bad_orders () SERVER parquet_server OPTIONS (files '{}', computed_columns '{{"total": "price * qty"}}')"#,
+        parquet_path.to_str().unwrap()
+    )
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_misspelled_option(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+
+    let err = format!(
+        "CREATE FOREIGN TABLE misspelled () SERVER parquet_server OPTIONS (fils '{}')",
+        parquet_path.to_str().unwrap()
+    )
+    .execute_result(&mut conn)
+    .unwrap_err();
+
+    let message = err.to_string();
+    assert!(message.contains("invalid option: fils"), "{message}");
+    assert!(
+        message.contains("valid options are: binary_as_string"),
+        "{message}"
+    );
+    assert!(message.ends_with("did you mean files?"), "{message}");
+
+    Ok(())
+}
+
+#[rstest]
+async fn test_uppercase_option_keys(mut conn: PgConnection, tempdir: TempDir) -> Result<()> {
+    let stored_batch = primitive_record_batch()?;
+    let parquet_path = tempdir.path().join("test_arrow_types.parquet");
+    let parquet_file = File::create(&parquet_path)?;
+
+    let mut writer = ArrowWriter::try_new(parquet_file, stored_batch.schema(), None).unwrap();
+    writer.write(&stored_batch)?;
+    writer.close()?;
+
+    setup_parquet_wrapper_and_server().execute(&mut conn);
+
+    // Postgres folds unquoted option names to lowercase, so the keys are quoted to keep their case
+    format!(
+        r#"CREATE FOREIGN TABLE uppercase_keys () SERVER parquet_server OPTIONS ("Files" '{}', "FILE_ROW_NUMBER" 'true')"#,
+        parquet_path.to_str().unwrap()
+    )
+    .execute(&mut conn);
+
+    let rows: Vec<(i32, i64)> =
+        "SELECT int32_col, file_row_number FROM uppercase_keys ORDER BY file_row_number"
+            .fetch(&mut conn);
+    assert_eq!(rows, vec![(1, 0), (-1, 1), (0, 2)]);
+
+    Ok(())
+}